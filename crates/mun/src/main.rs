@@ -35,6 +35,12 @@ fn main() -> Result<(), failure::Error> {
                         .takes_value(true)
                         .help("optimize with possible levels 0-3"),
                 )
+                .arg(
+                    Arg::with_name("debug-info")
+                        .short("g")
+                        .long("debug-info")
+                        .help("emit the source locations of functions"),
+                )
                 .arg(
                     Arg::with_name("target")
                         .long("target")
@@ -164,6 +170,7 @@ fn compiler_options(matches: &ArgMatches) -> Result<mun_compiler::CompilerOption
                 .value_of("target")
                 .map_or_else(Target::host_target, Target::search)?,
             optimization_lvl,
+            emit_debug_info: matches.is_present("debug-info"),
             out_dir: None,
             display_color,
         },
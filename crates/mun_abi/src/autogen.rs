@@ -19,17 +19,17 @@ pub struct Guid {
 #[test]
 fn bindgen_test_layout_Guid() {
     assert_eq!(
-        ::std::mem::size_of::<Guid>(),
+        ::core::mem::size_of::<Guid>(),
         16usize,
         concat!("Size of: ", stringify!(Guid))
     );
     assert_eq!(
-        ::std::mem::align_of::<Guid>(),
+        ::core::mem::align_of::<Guid>(),
         1usize,
         concat!("Alignment of ", stringify!(Guid))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<Guid>())).b as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<Guid>())).b as *const _ as usize },
         0usize,
         concat!("Offset of field: ", stringify!(Guid), "::", stringify!(b))
     );
@@ -45,7 +45,7 @@ pub struct TypeInfo {
     #[doc = " Type GUID"]
     pub guid: Guid,
     #[doc = " Type name"]
-    pub name: *const ::std::os::raw::c_char,
+    pub name: *const cty::c_char,
     #[doc = " The exact size of the type in bits without any padding"]
     pub size_in_bits: u32,
     #[doc = " The alignment of the type"]
@@ -56,17 +56,17 @@ pub struct TypeInfo {
 #[test]
 fn bindgen_test_layout_TypeInfo() {
     assert_eq!(
-        ::std::mem::size_of::<TypeInfo>(),
+        ::core::mem::size_of::<TypeInfo>(),
         32usize,
         concat!("Size of: ", stringify!(TypeInfo))
     );
     assert_eq!(
-        ::std::mem::align_of::<TypeInfo>(),
+        ::core::mem::align_of::<TypeInfo>(),
         8usize,
         concat!("Alignment of ", stringify!(TypeInfo))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<TypeInfo>())).guid as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<TypeInfo>())).guid as *const _ as usize },
         0usize,
         concat!(
             "Offset of field: ",
@@ -76,7 +76,7 @@ fn bindgen_test_layout_TypeInfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<TypeInfo>())).name as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<TypeInfo>())).name as *const _ as usize },
         16usize,
         concat!(
             "Offset of field: ",
@@ -86,7 +86,7 @@ fn bindgen_test_layout_TypeInfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<TypeInfo>())).size_in_bits as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<TypeInfo>())).size_in_bits as *const _ as usize },
         24usize,
         concat!(
             "Offset of field: ",
@@ -96,7 +96,7 @@ fn bindgen_test_layout_TypeInfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<TypeInfo>())).alignment as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<TypeInfo>())).alignment as *const _ as usize },
         28usize,
         concat!(
             "Offset of field: ",
@@ -106,7 +106,7 @@ fn bindgen_test_layout_TypeInfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<TypeInfo>())).group as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<TypeInfo>())).group as *const _ as usize },
         29usize,
         concat!(
             "Offset of field: ",
@@ -132,17 +132,17 @@ pub struct FunctionSignature {
 #[test]
 fn bindgen_test_layout_FunctionSignature() {
     assert_eq!(
-        ::std::mem::size_of::<FunctionSignature>(),
+        ::core::mem::size_of::<FunctionSignature>(),
         24usize,
         concat!("Size of: ", stringify!(FunctionSignature))
     );
     assert_eq!(
-        ::std::mem::align_of::<FunctionSignature>(),
+        ::core::mem::align_of::<FunctionSignature>(),
         8usize,
         concat!("Alignment of ", stringify!(FunctionSignature))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<FunctionSignature>())).arg_types as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<FunctionSignature>())).arg_types as *const _ as usize },
         0usize,
         concat!(
             "Offset of field: ",
@@ -152,7 +152,7 @@ fn bindgen_test_layout_FunctionSignature() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<FunctionSignature>())).return_type as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<FunctionSignature>())).return_type as *const _ as usize },
         8usize,
         concat!(
             "Offset of field: ",
@@ -162,7 +162,9 @@ fn bindgen_test_layout_FunctionSignature() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<FunctionSignature>())).num_arg_types as *const _ as usize },
+        unsafe {
+            &(*(::core::ptr::null::<FunctionSignature>())).num_arg_types as *const _ as usize
+        },
         16usize,
         concat!(
             "Offset of field: ",
@@ -180,24 +182,24 @@ fn bindgen_test_layout_FunctionSignature() {
 #[derive(Clone, Debug)]
 pub struct FunctionPrototype {
     #[doc = " Function name"]
-    pub name: *const ::std::os::raw::c_char,
+    pub name: *const cty::c_char,
     #[doc = " The type signature of the function"]
     pub signature: FunctionSignature,
 }
 #[test]
 fn bindgen_test_layout_FunctionPrototype() {
     assert_eq!(
-        ::std::mem::size_of::<FunctionPrototype>(),
+        ::core::mem::size_of::<FunctionPrototype>(),
         32usize,
         concat!("Size of: ", stringify!(FunctionPrototype))
     );
     assert_eq!(
-        ::std::mem::align_of::<FunctionPrototype>(),
+        ::core::mem::align_of::<FunctionPrototype>(),
         8usize,
         concat!("Alignment of ", stringify!(FunctionPrototype))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<FunctionPrototype>())).name as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<FunctionPrototype>())).name as *const _ as usize },
         0usize,
         concat!(
             "Offset of field: ",
@@ -207,7 +209,7 @@ fn bindgen_test_layout_FunctionPrototype() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<FunctionPrototype>())).signature as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<FunctionPrototype>())).signature as *const _ as usize },
         8usize,
         concat!(
             "Offset of field: ",
@@ -229,22 +231,22 @@ pub struct FunctionDefinition {
     #[doc = " Function prototype"]
     pub prototype: FunctionPrototype,
     #[doc = " Function pointer"]
-    pub fn_ptr: *const ::std::os::raw::c_void,
+    pub fn_ptr: *const cty::c_void,
 }
 #[test]
 fn bindgen_test_layout_FunctionDefinition() {
     assert_eq!(
-        ::std::mem::size_of::<FunctionDefinition>(),
+        ::core::mem::size_of::<FunctionDefinition>(),
         40usize,
         concat!("Size of: ", stringify!(FunctionDefinition))
     );
     assert_eq!(
-        ::std::mem::align_of::<FunctionDefinition>(),
+        ::core::mem::align_of::<FunctionDefinition>(),
         8usize,
         concat!("Alignment of ", stringify!(FunctionDefinition))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<FunctionDefinition>())).prototype as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<FunctionDefinition>())).prototype as *const _ as usize },
         0usize,
         concat!(
             "Offset of field: ",
@@ -254,7 +256,7 @@ fn bindgen_test_layout_FunctionDefinition() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<FunctionDefinition>())).fn_ptr as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<FunctionDefinition>())).fn_ptr as *const _ as usize },
         32usize,
         concat!(
             "Offset of field: ",
@@ -271,30 +273,38 @@ fn bindgen_test_layout_FunctionDefinition() {
 #[derive(Clone, Debug)]
 pub struct StructInfo {
     #[doc = " Struct fields' names"]
-    pub field_names: *const *const ::std::os::raw::c_char,
+    pub field_names: *const *const cty::c_char,
     #[doc = " Struct fields' information"]
     pub field_types: *const *const TypeInfo,
     #[doc = " Struct fields' offsets"]
     pub field_offsets: *const u16,
+    #[doc = " Struct fields' default values, which may be null if none of the fields have a default"]
+    #[doc = " value. Null elements denote fields without a default value."]
+    pub field_defaults: *const *const u8,
+    #[doc = " Struct fields' documentation, which may be null if none of the fields are documented."]
+    #[doc = " Null elements denote fields without documentation."]
+    pub field_docs: *const *const cty::c_char,
     #[doc = " Number of fields"]
     pub num_fields: u16,
     #[doc = " Struct memory kind"]
     pub memory_kind: StructMemoryKind,
+    #[doc = " Magic number used to validate the location of the struct information"]
+    pub magic: u32,
 }
 #[test]
 fn bindgen_test_layout_StructInfo() {
     assert_eq!(
-        ::std::mem::size_of::<StructInfo>(),
-        32usize,
+        ::core::mem::size_of::<StructInfo>(),
+        48usize,
         concat!("Size of: ", stringify!(StructInfo))
     );
     assert_eq!(
-        ::std::mem::align_of::<StructInfo>(),
+        ::core::mem::align_of::<StructInfo>(),
         8usize,
         concat!("Alignment of ", stringify!(StructInfo))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<StructInfo>())).field_names as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<StructInfo>())).field_names as *const _ as usize },
         0usize,
         concat!(
             "Offset of field: ",
@@ -304,7 +314,7 @@ fn bindgen_test_layout_StructInfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<StructInfo>())).field_types as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<StructInfo>())).field_types as *const _ as usize },
         8usize,
         concat!(
             "Offset of field: ",
@@ -314,7 +324,7 @@ fn bindgen_test_layout_StructInfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<StructInfo>())).field_offsets as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<StructInfo>())).field_offsets as *const _ as usize },
         16usize,
         concat!(
             "Offset of field: ",
@@ -324,8 +334,28 @@ fn bindgen_test_layout_StructInfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<StructInfo>())).num_fields as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<StructInfo>())).field_defaults as *const _ as usize },
         24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(StructInfo),
+            "::",
+            stringify!(field_defaults)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::core::ptr::null::<StructInfo>())).field_docs as *const _ as usize },
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(StructInfo),
+            "::",
+            stringify!(field_docs)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::core::ptr::null::<StructInfo>())).num_fields as *const _ as usize },
+        40usize,
         concat!(
             "Offset of field: ",
             stringify!(StructInfo),
@@ -334,8 +364,8 @@ fn bindgen_test_layout_StructInfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<StructInfo>())).memory_kind as *const _ as usize },
-        26usize,
+        unsafe { &(*(::core::ptr::null::<StructInfo>())).memory_kind as *const _ as usize },
+        42usize,
         concat!(
             "Offset of field: ",
             stringify!(StructInfo),
@@ -343,6 +373,16 @@ fn bindgen_test_layout_StructInfo() {
             stringify!(memory_kind)
         )
     );
+    assert_eq!(
+        unsafe { &(*(::core::ptr::null::<StructInfo>())).magic as *const _ as usize },
+        44usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(StructInfo),
+            "::",
+            stringify!(magic)
+        )
+    );
 }
 #[doc = " Represents a module declaration."]
 #[doc = ""]
@@ -351,7 +391,7 @@ fn bindgen_test_layout_StructInfo() {
 #[derive(Debug)]
 pub struct ModuleInfo {
     #[doc = " Module path"]
-    pub path: *const ::std::os::raw::c_char,
+    pub path: *const cty::c_char,
     #[doc = " Module functions"]
     pub functions: *const FunctionDefinition,
     #[doc = " Number of module functions"]
@@ -364,17 +404,17 @@ pub struct ModuleInfo {
 #[test]
 fn bindgen_test_layout_ModuleInfo() {
     assert_eq!(
-        ::std::mem::size_of::<ModuleInfo>(),
+        ::core::mem::size_of::<ModuleInfo>(),
         40usize,
         concat!("Size of: ", stringify!(ModuleInfo))
     );
     assert_eq!(
-        ::std::mem::align_of::<ModuleInfo>(),
+        ::core::mem::align_of::<ModuleInfo>(),
         8usize,
         concat!("Alignment of ", stringify!(ModuleInfo))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<ModuleInfo>())).path as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<ModuleInfo>())).path as *const _ as usize },
         0usize,
         concat!(
             "Offset of field: ",
@@ -384,7 +424,7 @@ fn bindgen_test_layout_ModuleInfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<ModuleInfo>())).functions as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<ModuleInfo>())).functions as *const _ as usize },
         8usize,
         concat!(
             "Offset of field: ",
@@ -394,7 +434,7 @@ fn bindgen_test_layout_ModuleInfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<ModuleInfo>())).num_functions as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<ModuleInfo>())).num_functions as *const _ as usize },
         16usize,
         concat!(
             "Offset of field: ",
@@ -404,7 +444,7 @@ fn bindgen_test_layout_ModuleInfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<ModuleInfo>())).types as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<ModuleInfo>())).types as *const _ as usize },
         24usize,
         concat!(
             "Offset of field: ",
@@ -414,7 +454,7 @@ fn bindgen_test_layout_ModuleInfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<ModuleInfo>())).num_types as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<ModuleInfo>())).num_types as *const _ as usize },
         32usize,
         concat!(
             "Offset of field: ",
@@ -435,24 +475,24 @@ pub struct DispatchTable {
     #[doc = " Function signatures"]
     pub prototypes: *const FunctionPrototype,
     #[doc = " Function pointers"]
-    pub fn_ptrs: *mut *const ::std::os::raw::c_void,
+    pub fn_ptrs: *mut *const cty::c_void,
     #[doc = " Number of functions"]
     pub num_entries: u32,
 }
 #[test]
 fn bindgen_test_layout_DispatchTable() {
     assert_eq!(
-        ::std::mem::size_of::<DispatchTable>(),
+        ::core::mem::size_of::<DispatchTable>(),
         24usize,
         concat!("Size of: ", stringify!(DispatchTable))
     );
     assert_eq!(
-        ::std::mem::align_of::<DispatchTable>(),
+        ::core::mem::align_of::<DispatchTable>(),
         8usize,
         concat!("Alignment of ", stringify!(DispatchTable))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<DispatchTable>())).prototypes as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<DispatchTable>())).prototypes as *const _ as usize },
         0usize,
         concat!(
             "Offset of field: ",
@@ -462,7 +502,7 @@ fn bindgen_test_layout_DispatchTable() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<DispatchTable>())).fn_ptrs as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<DispatchTable>())).fn_ptrs as *const _ as usize },
         8usize,
         concat!(
             "Offset of field: ",
@@ -472,7 +512,7 @@ fn bindgen_test_layout_DispatchTable() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<DispatchTable>())).num_entries as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<DispatchTable>())).num_entries as *const _ as usize },
         16usize,
         concat!(
             "Offset of field: ",
@@ -493,24 +533,24 @@ pub struct AssemblyInfo {
     #[doc = " Dispatch table"]
     pub dispatch_table: DispatchTable,
     #[doc = " Paths to assembly dependencies"]
-    pub dependencies: *const *const ::std::os::raw::c_char,
+    pub dependencies: *const *const cty::c_char,
     #[doc = " Number of dependencies"]
     pub num_dependencies: u32,
 }
 #[test]
 fn bindgen_test_layout_AssemblyInfo() {
     assert_eq!(
-        ::std::mem::size_of::<AssemblyInfo>(),
+        ::core::mem::size_of::<AssemblyInfo>(),
         80usize,
         concat!("Size of: ", stringify!(AssemblyInfo))
     );
     assert_eq!(
-        ::std::mem::align_of::<AssemblyInfo>(),
+        ::core::mem::align_of::<AssemblyInfo>(),
         8usize,
         concat!("Alignment of ", stringify!(AssemblyInfo))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<AssemblyInfo>())).symbols as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<AssemblyInfo>())).symbols as *const _ as usize },
         0usize,
         concat!(
             "Offset of field: ",
@@ -520,7 +560,7 @@ fn bindgen_test_layout_AssemblyInfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<AssemblyInfo>())).dispatch_table as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<AssemblyInfo>())).dispatch_table as *const _ as usize },
         40usize,
         concat!(
             "Offset of field: ",
@@ -530,7 +570,7 @@ fn bindgen_test_layout_AssemblyInfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<AssemblyInfo>())).dependencies as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<AssemblyInfo>())).dependencies as *const _ as usize },
         64usize,
         concat!(
             "Offset of field: ",
@@ -540,7 +580,7 @@ fn bindgen_test_layout_AssemblyInfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<AssemblyInfo>())).num_dependencies as *const _ as usize },
+        unsafe { &(*(::core::ptr::null::<AssemblyInfo>())).num_dependencies as *const _ as usize },
         72usize,
         concat!(
             "Offset of field: ",
@@ -550,3 +590,166 @@ fn bindgen_test_layout_AssemblyInfo() {
         )
     );
 }
+#[doc = " Represents the source location of a function definition."]
+#[doc = ""]
+#[doc = " <div rustbindgen derive=\"Debug\"></div>"]
+#[repr(C)]
+#[derive(Debug)]
+pub struct FunctionSourceLocation {
+    #[doc = " Function name"]
+    pub name: *const cty::c_char,
+    #[doc = " Relative path of the source file"]
+    pub file: *const cty::c_char,
+    #[doc = " One-based line number of the function definition"]
+    pub line: u32,
+}
+#[test]
+fn bindgen_test_layout_FunctionSourceLocation() {
+    assert_eq!(
+        ::core::mem::size_of::<FunctionSourceLocation>(),
+        24usize,
+        concat!("Size of: ", stringify!(FunctionSourceLocation))
+    );
+    assert_eq!(
+        ::core::mem::align_of::<FunctionSourceLocation>(),
+        8usize,
+        concat!("Alignment of ", stringify!(FunctionSourceLocation))
+    );
+    assert_eq!(
+        unsafe { &(*(::core::ptr::null::<FunctionSourceLocation>())).name as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(FunctionSourceLocation),
+            "::",
+            stringify!(name)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::core::ptr::null::<FunctionSourceLocation>())).file as *const _ as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(FunctionSourceLocation),
+            "::",
+            stringify!(file)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::core::ptr::null::<FunctionSourceLocation>())).line as *const _ as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(FunctionSourceLocation),
+            "::",
+            stringify!(line)
+        )
+    );
+}
+#[doc = " Represents the optional debug information of an assembly."]
+#[doc = ""]
+#[doc = " Debug information is only emitted by the compiler when it is enabled."]
+#[doc = ""]
+#[doc = " <div rustbindgen derive=\"Debug\"></div>"]
+#[repr(C)]
+#[derive(Debug)]
+pub struct DebugInfo {
+    #[doc = " Source locations of functions"]
+    pub locations: *const FunctionSourceLocation,
+    #[doc = " Number of source locations"]
+    pub num_locations: u32,
+}
+#[test]
+fn bindgen_test_layout_DebugInfo() {
+    assert_eq!(
+        ::core::mem::size_of::<DebugInfo>(),
+        16usize,
+        concat!("Size of: ", stringify!(DebugInfo))
+    );
+    assert_eq!(
+        ::core::mem::align_of::<DebugInfo>(),
+        8usize,
+        concat!("Alignment of ", stringify!(DebugInfo))
+    );
+    assert_eq!(
+        unsafe { &(*(::core::ptr::null::<DebugInfo>())).locations as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(DebugInfo),
+            "::",
+            stringify!(locations)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::core::ptr::null::<DebugInfo>())).num_locations as *const _ as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(DebugInfo),
+            "::",
+            stringify!(num_locations)
+        )
+    );
+}
+#[doc = " Represents a type of opaque handles to host-owned resources, e.g. database connections."]
+#[doc = ""]
+#[doc = " A runtime creates a handle with `create` and attaches it to a garbage collected struct. When"]
+#[doc = " the struct is collected, the runtime passes the handle to `drop`. Handles can be dropped on"]
+#[doc = " any thread."]
+#[doc = ""]
+#[doc = " <div rustbindgen derive=\"Debug\"></div>"]
+#[repr(C)]
+#[derive(Debug)]
+pub struct HandleType {
+    #[doc = " Type name"]
+    pub name: *const cty::c_char,
+    #[doc = " Creates a handle from the host-provided argument, or returns null on failure"]
+    pub create:
+        ::core::option::Option<unsafe extern "C" fn(arg: *mut cty::c_void) -> *mut cty::c_void>,
+    #[doc = " Releases a handle that was returned by `create`"]
+    pub drop: ::core::option::Option<unsafe extern "C" fn(handle: *mut cty::c_void)>,
+}
+#[test]
+fn bindgen_test_layout_HandleType() {
+    assert_eq!(
+        ::core::mem::size_of::<HandleType>(),
+        24usize,
+        concat!("Size of: ", stringify!(HandleType))
+    );
+    assert_eq!(
+        ::core::mem::align_of::<HandleType>(),
+        8usize,
+        concat!("Alignment of ", stringify!(HandleType))
+    );
+    assert_eq!(
+        unsafe { &(*(::core::ptr::null::<HandleType>())).name as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(HandleType),
+            "::",
+            stringify!(name)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::core::ptr::null::<HandleType>())).create as *const _ as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(HandleType),
+            "::",
+            stringify!(create)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::core::ptr::null::<HandleType>())).drop as *const _ as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(HandleType),
+            "::",
+            stringify!(drop)
+        )
+    );
+}
@@ -1,27 +1,58 @@
 use crate::prelude::*;
 
-use std::convert::TryInto;
-use std::ffi::{c_void, CStr};
-use std::fmt::Formatter;
-use std::marker::{Send, Sync};
-use std::mem;
-use std::str;
-use std::{fmt, slice};
+use core::convert::TryInto;
+use core::ffi::c_void;
+use core::fmt::Formatter;
+use core::marker::{Send, Sync};
+use core::mem;
+use core::str;
+use core::{fmt, slice};
+use cty::c_char;
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String};
+
+/// Converts the null-terminated C string that `ptr` points to into a string slice.
+///
+/// # Safety
+///
+/// `ptr` must point to a valid, null-terminated, and UTF-8 encoded C string that outlives `'a`.
+unsafe fn str_from_c<'a>(ptr: *const c_char) -> &'a str {
+    let mut len = 0;
+    while *ptr.add(len) != 0 {
+        len += 1;
+    }
+    str::from_utf8_unchecked(slice::from_raw_parts(ptr.cast::<u8>(), len))
+}
 
 impl TypeInfo {
     /// Returns the type's name.
     pub fn name(&self) -> &str {
-        unsafe { str::from_utf8_unchecked(CStr::from_ptr(self.name).to_bytes()) }
+        unsafe { str_from_c(self.name) }
     }
 
     /// Retrieves the type's struct information, if available.
     pub fn as_struct(&self) -> Option<&StructInfo> {
         if self.group.is_struct() {
-            let ptr = (self as *const TypeInfo).cast::<u8>();
-            let ptr = ptr.wrapping_add(mem::size_of::<TypeInfo>());
-            let offset = ptr.align_offset(mem::align_of::<StructInfo>());
-            let ptr = ptr.wrapping_add(offset);
-            Some(unsafe { &*ptr.cast::<StructInfo>() })
+            // The `StructInfo` directly follows the `TypeInfo`, padded to its alignment
+            let alignment = mem::align_of::<StructInfo>();
+            let offset = (mem::size_of::<TypeInfo>() + alignment - 1) & !(alignment - 1);
+            let ptr = (self as *const TypeInfo).cast::<u8>().wrapping_add(offset);
+            debug_assert_eq!(
+                ptr.align_offset(alignment),
+                0,
+                "misaligned `StructInfo` of type `{}`",
+                self.name()
+            );
+
+            let struct_info = unsafe { &*ptr.cast::<StructInfo>() };
+            debug_assert_eq!(
+                struct_info.magic,
+                STRUCT_INFO_MAGIC,
+                "invalid `StructInfo` of type `{}`",
+                self.name()
+            );
+            Some(struct_info)
         } else {
             None
         }
@@ -36,7 +67,8 @@ impl TypeInfo {
 
     /// Returns the size of the type in bytes
     pub fn size_in_bytes(&self) -> usize {
-        ((self.size_in_bits + 7) / 8)
+        // Rounding up in `u64` cannot overflow, as `size_in_bits` is at most `u32::MAX`.
+        ((u64::from(self.size_in_bits) + 7) / 8)
             .try_into()
             .expect("cannot covert size in bytes to platform size")
     }
@@ -47,6 +79,110 @@ impl TypeInfo {
             .try_into()
             .expect("cannot convert alignment to platform size")
     }
+
+    /// Returns whether the type's memory layout follows the C ABI of the target platform. That is,
+    /// every field is placed at the first offset after the previous field that satisfies the
+    /// field's alignment, and the size of the type is rounded up to its largest field alignment.
+    ///
+    /// Fields of garbage collected struct types are laid out as pointers. Fundamental types always
+    /// have a C-compatible layout.
+    pub fn has_c_layout(&self) -> bool {
+        let struct_info = match self.as_struct() {
+            Some(struct_info) => struct_info,
+            None => return true,
+        };
+
+        let align_up =
+            |offset: usize, alignment: usize| (offset + alignment - 1) / alignment * alignment;
+
+        let mut offset = 0;
+        let mut alignment = 1;
+        for (field_type, field_offset) in struct_info
+            .field_types()
+            .iter()
+            .zip(struct_info.field_offsets().iter())
+        {
+            let (field_size, field_alignment) = match field_type.as_struct() {
+                Some(s) if s.memory_kind == StructMemoryKind::GC => (
+                    mem::size_of::<*const c_void>(),
+                    mem::align_of::<*const c_void>(),
+                ),
+                _ if !field_type.has_c_layout() => return false,
+                _ => (field_type.size_in_bytes(), field_type.alignment()),
+            };
+
+            offset = align_up(offset, field_alignment);
+            if offset != usize::from(*field_offset) {
+                return false;
+            }
+            offset += field_size;
+            alignment = alignment.max(field_alignment);
+        }
+
+        alignment == self.alignment() && align_up(offset, alignment) == self.size_in_bytes()
+    }
+
+    /// Returns whether the type is a result: a struct that holds either a success or an error
+    /// payload, as selected by its tag. A result with payloads of types `T` and `E` has the layout
+    /// of the C struct:
+    ///
+    /// ```c
+    /// struct Result {
+    ///     uint8_t tag;
+    ///     T ok;
+    ///     E err;
+    /// };
+    /// ```
+    ///
+    /// That is, its fields are named after [`RESULT_FIELD_NAMES`], and it has a C-compatible
+    /// layout:
+    ///
+    /// * `tag` is a one-byte type at offset zero, which is either [`RESULT_TAG_OK`] or
+    ///   [`RESULT_TAG_ERR`]. Other values are invalid.
+    /// * `ok` is at the first offset after `tag` that satisfies the alignment of `T`.
+    /// * `err` is at the first offset after `ok` that satisfies the alignment of `E`.
+    /// * The size of the result is rounded up to its alignment, which is the largest alignment of
+    ///   its fields.
+    ///
+    /// As Mun does not have unions, the payloads do not share memory. Only the payload selected by
+    /// the tag is meaningful; the other payload must be initialized, but its value is ignored.
+    ///
+    /// [`RESULT_FIELD_NAMES`]: constant.RESULT_FIELD_NAMES.html
+    /// [`RESULT_TAG_OK`]: constant.RESULT_TAG_OK.html
+    /// [`RESULT_TAG_ERR`]: constant.RESULT_TAG_ERR.html
+    pub fn is_result(&self) -> bool {
+        let struct_info = match self.as_struct() {
+            Some(struct_info) => struct_info,
+            None => return false,
+        };
+
+        usize::from(struct_info.num_fields) == RESULT_FIELD_NAMES.len()
+            && struct_info
+                .field_names()
+                .eq(RESULT_FIELD_NAMES.iter().copied())
+            && struct_info.field_types()[0].group.is_fundamental()
+            && struct_info.field_types()[0].size_in_bits() == 8
+            && self.has_c_layout()
+    }
+
+    /// Returns whether the type is plain old data, i.e. its memory does not contain references to
+    /// garbage collected structs. Fundamental types are always plain old data, while a struct is
+    /// plain old data if none of its fields - including those of nested value structs - are of a
+    /// garbage collected struct type.
+    pub fn is_pod(&self) -> bool {
+        match self.as_struct() {
+            Some(struct_info) => {
+                struct_info
+                    .field_types()
+                    .iter()
+                    .all(|field_type| match field_type.as_struct() {
+                        Some(s) if s.memory_kind == StructMemoryKind::GC => false,
+                        _ => field_type.is_pod(),
+                    })
+            }
+            None => true,
+        }
+    }
 }
 
 impl fmt::Display for TypeInfo {
@@ -61,8 +197,8 @@ impl PartialEq for TypeInfo {
     }
 }
 
-impl std::hash::Hash for TypeInfo {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+impl core::hash::Hash for TypeInfo {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         self.guid.hash(state);
     }
 }
@@ -128,7 +264,7 @@ unsafe impl Sync for FunctionSignature {}
 impl FunctionPrototype {
     /// Returns the function's name.
     pub fn name(&self) -> &str {
-        unsafe { str::from_utf8_unchecked(CStr::from_ptr(self.name).to_bytes()) }
+        unsafe { str_from_c(self.name) }
     }
 }
 
@@ -164,9 +300,7 @@ impl StructInfo {
             unsafe { slice::from_raw_parts(self.field_names, self.num_fields as usize) }
         };
 
-        field_names
-            .iter()
-            .map(|n| unsafe { str::from_utf8_unchecked(CStr::from_ptr(*n).to_bytes()) })
+        field_names.iter().map(|n| unsafe { str_from_c(*n) })
     }
 
     /// Returns the struct's field types.
@@ -192,6 +326,36 @@ impl StructInfo {
         }
     }
 
+    /// Returns a pointer to the default value of the field at `index`, if it has one.
+    ///
+    /// The default value is stored as the field type's constant bit pattern.
+    pub fn field_default(&self, index: usize) -> Option<*const u8> {
+        if self.field_defaults.is_null() || index >= self.num_fields as usize {
+            None
+        } else {
+            let default = unsafe { *self.field_defaults.add(index) };
+            if default.is_null() {
+                None
+            } else {
+                Some(default)
+            }
+        }
+    }
+
+    /// Returns the documentation of the field at `index`, if it is documented.
+    pub fn field_doc(&self, index: usize) -> Option<&str> {
+        if self.field_docs.is_null() || index >= self.num_fields as usize {
+            None
+        } else {
+            let doc = unsafe { *self.field_docs.add(index) };
+            if doc.is_null() {
+                None
+            } else {
+                Some(unsafe { str_from_c(doc) })
+            }
+        }
+    }
+
     /// Returns the index of the field matching the specified `field_name`.
     pub fn find_field_index(
         type_name: &str,
@@ -215,7 +379,7 @@ impl StructInfo {
 impl ModuleInfo {
     /// Returns the module's full path.
     pub fn path(&self) -> &str {
-        unsafe { str::from_utf8_unchecked(CStr::from_ptr(self.path).to_bytes()) }
+        unsafe { str_from_c(self.path) }
     }
 
     // /// Finds the type's fields that match `filter`.
@@ -359,15 +523,83 @@ impl AssemblyInfo {
             unsafe { slice::from_raw_parts(self.dependencies, self.num_dependencies as usize) }
         };
 
-        dependencies
-            .iter()
-            .map(|d| unsafe { str::from_utf8_unchecked(CStr::from_ptr(*d).to_bytes()) })
+        dependencies.iter().map(|d| unsafe { str_from_c(*d) })
     }
 }
 
 unsafe impl Send for AssemblyInfo {}
 unsafe impl Sync for AssemblyInfo {}
 
+impl FunctionSourceLocation {
+    /// Returns the function's name.
+    pub fn name(&self) -> &str {
+        unsafe { str_from_c(self.name) }
+    }
+
+    /// Returns the relative path of the source file that defines the function.
+    pub fn file(&self) -> &str {
+        unsafe { str_from_c(self.file) }
+    }
+}
+
+unsafe impl Send for FunctionSourceLocation {}
+unsafe impl Sync for FunctionSourceLocation {}
+
+impl DebugInfo {
+    /// Returns the source locations of the assembly's functions.
+    pub fn locations(&self) -> &[FunctionSourceLocation] {
+        if self.num_locations == 0 {
+            &[]
+        } else {
+            unsafe { slice::from_raw_parts(self.locations, self.num_locations as usize) }
+        }
+    }
+
+    /// Returns the source location of the function with the specified `name`, if it exists.
+    pub fn find_location(&self, name: &str) -> Option<&FunctionSourceLocation> {
+        self.locations().iter().find(|l| l.name() == name)
+    }
+}
+
+unsafe impl Send for DebugInfo {}
+unsafe impl Sync for DebugInfo {}
+
+impl HandleType {
+    /// Returns the handle type's name.
+    pub fn name(&self) -> &str {
+        unsafe { str_from_c(self.name) }
+    }
+
+    /// Creates a handle from `arg`. Returns `None` if the type has no `create` callback, or if the
+    /// callback failed.
+    ///
+    /// # Safety
+    ///
+    /// `arg` must be valid for the `create` callback.
+    pub unsafe fn create_handle(&self, arg: *mut c_void) -> Option<*mut c_void> {
+        let handle = (self.create?)(arg);
+        if handle.is_null() {
+            None
+        } else {
+            Some(handle)
+        }
+    }
+
+    /// Releases a `handle` that was created by `create_handle`.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must have been created by this handle type, and must not be used afterwards.
+    pub unsafe fn drop_handle(&self, handle: *mut c_void) {
+        if let Some(drop) = self.drop {
+            drop(handle)
+        }
+    }
+}
+
+unsafe impl Send for HandleType {}
+unsafe impl Sync for HandleType {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -377,6 +609,7 @@ mod tests {
 
     /// A dummy struct for initializing a struct's `TypeInfo`
     #[allow(dead_code)]
+    #[repr(C)]
     struct StructTypeInfo {
         type_info: TypeInfo,
         struct_info: StructInfo,
@@ -429,6 +662,28 @@ mod tests {
         assert_eq!(type_info.alignment(), 8);
     }
 
+    #[test]
+    fn test_type_info_size_max() {
+        let type_name = CString::new(FAKE_TYPE_NAME).expect("Invalid fake type name.");
+        let type_info = fake_type_info(
+            &type_name,
+            TypeGroup::FundamentalTypes,
+            MAX_TYPE_SIZE_IN_BITS,
+            8,
+        );
+
+        assert_eq!(type_info.size_in_bits(), 4_294_967_295);
+        assert_eq!(type_info.size_in_bytes(), 536_870_912);
+
+        let type_info = fake_type_info(
+            &type_name,
+            TypeGroup::FundamentalTypes,
+            MAX_TYPE_SIZE_IN_BITS - 7,
+            8,
+        );
+        assert_eq!(type_info.size_in_bytes(), 536_870_911);
+    }
+
     #[test]
     fn test_type_info_group_fundamental() {
         let type_name = CString::new(FAKE_TYPE_NAME).expect("Invalid fake type name.");
@@ -542,8 +797,11 @@ mod tests {
             field_names: field_names.as_ptr(),
             field_types: field_types.as_ptr().cast::<*const TypeInfo>(),
             field_offsets: field_offsets.as_ptr(),
+            field_defaults: ptr::null(),
+            field_docs: ptr::null(),
             num_fields: field_names.len() as u16,
             memory_kind,
+            magic: STRUCT_INFO_MAGIC,
         }
     }
 
@@ -579,6 +837,76 @@ mod tests {
         assert_eq!(struct_info.field_offsets(), field_offsets);
     }
 
+    #[test]
+    fn test_struct_info_field_defaults() {
+        let field_name = CString::new(FAKE_FIELD_NAME).expect("Invalid fake field name.");
+        let type_name = CString::new(FAKE_TYPE_NAME).expect("Invalid fake type name.");
+        let type_info = fake_type_info(&type_name, TypeGroup::FundamentalTypes, 64, 8);
+
+        let field_names = &[field_name.as_ptr(), field_name.as_ptr()];
+        let field_types = &[&type_info, &type_info];
+        let field_offsets = &[0, 8];
+        let mut struct_info =
+            fake_struct_info(field_names, field_types, field_offsets, Default::default());
+
+        assert_eq!(struct_info.field_default(0), None);
+        assert_eq!(struct_info.field_default(1), None);
+
+        let default = 1.0f64;
+        let field_defaults = &[(&default as *const f64).cast::<u8>(), ptr::null()];
+        struct_info.field_defaults = field_defaults.as_ptr();
+
+        assert_eq!(struct_info.field_default(0), Some(field_defaults[0]));
+        assert_eq!(struct_info.field_default(1), None);
+        assert_eq!(struct_info.field_default(2), None);
+    }
+
+    #[test]
+    fn test_struct_info_field_docs() {
+        let field_name = CString::new(FAKE_FIELD_NAME).expect("Invalid fake field name.");
+        let field_doc = CString::new("The field").expect("Invalid fake field doc.");
+        let type_name = CString::new(FAKE_TYPE_NAME).expect("Invalid fake type name.");
+        let type_info = fake_type_info(&type_name, TypeGroup::FundamentalTypes, 64, 8);
+
+        let field_names = &[field_name.as_ptr(), field_name.as_ptr()];
+        let field_types = &[&type_info, &type_info];
+        let field_offsets = &[0, 8];
+        let mut struct_info =
+            fake_struct_info(field_names, field_types, field_offsets, Default::default());
+
+        assert_eq!(struct_info.field_doc(0), None);
+
+        let field_docs = &[ptr::null(), field_doc.as_ptr()];
+        struct_info.field_docs = field_docs.as_ptr();
+
+        assert_eq!(struct_info.field_doc(0), None);
+        assert_eq!(struct_info.field_doc(1), Some("The field"));
+        assert_eq!(struct_info.field_doc(2), None);
+    }
+
+    #[test]
+    fn test_type_info_as_struct() {
+        let struct_name = CString::new(FAKE_STRUCT_NAME).expect("Invalid fake struct name");
+        let struct_info = fake_struct_info(&[], &[], &[], StructMemoryKind::Value);
+        let struct_type_info = fake_struct_type_info(&struct_name, struct_info, 0, 1);
+
+        let struct_info = struct_type_info.type_info.as_struct().unwrap();
+        assert_eq!(struct_info.memory_kind, StructMemoryKind::Value);
+        assert_eq!(struct_info.magic, STRUCT_INFO_MAGIC);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "invalid `StructInfo`")]
+    fn test_type_info_as_struct_invalid_magic() {
+        let struct_name = CString::new(FAKE_STRUCT_NAME).expect("Invalid fake struct name");
+        let mut struct_info = fake_struct_info(&[], &[], &[], Default::default());
+        struct_info.magic = 0;
+        let struct_type_info = fake_struct_type_info(&struct_name, struct_info, 0, 1);
+
+        struct_type_info.type_info.as_struct();
+    }
+
     #[test]
     fn test_struct_info_memory_kind_gc() {
         let struct_memory_kind = StructMemoryKind::GC;
@@ -595,6 +923,155 @@ mod tests {
         assert_eq!(struct_info.memory_kind, struct_memory_kind);
     }
 
+    #[test]
+    fn test_type_info_has_c_layout() {
+        let u8_name = CString::new("core::u8").expect("Invalid fake type name.");
+        let u8_type_info = fake_type_info(&u8_name, TypeGroup::FundamentalTypes, 8, 1);
+        assert!(u8_type_info.has_c_layout());
+
+        let i64_name = CString::new("core::i64").expect("Invalid fake type name.");
+        let i64_type_info = fake_type_info(&i64_name, TypeGroup::FundamentalTypes, 64, 8);
+
+        let field_names = &[
+            CString::new("a").expect("Invalid fake field name."),
+            CString::new("b").expect("Invalid fake field name."),
+        ];
+        let field_names: Vec<*const c_char> = field_names.iter().map(|n| n.as_ptr()).collect();
+        let field_types = &[&u8_type_info, &i64_type_info];
+
+        let struct_name = CString::new(FAKE_STRUCT_NAME).expect("Invalid fake struct name");
+        let struct_info =
+            fake_struct_info(&field_names, field_types, &[0, 8], StructMemoryKind::Value);
+        let struct_type_info = fake_struct_type_info(&struct_name, struct_info, 128, 8);
+        assert!(struct_type_info.type_info.has_c_layout());
+    }
+
+    #[test]
+    fn test_type_info_has_c_layout_packed() {
+        let u8_name = CString::new("core::u8").expect("Invalid fake type name.");
+        let u8_type_info = fake_type_info(&u8_name, TypeGroup::FundamentalTypes, 8, 1);
+
+        let i64_name = CString::new("core::i64").expect("Invalid fake type name.");
+        let i64_type_info = fake_type_info(&i64_name, TypeGroup::FundamentalTypes, 64, 8);
+
+        let field_names = &[
+            CString::new("a").expect("Invalid fake field name."),
+            CString::new("b").expect("Invalid fake field name."),
+        ];
+        let field_names: Vec<*const c_char> = field_names.iter().map(|n| n.as_ptr()).collect();
+        let field_types = &[&u8_type_info, &i64_type_info];
+
+        let struct_name = CString::new(FAKE_STRUCT_NAME).expect("Invalid fake struct name");
+        let struct_info =
+            fake_struct_info(&field_names, field_types, &[0, 1], StructMemoryKind::Value);
+        let struct_type_info = fake_struct_type_info(&struct_name, struct_info, 72, 1);
+        assert!(!struct_type_info.type_info.has_c_layout());
+    }
+
+    #[test]
+    fn test_type_info_is_result() {
+        let u8_name = CString::new("core::u8").expect("Invalid fake type name.");
+        let u8_type_info = fake_type_info(&u8_name, TypeGroup::FundamentalTypes, 8, 1);
+
+        let i32_name = CString::new("core::i32").expect("Invalid fake type name.");
+        let i32_type_info = fake_type_info(&i32_name, TypeGroup::FundamentalTypes, 32, 4);
+        assert!(!i32_type_info.is_result());
+
+        let field_names: Vec<CString> = RESULT_FIELD_NAMES
+            .iter()
+            .map(|name| CString::new(*name).expect("Invalid fake field name."))
+            .collect();
+        let field_names: Vec<*const c_char> = field_names.iter().map(|n| n.as_ptr()).collect();
+        let field_types = &[&u8_type_info, &i32_type_info, &u8_type_info];
+
+        let struct_name = CString::new(FAKE_STRUCT_NAME).expect("Invalid fake struct name");
+        let struct_info = fake_struct_info(
+            &field_names,
+            field_types,
+            &[0, 4, 8],
+            StructMemoryKind::Value,
+        );
+        let struct_type_info = fake_struct_type_info(&struct_name, struct_info, 96, 4);
+        assert!(struct_type_info.type_info.is_result());
+
+        // The payloads must be laid out according to the C ABI
+        let struct_info = fake_struct_info(
+            &field_names,
+            field_types,
+            &[0, 1, 5],
+            StructMemoryKind::Value,
+        );
+        let struct_type_info = fake_struct_type_info(&struct_name, struct_info, 48, 1);
+        assert!(!struct_type_info.type_info.is_result());
+
+        // The fields must be named after `RESULT_FIELD_NAMES`
+        let other_names = &[
+            CString::new("a").expect("Invalid fake field name."),
+            CString::new("b").expect("Invalid fake field name."),
+            CString::new("c").expect("Invalid fake field name."),
+        ];
+        let other_names: Vec<*const c_char> = other_names.iter().map(|n| n.as_ptr()).collect();
+        let struct_info = fake_struct_info(
+            &other_names,
+            field_types,
+            &[0, 4, 8],
+            StructMemoryKind::Value,
+        );
+        let struct_type_info = fake_struct_type_info(&struct_name, struct_info, 96, 4);
+        assert!(!struct_type_info.type_info.is_result());
+    }
+
+    #[test]
+    fn test_type_info_is_pod() {
+        let u8_name = CString::new("core::u8").expect("Invalid fake type name.");
+        let u8_type_info = fake_type_info(&u8_name, TypeGroup::FundamentalTypes, 8, 1);
+        assert!(u8_type_info.is_pod());
+
+        let field_names = &[CString::new("a").expect("Invalid fake field name.")];
+        let field_names: Vec<*const c_char> = field_names.iter().map(|n| n.as_ptr()).collect();
+
+        let value_name = CString::new("Value").expect("Invalid fake struct name");
+        let value_info = fake_struct_info(
+            &field_names,
+            &[&u8_type_info],
+            &[0],
+            StructMemoryKind::Value,
+        );
+        let value_type_info = fake_struct_type_info(&value_name, value_info, 8, 1);
+        assert!(value_type_info.type_info.is_pod());
+
+        let gc_name = CString::new("Gc").expect("Invalid fake struct name");
+        let gc_info = fake_struct_info(
+            &field_names,
+            &[&value_type_info.type_info],
+            &[0],
+            StructMemoryKind::GC,
+        );
+        let gc_type_info = fake_struct_type_info(&gc_name, gc_info, 8, 1);
+        // A garbage collected struct can itself be plain old data
+        assert!(gc_type_info.type_info.is_pod());
+
+        let outer_name = CString::new("Outer").expect("Invalid fake struct name");
+        let outer_info = fake_struct_info(
+            &field_names,
+            &[&gc_type_info.type_info],
+            &[0],
+            StructMemoryKind::Value,
+        );
+        let outer_type_info = fake_struct_type_info(&outer_name, outer_info, 64, 8);
+        assert!(!outer_type_info.type_info.is_pod());
+
+        let nested_name = CString::new("Nested").expect("Invalid fake struct name");
+        let nested_info = fake_struct_info(
+            &field_names,
+            &[&outer_type_info.type_info],
+            &[0],
+            StructMemoryKind::Value,
+        );
+        let nested_type_info = fake_struct_type_info(&nested_name, nested_info, 64, 8);
+        assert!(!nested_type_info.type_info.is_pod());
+    }
+
     fn fake_module_info(
         path: &CStr,
         functions: &[FunctionDefinition],
@@ -920,4 +1397,70 @@ mod tests {
             assert_eq!(lhs, *rhs)
         }
     }
+
+    const FAKE_FILE_PATH: &str = "mod.mun";
+
+    #[test]
+    fn test_debug_info_locations_none() {
+        let debug_info = DebugInfo {
+            locations: ptr::null(),
+            num_locations: 0,
+        };
+
+        assert!(debug_info.locations().is_empty());
+        assert!(debug_info.find_location(FAKE_FN_NAME).is_none());
+    }
+
+    #[test]
+    fn test_debug_info_locations_some() {
+        let fn_name = CString::new(FAKE_FN_NAME).expect("Invalid fake fn name.");
+        let file_path = CString::new(FAKE_FILE_PATH).expect("Invalid fake file path.");
+        let locations = &[FunctionSourceLocation {
+            name: fn_name.as_ptr(),
+            file: file_path.as_ptr(),
+            line: 3,
+        }];
+        let debug_info = DebugInfo {
+            locations: locations.as_ptr(),
+            num_locations: locations.len() as u32,
+        };
+
+        assert_eq!(debug_info.locations().len(), locations.len());
+        let location = debug_info
+            .find_location(FAKE_FN_NAME)
+            .expect("Expected a source location.");
+        assert_eq!(location.name(), FAKE_FN_NAME);
+        assert_eq!(location.file(), FAKE_FILE_PATH);
+        assert_eq!(location.line, 3);
+    }
+
+    const FAKE_HANDLE_TYPE_NAME: &str = "Connection";
+
+    unsafe extern "C" fn fake_create(arg: *mut c_void) -> *mut c_void {
+        arg
+    }
+
+    unsafe extern "C" fn fake_drop(handle: *mut c_void) {
+        *(handle as *mut u32) += 1;
+    }
+
+    #[test]
+    fn test_handle_type_create_and_drop() {
+        let type_name = CString::new(FAKE_HANDLE_TYPE_NAME).expect("Invalid fake type name.");
+        let handle_type = HandleType {
+            name: type_name.as_ptr(),
+            create: Some(fake_create),
+            drop: Some(fake_drop),
+        };
+        assert_eq!(handle_type.name(), FAKE_HANDLE_TYPE_NAME);
+
+        let mut drops = 0u32;
+        let arg = &mut drops as *mut u32 as *mut c_void;
+        let handle = unsafe { handle_type.create_handle(arg) }.expect("Expected a handle.");
+        assert_eq!(handle, arg);
+        assert!(unsafe { handle_type.create_handle(ptr::null_mut()) }.is_none());
+
+        unsafe { handle_type.drop_handle(handle) };
+        assert_eq!(drops, 1);
+    }
 }
@@ -2,26 +2,95 @@
 //!
 //! The Mun ABI defines the binary format used to communicate between the Mun Compiler and Mun
 //! Runtime.
+//!
+//! The ABI's types and their accessors only depend on `core` and `alloc`. Disabling the default
+//! `std` feature makes the crate `no_std`, which excludes the static type information of Rust
+//! types (`HasStaticTypeInfo`) and the construction of function definitions
+//! (`IntoFunctionDefinition`).
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(all(test, not(feature = "std")))]
+extern crate std;
+
 // Bindings can be manually generated by running `cargo gen-abi`.
 mod autogen;
 mod autogen_impl;
+#[cfg(feature = "std")]
 mod function_info;
+#[cfg(feature = "std")]
 mod static_type_map;
+#[cfg(feature = "std")]
 mod type_info;
+#[cfg(feature = "std")]
+mod type_info_builder;
 
 pub use autogen::*;
+#[cfg(feature = "std")]
 pub use function_info::{FunctionDefinitionStorage, IntoFunctionDefinition};
+#[cfg(feature = "std")]
 pub use type_info::HasStaticTypeInfo;
+#[cfg(feature = "std")]
+pub use type_info_builder::{OwnedTypeInfo, TypeInfoBuilder};
+
+/// The version of the ABI. It is returned by the `get_version` function that every assembly
+/// exports, which allows the runtime to refuse loading an assembly that was compiled against a
+/// different ABI.
+///
+/// It must be incremented with every change to the layout or semantics of the ABI's types.
+pub const ABI_VERSION: u32 = 201;
+
+/// The magic number that is stored in every `StructInfo`. It is used to validate that the
+/// `StructInfo` trailing a struct's `TypeInfo` is read from the correct location.
+pub const STRUCT_INFO_MAGIC: u32 = 0x4D55_4E53;
+
+/// The tag of a result that holds a success payload. See [`TypeInfo::is_result`].
+///
+/// [`TypeInfo::is_result`]: struct.TypeInfo.html#method.is_result
+pub const RESULT_TAG_OK: u8 = 0;
+
+/// The tag of a result that holds an error payload. See [`TypeInfo::is_result`].
+///
+/// [`TypeInfo::is_result`]: struct.TypeInfo.html#method.is_result
+pub const RESULT_TAG_ERR: u8 = 1;
+
+/// The names of the fields of a result: its tag, its success payload, and its error payload.
+pub const RESULT_FIELD_NAMES: [&str; 3] = ["tag", "ok", "err"];
+
+/// The maximum size of a type in bits that can be stored in `TypeInfo::size_in_bits`, i.e. a type
+/// can be at most 512 MiB. The compiler refuses to emit type information for larger types.
+pub const MAX_TYPE_SIZE_IN_BITS: u32 = core::u32::MAX;
+
+/// The prefix of the names of the symbols under which an assembly exports its functions.
+pub const FN_SYMBOL_PREFIX: &str = "mun$";
+
+/// Returns the name of the symbol under which an assembly exports the public function at
+/// `fn_path`, e.g. `main` or `foo::bar`. This allows loading a function through the dynamic linker
+/// of the OS, e.g. `dlsym`, instead of the runtime.
+///
+/// The symbol name is the `FN_SYMBOL_PREFIX`, followed by the segments of the path separated by
+/// `$`. As `$` cannot occur in Mun identifiers, and Mun does not support function overloading,
+/// every function has a unique symbol name: e.g. `foo::bar` is exported as `mun$foo$bar`.
+pub fn fn_symbol_name(fn_path: &str) -> String {
+    let mut symbol = String::from(FN_SYMBOL_PREFIX);
+    symbol.push_str(&fn_path.replace("::", "$"));
+    symbol
+}
 
 /// The Mun ABI prelude
 ///
 /// The *prelude* contains imports that are used almost every time.
 pub mod prelude {
     pub use crate::autogen::*;
+    #[cfg(feature = "std")]
+    pub use crate::{HasStaticTypeInfo, IntoFunctionDefinition};
     pub use crate::{
-        HasStaticTypeInfo, IntoFunctionDefinition, Privacy, StructMemoryKind, TypeGroup,
+        Privacy, StructMemoryKind, TypeGroup, ABI_VERSION, MAX_TYPE_SIZE_IN_BITS,
+        RESULT_FIELD_NAMES, RESULT_TAG_ERR, RESULT_TAG_OK, STRUCT_INFO_MAGIC,
     };
 }
 
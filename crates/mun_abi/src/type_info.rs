@@ -1,9 +1,24 @@
 use crate::{static_type_map::StaticTypeMap, Guid, TypeGroup, TypeInfo};
 use once_cell::sync::OnceCell;
-use std::convert::TryInto;
 use std::ffi::{CStr, CString};
+use std::ptr::NonNull;
 use std::sync::Once;
 
+/// Asserts at compile time that the size in bits and the alignment of `$ty` fit in the
+/// `TypeInfo::size_in_bits` and `TypeInfo::alignment` fields, so converting them is lossless.
+///
+/// A type that is too large fails to compile with a mismatched array length.
+macro_rules! assert_abi_layout {
+    ($ty:ty) => {
+        const _: [(); 0] =
+            [(); ((std::mem::size_of::<$ty>() * 8 > crate::MAX_TYPE_SIZE_IN_BITS as usize)
+                || (std::mem::align_of::<$ty>() > std::u8::MAX as usize)) as usize];
+    };
+}
+
+// All pointers to sized types have the same layout, so it suffices to assert it for one.
+assert_abi_layout!(*const std::ffi::c_void);
+
 /// A trait that defines that for a type we can statically return a `TypeInfo`.
 pub trait HasStaticTypeInfo {
     /// Returns a reference to the TypeInfo for the type
@@ -51,12 +66,8 @@ impl<T: HasStaticTypeInfoName + 'static> HasStaticTypeInfo for *const T {
                     guid,
                     name: name_ptr,
                     group: TypeGroup::FundamentalTypes,
-                    size_in_bits: (std::mem::size_of::<*const T>() * 8)
-                        .try_into()
-                        .expect("size of T is larger than the maximum allowed ABI size. Please file a bug."),
-                    alignment: (std::mem::align_of::<*const T>())
-                        .try_into()
-                        .expect("alignment of T is larger than the maximum allowed ABI size. Please file a bug."),
+                    size_in_bits: (std::mem::size_of::<*const T>() * 8) as u32,
+                    alignment: std::mem::align_of::<*const T>() as u8,
                 },
             )
         })
@@ -89,12 +100,8 @@ impl<T: HasStaticTypeInfoName + 'static> HasStaticTypeInfo for *mut T {
                     guid,
                     name: name_ptr,
                     group: TypeGroup::FundamentalTypes,
-                    size_in_bits: (std::mem::size_of::<*const T>() * 8)
-                        .try_into()
-                        .expect("size of T is larger than the maximum allowed ABI size. Please file a bug."),
-                    alignment: (std::mem::align_of::<*const T>())
-                        .try_into()
-                        .expect("alignment of T is larger than the maximum allowed ABI size. Please file a bug."),
+                    size_in_bits: (std::mem::size_of::<*const T>() * 8) as u32,
+                    alignment: std::mem::align_of::<*const T>() as u8,
                 },
             )
         })
@@ -102,11 +109,27 @@ impl<T: HasStaticTypeInfoName + 'static> HasStaticTypeInfo for *mut T {
     }
 }
 
+/// A `NonNull<T>` has the same ABI representation, and thus the same type info, as a `*mut T`.
+impl<T: HasStaticTypeInfoName + 'static> HasStaticTypeInfo for NonNull<T> {
+    fn type_info() -> &'static TypeInfo {
+        <*mut T>::type_info()
+    }
+}
+
+/// A `&T` has the same ABI representation, and thus the same type info, as a `*const T`.
+impl<'a, T: HasStaticTypeInfoName + 'static> HasStaticTypeInfo for &'a T {
+    fn type_info() -> &'static TypeInfo {
+        <*const T>::type_info()
+    }
+}
+
 macro_rules! impl_basic_type_info {
     ($(
         $ty:ty
     ),+) => {
         $(
+            assert_abi_layout!($ty);
+
             impl HasStaticTypeInfo for $ty {
                 fn type_info() -> &'static TypeInfo {
                     static TYPE_INFO: OnceCell<TypeInfo> = OnceCell::new();
@@ -119,12 +142,8 @@ macro_rules! impl_basic_type_info {
                             guid: Guid{ b: md5::compute(&type_info_name.as_bytes()).0 },
                             name: type_info_name.as_ptr(),
                             group: TypeGroup::FundamentalTypes,
-                            size_in_bits: (std::mem::size_of::<$ty>() * 8)
-                                .try_into()
-                                .expect("size of T is larger than the maximum allowed ABI size. Please file a bug."),
-                            alignment: (std::mem::align_of::<$ty>())
-                                .try_into()
-                                .expect("alignment of T is larger than the maximum allowed ABI size. Please file a bug."),
+                            size_in_bits: (std::mem::size_of::<$ty>() * 8) as u32,
+                            alignment: std::mem::align_of::<$ty>() as u8,
                         }
                     })
                 }
@@ -187,7 +206,8 @@ impl HasStaticTypeInfo for isize {
 
 #[cfg(test)]
 mod tests {
-    use super::HasStaticTypeInfoName;
+    use super::{HasStaticTypeInfo, HasStaticTypeInfoName};
+    use std::ptr::NonNull;
 
     #[test]
     fn ptr_test() {
@@ -200,4 +220,25 @@ mod tests {
         let ty = <*const *const std::ffi::c_void>::type_name();
         assert_eq!(ty.to_str().unwrap(), "*const *const core::void");
     }
+
+    #[test]
+    fn non_null_and_ref_test() {
+        assert!(std::ptr::eq(
+            <NonNull<std::ffi::c_void>>::type_info(),
+            <*mut std::ffi::c_void>::type_info()
+        ));
+        assert_eq!(
+            <NonNull<std::ffi::c_void>>::type_name().to_str().unwrap(),
+            "*mut core::void"
+        );
+
+        assert!(std::ptr::eq(
+            <&std::ffi::c_void>::type_info(),
+            <*const std::ffi::c_void>::type_info()
+        ));
+        assert_eq!(
+            <&std::ffi::c_void>::type_name().to_str().unwrap(),
+            "*const core::void"
+        );
+    }
 }
@@ -0,0 +1,292 @@
+use crate::{
+    Guid, HasStaticTypeInfo, StructInfo, StructMemoryKind, TypeGroup, TypeInfo, STRUCT_INFO_MAGIC,
+};
+use std::{
+    convert::TryFrom,
+    ffi::{c_void, CString},
+    mem,
+    ops::Deref,
+    os::raw::c_char,
+    ptr,
+    sync::Arc,
+};
+
+/// The memory of a struct's `TypeInfo`, which is directly followed by its `StructInfo`.
+#[repr(C)]
+struct StructTypeInfo {
+    type_info: TypeInfo,
+    struct_info: StructInfo,
+}
+
+/// The memory of an `OwnedTypeInfo`, which is boxed so the `TypeInfo` has a stable address.
+enum Storage {
+    Fundamental(Box<TypeInfo>),
+    Struct(Box<StructTypeInfo>),
+}
+
+/// A `TypeInfo` that owns all memory it points to, as constructed by a `TypeInfoBuilder`.
+pub struct OwnedTypeInfo {
+    storage: Storage,
+    /// The string from which the GUID of a struct type that contains this type is derived
+    guid_string: String,
+    _name: CString,
+    _field_names: Vec<CString>,
+    _field_name_ptrs: Vec<*const c_char>,
+    _field_types: Vec<Arc<OwnedTypeInfo>>,
+    _field_type_ptrs: Vec<*const TypeInfo>,
+    _field_offsets: Vec<u16>,
+}
+
+// Safety: The memory of an `OwnedTypeInfo` is never mutated after construction.
+unsafe impl Send for OwnedTypeInfo {}
+unsafe impl Sync for OwnedTypeInfo {}
+
+impl Deref for OwnedTypeInfo {
+    type Target = TypeInfo;
+
+    fn deref(&self) -> &TypeInfo {
+        match &self.storage {
+            Storage::Fundamental(type_info) => type_info,
+            Storage::Struct(struct_type_info) => &struct_type_info.type_info,
+        }
+    }
+}
+
+/// Constructs struct `TypeInfo`s, which are laid out identical to the `TypeInfo`s that the Mun
+/// compiler emits for the struct's definition. This allows constructing realistic types without
+/// compiling Mun code, e.g. for tests.
+///
+/// Fields are placed at the first offset after the previous field that satisfies the field's
+/// alignment, where fields of garbage collected struct types are laid out as pointers. The size of
+/// the struct is rounded up to its largest field alignment.
+pub struct TypeInfoBuilder {
+    name: String,
+    memory_kind: StructMemoryKind,
+    fields: Vec<(String, Arc<OwnedTypeInfo>)>,
+}
+
+impl TypeInfoBuilder {
+    /// Constructs the `TypeInfo` of the fundamental type `T`, which is identical to
+    /// `T::type_info()`.
+    pub fn fundamental<T: HasStaticTypeInfo>() -> Arc<OwnedTypeInfo> {
+        let type_info = T::type_info();
+        let name = CString::new(type_info.name()).unwrap();
+        Arc::new(OwnedTypeInfo {
+            storage: Storage::Fundamental(Box::new(TypeInfo {
+                guid: type_info.guid,
+                name: name.as_ptr(),
+                size_in_bits: type_info.size_in_bits,
+                alignment: type_info.alignment,
+                group: TypeGroup::FundamentalTypes,
+            })),
+            guid_string: type_info.name().to_string(),
+            _name: name,
+            _field_names: Vec::new(),
+            _field_name_ptrs: Vec::new(),
+            _field_types: Vec::new(),
+            _field_type_ptrs: Vec::new(),
+            _field_offsets: Vec::new(),
+        })
+    }
+
+    /// Starts the construction of a garbage collected struct type called `name`, without fields.
+    pub fn new_struct<S: Into<String>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            memory_kind: StructMemoryKind::default(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Sets the memory kind of the struct.
+    pub fn memory_kind(mut self, memory_kind: StructMemoryKind) -> Self {
+        self.memory_kind = memory_kind;
+        self
+    }
+
+    /// Appends a field called `name` of type `ty` to the struct.
+    pub fn field<S: Into<String>>(mut self, name: S, ty: &Arc<OwnedTypeInfo>) -> Self {
+        self.fields.push((name.into(), ty.clone()));
+        self
+    }
+
+    /// Constructs the struct's `TypeInfo`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a field name contains a nul byte, if the struct has more than `u16::MAX` fields,
+    /// or if a field offset or the size of the struct do not fit in the ABI.
+    pub fn build(self) -> Arc<OwnedTypeInfo> {
+        let align_up =
+            |offset: usize, alignment: usize| (offset + alignment - 1) / alignment * alignment;
+
+        let mut offset = 0;
+        let mut alignment = 1;
+        let mut field_offsets = Vec::with_capacity(self.fields.len());
+        for (name, ty) in self.fields.iter() {
+            let (field_size, field_alignment) = match ty.as_struct() {
+                Some(s) if s.memory_kind == StructMemoryKind::GC => (
+                    mem::size_of::<*const c_void>(),
+                    mem::align_of::<*const c_void>(),
+                ),
+                _ => (ty.size_in_bytes(), ty.alignment()),
+            };
+
+            offset = align_up(offset, field_alignment);
+            field_offsets.push(u16::try_from(offset).unwrap_or_else(|_| {
+                panic!("the offset of field `{}` does not fit in a `u16`", name)
+            }));
+            offset += field_size;
+            alignment = alignment.max(field_alignment);
+        }
+        // `MAX_TYPE_SIZE_IN_BITS` is `u32::MAX`, so every size that fits in a `u32` is valid
+        let size_in_bits = u32::try_from(align_up(offset, alignment) * 8)
+            .unwrap_or_else(|_| panic!("struct `{}` is too large", self.name));
+
+        // The GUID is derived in the same way as the compiler does. Garbage collected structs are
+        // referenced by name only, while value structs include their fields.
+        let fields_guid_string: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(name, ty)| format!("{}: {}", name, ty.guid_string))
+            .collect();
+        let full_guid_string = format!("struct {}{{{}}}", self.name, fields_guid_string.join(","));
+        let guid = Guid {
+            b: md5::compute(&full_guid_string).0,
+        };
+        let guid_string = match self.memory_kind {
+            StructMemoryKind::GC => format!("struct {}", self.name),
+            StructMemoryKind::Value => full_guid_string,
+        };
+
+        let name = CString::new(self.name).expect("struct name contains a nul byte");
+        let field_names: Vec<CString> = self
+            .fields
+            .iter()
+            .map(|(name, _)| CString::new(name.as_str()).expect("field name contains a nul byte"))
+            .collect();
+        let field_name_ptrs: Vec<*const c_char> =
+            field_names.iter().map(|name| name.as_ptr()).collect();
+        let field_types: Vec<Arc<OwnedTypeInfo>> =
+            self.fields.into_iter().map(|(_, ty)| ty).collect();
+        let field_type_ptrs: Vec<*const TypeInfo> = field_types
+            .iter()
+            .map(|ty| {
+                let type_info: &TypeInfo = ty;
+                type_info as *const TypeInfo
+            })
+            .collect();
+        let num_fields = u16::try_from(field_types.len()).expect("too many fields");
+
+        let struct_type_info = StructTypeInfo {
+            type_info: TypeInfo {
+                guid,
+                name: name.as_ptr(),
+                size_in_bits,
+                // The alignment is at most that of a field, whose alignment fits in a `u8`
+                alignment: alignment as u8,
+                group: TypeGroup::StructTypes,
+            },
+            struct_info: StructInfo {
+                field_names: field_name_ptrs.as_ptr(),
+                field_types: field_type_ptrs.as_ptr(),
+                field_offsets: field_offsets.as_ptr(),
+                field_defaults: ptr::null(),
+                field_docs: ptr::null(),
+                num_fields,
+                memory_kind: self.memory_kind,
+                magic: STRUCT_INFO_MAGIC,
+            },
+        };
+
+        Arc::new(OwnedTypeInfo {
+            storage: Storage::Struct(Box::new(struct_type_info)),
+            guid_string,
+            _name: name,
+            _field_names: field_names,
+            _field_name_ptrs: field_name_ptrs,
+            _field_types: field_types,
+            _field_type_ptrs: field_type_ptrs,
+            _field_offsets: field_offsets,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TypeInfoBuilder;
+    use crate::{HasStaticTypeInfo, StructMemoryKind, TypeGroup};
+
+    #[test]
+    fn fundamental() {
+        let type_info = TypeInfoBuilder::fundamental::<f64>();
+        assert_eq!(&**type_info, f64::type_info());
+        assert_eq!(type_info.name(), "core::f64");
+        assert_eq!(type_info.size_in_bits(), 64);
+        assert!(type_info.as_struct().is_none());
+    }
+
+    #[test]
+    fn struct_layout() {
+        let bar = TypeInfoBuilder::new_struct("Bar").build();
+        let baz = TypeInfoBuilder::new_struct("Baz")
+            .memory_kind(StructMemoryKind::Value)
+            .field("a", &TypeInfoBuilder::fundamental::<u8>())
+            .field("b", &TypeInfoBuilder::fundamental::<i32>())
+            .build();
+        let foo = TypeInfoBuilder::new_struct("Foo")
+            .field("a", &TypeInfoBuilder::fundamental::<u8>())
+            .field("bar", &bar)
+            .field("baz", &baz)
+            .build();
+
+        assert_eq!(foo.group, TypeGroup::StructTypes);
+        assert_eq!(foo.name(), "Foo");
+        assert!(foo.has_c_layout());
+        assert!(baz.has_c_layout());
+        assert_eq!(baz.size_in_bytes(), 8);
+        assert_eq!(baz.alignment(), 4);
+
+        let pointer_size = std::mem::size_of::<*const u8>();
+        let struct_info = foo.as_struct().unwrap();
+        assert_eq!(struct_info.memory_kind, StructMemoryKind::GC);
+        assert_eq!(
+            struct_info.field_names().collect::<Vec<_>>(),
+            ["a", "bar", "baz"]
+        );
+        assert_eq!(
+            struct_info.field_offsets(),
+            [0, pointer_size as u16, 2 * pointer_size as u16]
+        );
+        assert_eq!(struct_info.field_types()[0], u8::type_info());
+        assert_eq!(struct_info.field_types()[1], &**bar);
+        assert_eq!(foo.size_in_bytes(), 2 * pointer_size + 8);
+        assert_eq!(foo.alignment(), std::mem::align_of::<*const u8>());
+    }
+
+    #[test]
+    fn struct_guid() {
+        // The GUID of a struct depends on its fields, but only on the name of garbage collected
+        // struct fields.
+        let bar = TypeInfoBuilder::new_struct("Bar")
+            .field("a", &TypeInfoBuilder::fundamental::<i32>())
+            .build();
+        let foo = TypeInfoBuilder::new_struct("Foo")
+            .field("bar", &bar)
+            .build();
+
+        let expected = md5::compute("struct Foo{bar: struct Bar}").0;
+        assert_eq!(foo.guid.b, expected);
+
+        let bar = TypeInfoBuilder::new_struct("Bar")
+            .memory_kind(StructMemoryKind::Value)
+            .field("a", &TypeInfoBuilder::fundamental::<i32>())
+            .build();
+        let foo = TypeInfoBuilder::new_struct("Foo")
+            .field("bar", &bar)
+            .build();
+
+        let expected = md5::compute("struct Foo{bar: struct Bar{a: core::i32}}").0;
+        assert_eq!(foo.guid.b, expected);
+    }
+}
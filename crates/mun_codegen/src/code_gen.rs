@@ -32,6 +32,11 @@ enum CodeGenerationError {
     UnknownTargetTriple(String),
     #[fail(display = "error creating target machine")]
     CouldNotCreateTargetMachine,
+    #[fail(
+        display = "relocation model `{:?}` cannot be used for shared libraries on target `{}`",
+        0, 1
+    )]
+    UnsupportedRelocModel(spec::RelocModel, String),
     #[fail(display = "error creating object file")]
     CouldNotCreateObjectFile(io::Error),
     #[fail(display = "error generating machine code")]
@@ -48,6 +53,8 @@ pub struct ObjectFile {
     target: spec::Target,
     src_path: RelativePathBuf,
     obj_file: NamedTempFile,
+    exported_fns: Vec<String>,
+    exported_globals: Vec<String>,
 }
 
 impl ObjectFile {
@@ -71,6 +78,8 @@ impl ObjectFile {
             target: target.clone(),
             src_path,
             obj_file,
+            exported_fns: exported_fns(&module),
+            exported_globals: exported_globals(&module),
         })
     }
 
@@ -78,6 +87,12 @@ impl ObjectFile {
         // Construct a linker for the target
         let mut linker = linker::create_with_target(&self.target);
         linker.add_object(self.obj_file.path())?;
+        for symbol in self.exported_fns.iter() {
+            linker.add_exported_fn(symbol);
+        }
+        for symbol in self.exported_globals.iter() {
+            linker.add_exported_global(symbol);
+        }
 
         let output_path = assembly_output_path(&self.src_path, out_dir);
 
@@ -89,6 +104,32 @@ impl ObjectFile {
     }
 }
 
+/// Returns the names of all functions in `module` that are exported from the shared object.
+fn exported_fns(module: &Module) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut function = module.get_first_function();
+    while let Some(value) = function {
+        if value.get_linkage() == Linkage::DLLExport {
+            names.push(value.get_name().to_string_lossy().into_owned());
+        }
+        function = value.get_next_function();
+    }
+    names
+}
+
+/// Returns the names of all globals in `module` that are exported from the shared object.
+fn exported_globals(module: &Module) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut global = module.get_first_global();
+    while let Some(value) = global {
+        if value.get_linkage() == Linkage::DLLExport {
+            names.push(value.get_name().to_string_lossy().into_owned());
+        }
+        global = value.get_next_global();
+    }
+    names
+}
+
 /// A struct that can be used to build an LLVM `Module`.
 pub struct ModuleBuilder<'a, D: IrDatabase> {
     db: &'a D,
@@ -103,6 +144,18 @@ impl<'a, D: IrDatabase> ModuleBuilder<'a, D> {
     pub fn new(db: &'a D, file_id: FileId) -> Result<Self, failure::Error> {
         let target = db.target();
 
+        // Mun libraries are shared libraries, which can only contain position-dependent code on
+        // Windows, where the loader relocates it
+        if target.options.relocation_model != spec::RelocModel::Pic
+            && !target.options.is_like_windows
+        {
+            return Err(CodeGenerationError::UnsupportedRelocModel(
+                target.options.relocation_model,
+                target.llvm_target.clone(),
+            )
+            .into());
+        }
+
         // Construct a module for the assembly
         let assembly_module = Arc::new(
             db.context()
@@ -117,6 +170,10 @@ impl<'a, D: IrDatabase> ModuleBuilder<'a, D> {
             .map_err(|e| CodeGenerationError::UnknownTargetTriple(e.to_string()))?;
         assembly_module.set_target(&llvm_target);
 
+        // Use the target's data layout rather than LLVM's default, so the layout the IR is
+        // generated with is the one the machine code is emitted with
+        assembly_module.set_data_layout(&db.target_data().get_data_layout());
+
         // Construct target machine for machine code generation
         let target_machine = llvm_target
             .create_target_machine(
@@ -124,7 +181,11 @@ impl<'a, D: IrDatabase> ModuleBuilder<'a, D> {
                 &target.options.cpu,
                 &target.options.features,
                 db.optimization_lvl(),
-                RelocMode::PIC,
+                match target.options.relocation_model {
+                    spec::RelocModel::Pic => RelocMode::PIC,
+                    spec::RelocModel::DynamicNoPic => RelocMode::DynamicNoPic,
+                    spec::RelocModel::Static => RelocMode::Static,
+                },
                 CodeModel::Default,
             )
             .ok_or(CodeGenerationError::CouldNotCreateTargetMachine)?;
@@ -193,10 +254,20 @@ fn assembly_output_path(src_path: &RelativePathBuf, out_dir: Option<&Path>) -> P
 
 /// Optimizes the specified LLVM `Module` using the default passes for the given
 /// `OptimizationLevel`.
-fn optimize_module(module: &Module, optimization_lvl: OptimizationLevel) {
+pub(crate) fn optimize_module(module: &Module, optimization_lvl: OptimizationLevel) {
     let pass_builder = PassManagerBuilder::create();
     pass_builder.set_optimization_level(optimization_lvl);
 
+    // Use the same inlining thresholds as Clang, which are raised for functions that are declared
+    // `inline`
+    match optimization_lvl {
+        OptimizationLevel::None => (),
+        OptimizationLevel::Less | OptimizationLevel::Default => {
+            pass_builder.set_inliner_with_threshold(225)
+        }
+        OptimizationLevel::Aggressive => pass_builder.set_inliner_with_threshold(250),
+    }
+
     let module_pass_manager = PassManager::create(());
     pass_builder.populate_module_pass_manager(&module_pass_manager);
     module_pass_manager.run_on(module);
@@ -274,7 +345,11 @@ pub(crate) fn gen_u16_array(
         u16_type.ptr_type(AddressSpace::Const).const_null()
     } else {
         let integers = integers
-            .map(|i| u16_type.const_int(i, false))
+            .map(|i| {
+                // Larger integers are reported as diagnostics, so code is never generated for them
+                debug_assert!(i <= u64::from(std::u16::MAX), "{} does not fit in a u16", i);
+                u16_type.const_int(i, false)
+            })
             .collect::<Vec<IntValue>>();
 
         let array_ir = u16_type.const_array(&integers);
@@ -36,6 +36,12 @@ pub fn create_with_target(target: &spec::Target) -> Box<dyn Linker> {
 
 pub trait Linker {
     fn add_object(&mut self, path: &Path) -> Result<(), LinkerError>;
+    /// Exports the function `symbol` from the shared object. Linkers that export all global
+    /// symbols by default ignore this.
+    fn add_exported_fn(&mut self, _symbol: &str) {}
+    /// Exports the global variable `symbol` from the shared object. Linkers that export all
+    /// global symbols by default ignore this.
+    fn add_exported_global(&mut self, _symbol: &str) {}
     fn build_shared_object(&mut self, path: &Path) -> Result<(), LinkerError>;
     fn finalize(&mut self) -> Result<(), LinkerError>;
 }
@@ -162,6 +168,14 @@ impl Linker for MsvcLinker {
         Ok(())
     }
 
+    fn add_exported_fn(&mut self, symbol: &str) {
+        self.args.push(format!("/EXPORT:{}", symbol));
+    }
+
+    fn add_exported_global(&mut self, symbol: &str) {
+        self.args.push(format!("/EXPORT:{},DATA", symbol));
+    }
+
     fn build_shared_object(&mut self, path: &Path) -> Result<(), LinkerError> {
         let dll_path_str = path
             .to_str()
@@ -173,8 +187,6 @@ impl Linker for MsvcLinker {
 
         self.args.push("/DLL".to_owned());
         self.args.push("/NOENTRY".to_owned());
-        self.args.push("/EXPORT:get_info".to_owned());
-        self.args.push("/EXPORT:set_allocator_handle".to_owned());
         self.args.push(format!("/IMPLIB:{}", dll_lib_path_str));
         self.args.push(format!("/OUT:{}", dll_path_str));
         Ok(())
@@ -289,7 +289,104 @@ pub(super) fn gen_reflection_ir(
 
     // Construct the actual `get_info` function
     gen_get_info_fn(db, module, &abi_types, module_info, dispatch_table);
+    gen_get_version_fn(db, module);
+    gen_opt_level(db, module);
     gen_set_allocator_handle_fn(db, module);
+
+    // Export all functions under a stable symbol name
+    gen_exported_fn_symbols(db, module, api.iter());
+
+    // Construct the optional debug information
+    if db.emit_debug_info() {
+        gen_debug_info(db, module, &abi_types, api.iter());
+    }
+}
+
+/// Constructs an exported function for every function in `functions`, which forwards its
+/// arguments to the function. The exported functions are named according to
+/// `abi::fn_symbol_name`, such that they can be loaded through the dynamic linker of the OS.
+fn gen_exported_fn_symbols<'a, D: IrDatabase>(
+    db: &D,
+    module: &Module,
+    functions: impl Iterator<Item = &'a hir::Function>,
+) {
+    let builder = db.context().create_builder();
+    for f in functions {
+        let name = f.name(db).to_string();
+        let value = module
+            // If a wrapper function exists, use that (required for struct types)
+            .get_function(&format!("{}_wrapper", name))
+            // Otherwise, use the normal function
+            .or_else(|| module.get_function(&name))
+            .unwrap();
+
+        let exported_fn = module.add_function(
+            &abi::fn_symbol_name(&name),
+            value.get_type(),
+            Some(Linkage::DLLExport),
+        );
+        let body_ir = db.context().append_basic_block(&exported_fn, "body");
+        builder.position_at_end(&body_ir);
+
+        let args = exported_fn.get_params();
+        let result = builder
+            .build_call(value, &args, "")
+            .try_as_basic_value()
+            .left();
+        match result {
+            Some(result) => builder.build_return(Some(&result)),
+            None => builder.build_return(None),
+        };
+    }
+}
+
+/// Construct an exported global called `debug_info` that holds the source locations of all
+/// functions. e.g.:
+/// ```c
+/// MunDebugInfo debug_info = { ... }
+/// ```
+fn gen_debug_info<'a, D: IrDatabase>(
+    db: &D,
+    module: &Module,
+    types: &AbiTypes,
+    functions: impl Iterator<Item = &'a hir::Function>,
+) {
+    let locations: Vec<StructValue> = functions
+        .map(|f| {
+            let name = f.name(db).to_string();
+            let file = db.file_relative_path(f.module(db).file_id()).to_string();
+            types.function_source_location_type.const_named_struct(&[
+                intern_string(module, &name, &name).into(),
+                intern_string(module, &file, "debug_info::file").into(),
+                module
+                    .get_context()
+                    .i32_type()
+                    .const_int(u64::from(f.source_line(db) + 1), false)
+                    .into(),
+            ])
+        })
+        .collect();
+
+    let num_locations = locations.len();
+    let locations = gen_global(
+        module,
+        &types.function_source_location_type.const_array(&locations),
+        "debug_info::locations",
+    );
+
+    let debug_info = types.debug_info_type.const_named_struct(&[
+        locations.as_pointer_value().into(),
+        module
+            .get_context()
+            .i32_type()
+            .const_int(num_locations as u64, false)
+            .into(),
+    ]);
+
+    let global = module.add_global(types.debug_info_type, None, "debug_info");
+    global.set_linkage(Linkage::DLLExport);
+    global.set_constant(true);
+    global.set_initializer(&debug_info);
 }
 
 /// Construct the actual `get_info` function.
@@ -382,6 +479,47 @@ fn gen_get_info_fn(
     function::create_pass_manager(&module, db.optimization_lvl()).run_on(&get_symbols_fn);
 }
 
+/// Construct the `get_version` function, which returns the `abi::ABI_VERSION` that the assembly
+/// was compiled against. e.g.:
+/// ```c
+/// uint32_t get_version() { return ABI_VERSION; }
+/// ```
+fn gen_get_version_fn(db: &impl IrDatabase, module: &Module) {
+    let context = module.get_context();
+    let get_version_fn_type = context.i32_type().fn_type(&[], false);
+
+    let get_version_fn =
+        module.add_function("get_version", get_version_fn_type, Some(Linkage::DLLExport));
+
+    let builder = db.context().create_builder();
+    let body_ir = db.context().append_basic_block(&get_version_fn, "body");
+    builder.position_at_end(&body_ir);
+
+    builder.build_return(Some(
+        &context
+            .i32_type()
+            .const_int(u64::from(abi::ABI_VERSION), false),
+    ));
+}
+
+/// Construct the exported `opt_level` global, which holds the optimization level that the
+/// assembly was compiled with, from `0` for no optimizations to `3` for aggressive optimizations.
+/// e.g.:
+/// ```c
+/// const uint8_t opt_level = 2;
+/// ```
+fn gen_opt_level(db: &impl IrDatabase, module: &Module) {
+    let context = module.get_context();
+    let opt_level = context
+        .i8_type()
+        .const_int(db.optimization_lvl() as u64, false);
+
+    let global = module.add_global(context.i8_type(), None, "opt_level");
+    global.set_linkage(Linkage::DLLExport);
+    global.set_constant(true);
+    global.set_initializer(&opt_level);
+}
+
 fn gen_set_allocator_handle_fn(db: &impl IrDatabase, module: &Module) {
     let context = module.get_context();
     let allocator_handle_type = context.i8_type().ptr_type(AddressSpace::Generic);
@@ -1,7 +1,7 @@
 #![allow(clippy::type_repetition_in_bounds)]
 
 use crate::{
-    ir::{file::FileIR, file_group::FileGroupIR},
+    ir::{const_eval::ConstValue, file::FileIR, file_group::FileGroupIR},
     type_info::TypeInfo,
     CodeGenParams, Context,
 };
@@ -24,6 +24,10 @@ pub trait IrDatabase: hir::HirDatabase {
     #[salsa::input]
     fn optimization_lvl(&self) -> OptimizationLevel;
 
+    /// Gets whether or not debug information should be emitted for the generated assemblies.
+    #[salsa::input]
+    fn emit_debug_info(&self) -> bool;
+
     /// Returns the target machine's data layout for code generation.
     #[salsa::invoke(crate::code_gen::target_data_query)]
     fn target_data(&self) -> Arc<TargetData>;
@@ -49,4 +53,9 @@ pub trait IrDatabase: hir::HirDatabase {
     /// Given a type, return the runtime `TypeInfo` that can be used to reflect the type.
     #[salsa::invoke(crate::ir::ty::type_info_query)]
     fn type_info(&self, ty: hir::Ty) -> TypeInfo;
+
+    /// Given an expression in the body of a function, return its folded value if the expression
+    /// is a compile-time constant.
+    #[salsa::invoke(crate::ir::const_eval::const_eval_query)]
+    fn const_eval(&self, function: hir::Function, expr: hir::ExprId) -> Option<ConstValue>;
 }
@@ -0,0 +1,33 @@
+use crate::IrDatabase;
+use hir::{diagnostics::DiagnosticSink, FileId, ModuleDef};
+
+/// Reports diagnostics for the structs in the file of `file_id` whose memory layout on the target
+/// cannot be described by the type information that is emitted for them.
+///
+/// The layout is the one that code is generated with, so this requires the file to be free of the
+/// errors that are reported by `hir::Module::diagnostics`.
+pub fn layout_diagnostics(db: &impl IrDatabase, file_id: FileId, sink: &mut DiagnosticSink) {
+    let target_data = db.target_data();
+    for def in db.module_data(file_id).definitions() {
+        if let ModuleDef::Struct(s) = def {
+            let struct_ir = db.struct_ty(*s);
+            let field_offsets: Vec<u64> = (0..s.fields(db).len())
+                .map(|idx| {
+                    target_data
+                        .offset_of_element(&struct_ir, idx as u32)
+                        .unwrap()
+                })
+                .collect();
+
+            // Field offsets are emitted as `u16`s
+            s.layout_diagnostics(
+                db,
+                target_data.get_bit_size(&struct_ir),
+                &field_offsets,
+                u64::from(abi::MAX_TYPE_SIZE_IN_BITS),
+                u64::from(std::u16::MAX),
+                sink,
+            );
+        }
+    }
+}
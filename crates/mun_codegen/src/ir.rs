@@ -9,6 +9,7 @@ use inkwell::AddressSpace;
 pub(crate) mod abi_types;
 pub mod adt;
 pub mod body;
+pub(crate) mod const_eval;
 #[macro_use]
 pub(crate) mod dispatch_table;
 pub mod file;
@@ -15,6 +15,8 @@ pub(crate) struct AbiTypes {
     pub module_info_type: StructType,
     pub dispatch_table_type: StructType,
     pub assembly_info_type: StructType,
+    pub function_source_location_type: StructType,
+    pub debug_info_type: StructType,
 }
 
 /// Returns an `AbiTypes` struct that contains references to all LLVM ABI types.
@@ -87,8 +89,11 @@ pub(crate) fn gen_abi_types(context: &Context) -> AbiTypes {
             str_type.ptr_type(AddressSpace::Const).into(), // field_names
             type_info_ptr_type.ptr_type(AddressSpace::Const).into(), // field_types
             context.i16_type().ptr_type(AddressSpace::Const).into(), // field_offsets
+            str_type.ptr_type(AddressSpace::Const).into(), // field_defaults
+            str_type.ptr_type(AddressSpace::Const).into(), // field_docs
             context.i16_type().into(),                     // num_fields
             context.i8_type().into(),                      // memory_kind
+            context.i32_type().into(),                     // magic
         ],
         false,
     );
@@ -136,6 +141,30 @@ pub(crate) fn gen_abi_types(context: &Context) -> AbiTypes {
         false,
     );
 
+    // Construct the `MunFunctionSourceLocation` struct
+    let function_source_location_type =
+        context.opaque_struct_type("struct.MunFunctionSourceLocation");
+    function_source_location_type.set_body(
+        &[
+            str_type.into(),           // name
+            str_type.into(),           // file
+            context.i32_type().into(), // line
+        ],
+        false,
+    );
+
+    // Construct the `MunDebugInfo` struct
+    let debug_info_type = context.opaque_struct_type("struct.MunDebugInfo");
+    debug_info_type.set_body(
+        &[
+            function_source_location_type
+                .ptr_type(AddressSpace::Const)
+                .into(), // locations
+            context.i32_type().into(), // num_locations
+        ],
+        false,
+    );
+
     AbiTypes {
         guid_type,
         type_group_type,
@@ -148,5 +177,7 @@ pub(crate) fn gen_abi_types(context: &Context) -> AbiTypes {
         module_info_type,
         dispatch_table_type,
         assembly_info_type,
+        function_source_location_type,
+        debug_info_type,
     }
 }
@@ -3,6 +3,11 @@ use crate::ir::try_convert_any_to_basic;
 use crate::{CodeGenParams, IrDatabase};
 use inkwell::types::{BasicTypeEnum, StructType};
 
+/// Generates the body of the IR struct type for the specified HIR struct.
+///
+/// The struct type is not packed, so the target's data layout places all fields according to the
+/// platform's C ABI. The field offsets and size stored in the struct's `TypeInfo` are derived from
+/// the same IR type, which ensures that the ABI metadata matches the generated memory layout.
 pub(super) fn gen_struct_decl(db: &impl IrDatabase, s: hir::Struct) -> StructType {
     let struct_type = db.struct_ty(s);
     if struct_type.is_opaque() {
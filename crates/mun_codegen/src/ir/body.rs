@@ -1028,9 +1028,20 @@ impl<'a, 'b, D: IrDatabase> BodyIrGenerator<'a, 'b, D> {
         !self.params.make_marshallable
     }
 
+    /// Returns whether calls to `function` are made directly rather than through the dispatch
+    /// table, so LLVM can inline them. This applies to `inline` functions in the same file, which
+    /// are always reloaded together with their callers. They keep their dispatch table entry, so
+    /// the runtime can still call them.
+    fn should_call_directly(&self, function: hir::Function) -> bool {
+        function.is_inline(self.db) && self.function_map.contains_key(&function)
+    }
+
     /// Generates IR for a function call.
     fn gen_call(&mut self, function: hir::Function, args: &[BasicValueEnum]) -> CallSiteValue {
-        if self.dispatch_table.contains(function) && self.should_use_dispatch_table() {
+        if self.dispatch_table.contains(function)
+            && self.should_use_dispatch_table()
+            && !self.should_call_directly(function)
+        {
             let ptr_value = self.dispatch_table.gen_function_lookup(
                 self.db,
                 self.external_globals.dispatch_table,
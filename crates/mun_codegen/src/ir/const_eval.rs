@@ -0,0 +1,275 @@
+use crate::IrDatabase;
+use hir::{
+    ArithOp, BinaryOp, Body, CmpOp, Expr, ExprId, FloatBitness, InferenceResult, IntBitness,
+    Literal, LogicOp, Ordering, ResolveBitness, Signedness, TypeCtor, UnaryOp,
+};
+use std::cmp;
+
+/// A compile-time constant value of a fundamental type.
+#[derive(Clone, Copy, Debug)]
+pub enum ConstValue {
+    /// A boolean value
+    Bool(bool),
+    /// A signed integer value
+    Int(i128),
+    /// An unsigned integer value
+    UInt(u128),
+    /// A floating-point value. The value of an `f32` constant is rounded to single precision.
+    Float(f64),
+}
+
+impl PartialEq for ConstValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ConstValue::Bool(lhs), ConstValue::Bool(rhs)) => lhs == rhs,
+            (ConstValue::Int(lhs), ConstValue::Int(rhs)) => lhs == rhs,
+            (ConstValue::UInt(lhs), ConstValue::UInt(rhs)) => lhs == rhs,
+            // Compare the bit patterns, so the equality is reflexive for NaN values
+            (ConstValue::Float(lhs), ConstValue::Float(rhs)) => lhs.to_bits() == rhs.to_bits(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ConstValue {}
+
+/// Returns the folded value of the expression `expr` in the body of `function`, or `None` if the
+/// expression is not a compile-time constant.
+///
+/// The folded value follows the semantics of the generated code: integer arithmetic wraps around
+/// on overflow and `f32` arithmetic is performed with single precision. Operations that are
+/// undefined at runtime, like a division by zero or a shift by more bits than the type has, are
+/// not considered constant.
+pub(crate) fn const_eval_query(
+    db: &impl IrDatabase,
+    function: hir::Function,
+    expr: ExprId,
+) -> Option<ConstValue> {
+    let body = function.body(db);
+    let infer = function.infer(db);
+    ConstEvaluator {
+        db,
+        body: &body,
+        infer: &infer,
+    }
+    .eval(expr)
+}
+
+/// The type of a constant value, which determines how operations are folded.
+#[derive(Clone, Copy)]
+enum ConstType {
+    Bool,
+    Int { bits: u32, signedness: Signedness },
+    Float(FloatBitness),
+}
+
+struct ConstEvaluator<'a, D: IrDatabase> {
+    db: &'a D,
+    body: &'a Body,
+    infer: &'a InferenceResult,
+}
+
+impl<'a, D: IrDatabase> ConstEvaluator<'a, D> {
+    /// Returns the type of the expression, if it is a fundamental type.
+    fn ty(&self, expr: ExprId) -> Option<ConstType> {
+        match self.infer[expr].as_simple()? {
+            TypeCtor::Bool => Some(ConstType::Bool),
+            TypeCtor::Int(ty) => {
+                let ty = ty.resolve(&self.db.target_data_layout());
+                let bits = match ty.bitness {
+                    IntBitness::X8 => 8,
+                    IntBitness::X16 => 16,
+                    IntBitness::X32 => 32,
+                    IntBitness::X64 => 64,
+                    IntBitness::X128 => 128,
+                    IntBitness::Xsize => unreachable!("unresolved bitness in constant evaluation"),
+                };
+                Some(ConstType::Int {
+                    bits,
+                    signedness: ty.signedness,
+                })
+            }
+            TypeCtor::Float(ty) => Some(ConstType::Float(
+                ty.resolve(&self.db.target_data_layout()).bitness,
+            )),
+            _ => None,
+        }
+    }
+
+    fn eval(&self, expr: ExprId) -> Option<ConstValue> {
+        match &self.body[expr] {
+            Expr::Literal(lit) => self.eval_literal(lit, expr),
+            Expr::UnaryOp { expr: operand, op } => {
+                let value = self.eval(*operand)?;
+                eval_unary_op(*op, value, self.ty(expr)?)
+            }
+            Expr::BinaryOp {
+                lhs,
+                rhs,
+                op: Some(op),
+            } => {
+                let lhs_value = self.eval(*lhs)?;
+                let rhs_value = self.eval(*rhs)?;
+                match op {
+                    BinaryOp::ArithOp(op) => {
+                        eval_arith_op(*op, lhs_value, rhs_value, self.ty(expr)?)
+                    }
+                    BinaryOp::CmpOp(op) => eval_cmp_op(*op, lhs_value, rhs_value),
+                    BinaryOp::LogicOp(op) => eval_logic_op(*op, lhs_value, rhs_value),
+                    BinaryOp::Assignment { .. } => None,
+                }
+            }
+            Expr::Block {
+                statements,
+                tail: Some(tail),
+            } if statements.is_empty() => self.eval(*tail),
+            Expr::If {
+                condition,
+                then_branch,
+                else_branch: Some(else_branch),
+            } => match self.eval(*condition)? {
+                ConstValue::Bool(true) => self.eval(*then_branch),
+                ConstValue::Bool(false) => self.eval(*else_branch),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn eval_literal(&self, lit: &Literal, expr: ExprId) -> Option<ConstValue> {
+        match (lit, self.ty(expr)?) {
+            (Literal::Bool(value), ConstType::Bool) => Some(ConstValue::Bool(*value)),
+            (Literal::Int(lit), ty @ ConstType::Int { .. }) => {
+                Some(wrap(ConstValue::UInt(lit.value), ty))
+            }
+            (Literal::Float(lit), ty @ ConstType::Float(_)) => {
+                Some(wrap(ConstValue::Float(lit.value), ty))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Truncates the `value` to the precision of the type `ty`.
+fn wrap(value: ConstValue, ty: ConstType) -> ConstValue {
+    match (value, ty) {
+        (ConstValue::Int(value), ConstType::Int { bits, signedness }) => wrap(
+            ConstValue::UInt(value as u128),
+            ConstType::Int { bits, signedness },
+        ),
+        (ConstValue::UInt(value), ConstType::Int { bits, signedness }) => {
+            let shift = 128 - bits;
+            match signedness {
+                Signedness::Signed => ConstValue::Int(((value << shift) as i128) >> shift),
+                Signedness::Unsigned => ConstValue::UInt((value << shift) >> shift),
+            }
+        }
+        (ConstValue::Float(value), ConstType::Float(FloatBitness::X32)) => {
+            ConstValue::Float(f64::from(value as f32))
+        }
+        (value, _) => value,
+    }
+}
+
+fn eval_unary_op(op: UnaryOp, value: ConstValue, ty: ConstType) -> Option<ConstValue> {
+    let result = match (op, value) {
+        (UnaryOp::Not, ConstValue::Bool(value)) => ConstValue::Bool(!value),
+        (UnaryOp::Not, ConstValue::Int(value)) => ConstValue::Int(!value),
+        (UnaryOp::Not, ConstValue::UInt(value)) => ConstValue::UInt(!value),
+        (UnaryOp::Neg, ConstValue::Int(value)) => ConstValue::Int(value.wrapping_neg()),
+        (UnaryOp::Neg, ConstValue::Float(value)) => ConstValue::Float(-value),
+        _ => return None,
+    };
+    Some(wrap(result, ty))
+}
+
+fn eval_arith_op(
+    op: ArithOp,
+    lhs: ConstValue,
+    rhs: ConstValue,
+    ty: ConstType,
+) -> Option<ConstValue> {
+    let result = match (lhs, rhs, ty) {
+        (ConstValue::Bool(lhs), ConstValue::Bool(rhs), _) => match op {
+            ArithOp::BitAnd => ConstValue::Bool(lhs & rhs),
+            ArithOp::BitOr => ConstValue::Bool(lhs | rhs),
+            ArithOp::BitXor => ConstValue::Bool(lhs ^ rhs),
+            _ => return None,
+        },
+        (ConstValue::Int(lhs), ConstValue::Int(rhs), ConstType::Int { bits, .. }) => {
+            let min = -1i128 << (bits - 1);
+            ConstValue::Int(match op {
+                ArithOp::Add => lhs.wrapping_add(rhs),
+                ArithOp::Subtract => lhs.wrapping_sub(rhs),
+                ArithOp::Multiply => lhs.wrapping_mul(rhs),
+                ArithOp::Divide if rhs != 0 && !(lhs == min && rhs == -1) => lhs / rhs,
+                ArithOp::Remainder if rhs != 0 && !(lhs == min && rhs == -1) => lhs % rhs,
+                ArithOp::LeftShift if (0..i128::from(bits)).contains(&rhs) => lhs << rhs,
+                ArithOp::RightShift if (0..i128::from(bits)).contains(&rhs) => lhs >> rhs,
+                ArithOp::BitAnd => lhs & rhs,
+                ArithOp::BitOr => lhs | rhs,
+                ArithOp::BitXor => lhs ^ rhs,
+                _ => return None,
+            })
+        }
+        (ConstValue::UInt(lhs), ConstValue::UInt(rhs), ConstType::Int { bits, .. }) => {
+            ConstValue::UInt(match op {
+                ArithOp::Add => lhs.wrapping_add(rhs),
+                ArithOp::Subtract => lhs.wrapping_sub(rhs),
+                ArithOp::Multiply => lhs.wrapping_mul(rhs),
+                ArithOp::Divide if rhs != 0 => lhs / rhs,
+                ArithOp::Remainder if rhs != 0 => lhs % rhs,
+                ArithOp::LeftShift if rhs < u128::from(bits) => lhs << rhs,
+                ArithOp::RightShift if rhs < u128::from(bits) => lhs >> rhs,
+                ArithOp::BitAnd => lhs & rhs,
+                ArithOp::BitOr => lhs | rhs,
+                ArithOp::BitXor => lhs ^ rhs,
+                _ => return None,
+            })
+        }
+        (ConstValue::Float(lhs), ConstValue::Float(rhs), _) => ConstValue::Float(match op {
+            ArithOp::Add => lhs + rhs,
+            ArithOp::Subtract => lhs - rhs,
+            ArithOp::Multiply => lhs * rhs,
+            ArithOp::Divide => lhs / rhs,
+            ArithOp::Remainder => lhs % rhs,
+            _ => return None,
+        }),
+        _ => return None,
+    };
+    Some(wrap(result, ty))
+}
+
+fn eval_cmp_op(op: CmpOp, lhs: ConstValue, rhs: ConstValue) -> Option<ConstValue> {
+    let ordering = match (lhs, rhs) {
+        (ConstValue::Bool(lhs), ConstValue::Bool(rhs)) => lhs.partial_cmp(&rhs),
+        (ConstValue::Int(lhs), ConstValue::Int(rhs)) => lhs.partial_cmp(&rhs),
+        (ConstValue::UInt(lhs), ConstValue::UInt(rhs)) => lhs.partial_cmp(&rhs),
+        (ConstValue::Float(lhs), ConstValue::Float(rhs)) => lhs.partial_cmp(&rhs),
+        _ => return None,
+    };
+
+    // Comparisons with NaN are unordered, which always results in `false`
+    let result = ordering.map_or(false, |ordering| match op {
+        CmpOp::Eq { negated } => (ordering == cmp::Ordering::Equal) != negated,
+        CmpOp::Ord {
+            ordering: Ordering::Less,
+            strict,
+        } => ordering == cmp::Ordering::Less || (!strict && ordering == cmp::Ordering::Equal),
+        CmpOp::Ord {
+            ordering: Ordering::Greater,
+            strict,
+        } => ordering == cmp::Ordering::Greater || (!strict && ordering == cmp::Ordering::Equal),
+    });
+    Some(ConstValue::Bool(result))
+}
+
+fn eval_logic_op(op: LogicOp, lhs: ConstValue, rhs: ConstValue) -> Option<ConstValue> {
+    match (lhs, rhs) {
+        (ConstValue::Bool(lhs), ConstValue::Bool(rhs)) => Some(ConstValue::Bool(match op {
+            LogicOp::And => lhs && rhs,
+            LogicOp::Or => lhs || rhs,
+        })),
+        _ => None,
+    }
+}
@@ -22,6 +22,7 @@ pub(crate) fn ir_query(db: &impl IrDatabase, file_id: FileId) -> Arc<FileIR> {
     let llvm_module = db
         .context()
         .create_module(db.file_relative_path(file_id).as_str());
+    llvm_module.set_data_layout(&db.target_data().get_data_layout());
 
     let group_ir = db.group_ir(file_id);
 
@@ -31,6 +31,7 @@ pub struct FileGroupIR {
 /// files using something like `FileGroupId`.
 pub(crate) fn ir_query(db: &impl IrDatabase, file_id: hir::FileId) -> Arc<FileGroupIR> {
     let llvm_module = db.context().create_module("group_name");
+    llvm_module.set_data_layout(&db.target_data().get_data_layout());
 
     // Use a `BTreeMap` to guarantee deterministically ordered output.
     let mut intrinsics_map = BTreeMap::new();
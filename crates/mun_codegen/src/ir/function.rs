@@ -1,6 +1,7 @@
 use crate::ir::{body::BodyIrGenerator, dispatch_table::DispatchTable, type_table::TypeTable};
 use crate::values::FunctionValue;
 use crate::{CodeGenParams, IrDatabase, Module, OptimizationLevel};
+use inkwell::attributes::{Attribute, AttributeLoc};
 use inkwell::passes::{PassManager, PassManagerBuilder};
 use inkwell::types::AnyTypeEnum;
 
@@ -41,11 +42,26 @@ pub(crate) fn gen_signature(
         }
     };
 
-    if let AnyTypeEnum::FunctionType(ty) = db.type_ir(f.ty(db), params) {
+    let is_wrapper = params.make_marshallable;
+    let function = if let AnyTypeEnum::FunctionType(ty) = db.type_ir(f.ty(db), params) {
         module.add_function(&name, ty, None)
     } else {
         panic!("not a function type")
+    };
+
+    // The hint only affects calls within the assembly. The function itself is still emitted, so
+    // it can be called through the runtime's function and dispatch tables. Its wrapper is not
+    // hinted, so the function is inlined into the wrapper instead.
+    if f.is_inline(db) && !is_wrapper {
+        function.add_attribute(
+            AttributeLoc::Function,
+            module
+                .get_context()
+                .create_enum_attribute(Attribute::get_named_enum_kind_id("inlinehint"), 0),
+        );
     }
+
+    function
 }
 
 /// Generates the body of a `hir::Function` for an associated `FunctionValue`.
@@ -83,9 +83,21 @@ fn int_ty_query(db: &impl IrDatabase, ity: IntTy) -> IntType {
 pub fn struct_ty_query(db: &impl IrDatabase, s: hir::Struct) -> StructType {
     let name = s.name(db).to_string();
     for field in s.fields(db).iter() {
+        // A field of a garbage collected struct type is stored as a pointer to the struct's
+        // opaque forward declaration, so the field's type does not have to be known. This allows
+        // a struct to reference itself through a `struct(gc)` field.
+        let field_ty = field.ty(db);
+        let is_gc_struct = field_ty
+            .as_struct()
+            .map(|s| s.data(db).memory_kind == hir::StructMemoryKind::GC)
+            .unwrap_or(false);
+        if is_gc_struct {
+            continue;
+        }
+
         // Ensure that salsa's cached value incorporates the struct fields
         let _field_type_ir = db.type_ir(
-            field.ty(db),
+            field_ty,
             CodeGenParams {
                 make_marshallable: false,
             },
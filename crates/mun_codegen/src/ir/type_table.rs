@@ -7,12 +7,12 @@ use crate::ir::{
 };
 use crate::type_info::{TypeGroup, TypeInfo};
 use crate::IrDatabase;
-use hir::{Body, ExprId, InferenceResult};
+use hir::{Body, ExprId, FieldDefault, InferenceResult, Literal, ResolveBitness, TypeCtor};
 use inkwell::{
-    module::Module,
+    module::{Linkage, Module},
     targets::TargetData,
     types::ArrayType,
-    values::{GlobalValue, IntValue, PointerValue, StructValue},
+    values::{BasicValueEnum, GlobalValue, IntValue, PointerValue, StructValue, UnnamedAddress},
     AddressSpace,
 };
 use std::collections::{BTreeSet, HashMap};
@@ -64,6 +64,20 @@ impl TypeTable {
         module.get_global(&type_info_global_name(type_info))
     }
 
+    /// Returns the types in the `TypeTable`, in the order of their indices.
+    pub fn type_infos(&self) -> Vec<&TypeInfo> {
+        let mut type_infos: Vec<(&TypeInfo, usize)> = self
+            .type_info_to_index
+            .iter()
+            .map(|(type_info, index)| (type_info, *index))
+            .collect();
+        type_infos.sort_by_key(|(_, index)| *index);
+        type_infos
+            .into_iter()
+            .map(|(type_info, _)| type_info)
+            .collect()
+    }
+
     /// Returns the number of types in the `TypeTable`.
     pub fn num_types(&self) -> usize {
         self.entries.len()
@@ -166,7 +180,10 @@ impl<'a, D: IrDatabase> TypeTableBuilder<'a, D> {
     /// Collects unique `TypeInfo` from the specified struct type.
     pub fn collect_struct(&mut self, hir_struct: hir::Struct) {
         let type_info = self.db.type_info(hir_struct.ty(self.db));
-        self.entries.insert(type_info);
+        if !self.entries.insert(type_info) {
+            // The struct was already collected, e.g. because it references itself
+            return;
+        }
 
         let fields = hir_struct.fields(self.db);
         for field in fields.into_iter() {
@@ -174,12 +191,27 @@ impl<'a, D: IrDatabase> TypeTableBuilder<'a, D> {
         }
     }
 
+    /// Returns the global that contains the IR of `type_info`, generating it if it does not exist
+    /// yet.
     fn gen_type_info(
         &self,
         type_info_to_ir: &mut HashMap<TypeInfo, GlobalValue>,
         type_info: &TypeInfo,
     ) -> GlobalValue {
+        if let Some(ir_value) = type_info_to_ir.get(type_info) {
+            return *ir_value;
+        }
+
         let context = self.module.get_context();
+        let size_in_bits = type_info.size.bit_size;
+        // Larger structs are reported as diagnostics, so code is never generated for them
+        debug_assert!(
+            size_in_bits <= u64::from(abi::MAX_TYPE_SIZE_IN_BITS),
+            "type `{}` is {} bits large, which exceeds the maximum of {} bits",
+            type_info.name,
+            size_in_bits,
+            abi::MAX_TYPE_SIZE_IN_BITS
+        );
         let guid_bytes_ir: [IntValue; 16] = array_init::array_init(|i| {
             context
                 .i8_type()
@@ -193,10 +225,7 @@ impl<'a, D: IrDatabase> TypeTableBuilder<'a, D> {
                 &format!("type_info::<{}>::name", type_info.name),
             )
             .into(),
-            context
-                .i32_type()
-                .const_int(type_info.size.bit_size, false)
-                .into(),
+            context.i32_type().const_int(size_in_bits, false).into(),
             context
                 .i8_type()
                 .const_int(type_info.size.alignment as u64, false)
@@ -206,18 +235,41 @@ impl<'a, D: IrDatabase> TypeTableBuilder<'a, D> {
                 .const_int(type_info.group.clone().into(), false)
                 .into(),
         ]);
-        let type_info_ir = match type_info.group {
-            TypeGroup::FundamentalTypes => type_info_ir,
+        match type_info.group {
+            TypeGroup::FundamentalTypes => {
+                let global = gen_global(
+                    self.module,
+                    &type_info_ir,
+                    &type_info_global_name(type_info),
+                );
+                type_info_to_ir.insert(type_info.clone(), global);
+                global
+            }
             TypeGroup::StructTypes(s) => {
+                // The global is declared before its initializer is generated, as a struct can
+                // reference its own type through a `struct(gc)` field.
+                let global_type = context.struct_type(
+                    &[
+                        self.abi_types.type_info_type.into(),
+                        self.abi_types.struct_info_type.into(),
+                    ],
+                    false,
+                );
+                let global =
+                    self.module
+                        .add_global(global_type, None, &type_info_global_name(type_info));
+                global.set_linkage(Linkage::Private);
+                global.set_constant(true);
+                global.set_unnamed_address(UnnamedAddress::Global);
+                type_info_to_ir.insert(type_info.clone(), global);
+
                 let struct_info_ir = self.gen_struct_info(type_info_to_ir, s);
-                context.const_struct(&[type_info_ir.into(), struct_info_ir.into()], false)
+                global.set_initializer(
+                    &context.const_struct(&[type_info_ir.into(), struct_info_ir.into()], false),
+                );
+                global
             }
-        };
-        gen_global(
-            self.module,
-            &type_info_ir,
-            &type_info_global_name(type_info),
-        )
+        }
     }
 
     fn gen_struct_info(
@@ -238,14 +290,8 @@ impl<'a, D: IrDatabase> TypeTableBuilder<'a, D> {
             .iter()
             .map(|field| {
                 let field_type_info = self.db.type_info(field.ty(self.db));
-                if let Some(ir_value) = type_info_to_ir.get(&field_type_info) {
-                    *ir_value
-                } else {
-                    let ir_value = self.gen_type_info(type_info_to_ir, &field_type_info);
-                    type_info_to_ir.insert(field_type_info, ir_value);
-                    ir_value
-                }
-                .as_pointer_value()
+                self.gen_type_info(type_info_to_ir, &field_type_info)
+                    .as_pointer_value()
             })
             .collect();
 
@@ -266,10 +312,15 @@ impl<'a, D: IrDatabase> TypeTableBuilder<'a, D> {
             &format!("struct_info::<{}>::field_offsets", name),
         );
 
+        let field_defaults = self.gen_field_defaults(&name, &fields);
+        let field_docs = self.gen_field_docs(&name, &fields);
+
         self.abi_types.struct_info_type.const_named_struct(&[
             field_names.into(),
             field_types.into(),
             field_offsets.into(),
+            field_defaults.into(),
+            field_docs.into(),
             self.module
                 .get_context()
                 .i16_type()
@@ -280,9 +331,133 @@ impl<'a, D: IrDatabase> TypeTableBuilder<'a, D> {
                 .i8_type()
                 .const_int(hir_struct.data(self.db).memory_kind.clone().into(), false)
                 .into(),
+            self.module
+                .get_context()
+                .i32_type()
+                .const_int(abi::STRUCT_INFO_MAGIC.into(), false)
+                .into(),
         ])
     }
 
+    /// Generates a global array of pointers to the default values of the struct's `fields`. If
+    /// none of the fields has a default value, a null pointer is returned instead.
+    fn gen_field_defaults(&self, struct_name: &str, fields: &[hir::StructField]) -> PointerValue {
+        let str_type = self
+            .module
+            .get_context()
+            .i8_type()
+            .ptr_type(AddressSpace::Const);
+
+        let defaults: Vec<Option<PointerValue>> = fields
+            .iter()
+            .map(|field| {
+                let value = self.gen_field_default(field)?;
+                let name = format!(
+                    "struct_info::<{}>::field_defaults::{}",
+                    struct_name,
+                    field.name(self.db)
+                );
+                Some(gen_global(self.module, &value, &name).as_pointer_value())
+            })
+            .collect();
+
+        if defaults.iter().all(Option::is_none) {
+            str_type.ptr_type(AddressSpace::Const).const_null()
+        } else {
+            let defaults: Vec<PointerValue> = defaults
+                .into_iter()
+                .map(|default| default.unwrap_or_else(|| str_type.const_null()))
+                .collect();
+
+            let defaults_ir = str_type.const_array(&defaults);
+            gen_global(
+                self.module,
+                &defaults_ir,
+                &format!("struct_info::<{}>::field_defaults", struct_name),
+            )
+            .as_pointer_value()
+        }
+    }
+
+    /// Generates a global array of pointers to the documentation of the struct's `fields`. If none
+    /// of the fields is documented, a null pointer is returned instead.
+    fn gen_field_docs(&self, struct_name: &str, fields: &[hir::StructField]) -> PointerValue {
+        let str_type = self
+            .module
+            .get_context()
+            .i8_type()
+            .ptr_type(AddressSpace::Const);
+
+        let docs: Vec<Option<String>> = fields.iter().map(|field| field.doc(self.db)).collect();
+        if docs.iter().all(Option::is_none) {
+            str_type.ptr_type(AddressSpace::Const).const_null()
+        } else {
+            let name = format!("struct_info::<{}>::field_docs", struct_name);
+            let docs: Vec<PointerValue> = docs
+                .into_iter()
+                .map(|doc| {
+                    doc.map_or_else(
+                        || str_type.const_null(),
+                        |doc| intern_string(self.module, &doc, &name),
+                    )
+                })
+                .collect();
+
+            let docs_ir = str_type.const_array(&docs);
+            gen_global(self.module, &docs_ir, "").as_pointer_value()
+        }
+    }
+
+    /// Generates the IR value of the declared default value of a `field`, if it has one. Default
+    /// values that do not match the type of the field, or are out of range of it, are reported by
+    /// `hir::Struct::diagnostics`, so code is never generated for them.
+    fn gen_field_default(&self, field: &hir::StructField) -> Option<BasicValueEnum> {
+        let FieldDefault { negated, literal } = field.default_value(self.db)?;
+        let context = self.module.get_context();
+
+        match (field.ty(self.db).as_simple()?, literal) {
+            (TypeCtor::Bool, Literal::Bool(value)) if !negated => {
+                let ty = context.bool_type();
+                Some(if value {
+                    ty.const_all_ones().into()
+                } else {
+                    ty.const_zero().into()
+                })
+            }
+            (TypeCtor::Int(int_ty), Literal::Int(lit)) => {
+                let value = if negated {
+                    lit.value.wrapping_neg()
+                } else {
+                    lit.value
+                };
+
+                let ty = match int_ty.resolve(&self.db.target_data_layout()).bitness {
+                    hir::IntBitness::X8 => context.i8_type(),
+                    hir::IntBitness::X16 => context.i16_type(),
+                    hir::IntBitness::X32 => context.i32_type(),
+                    hir::IntBitness::X64 => context.i64_type(),
+                    hir::IntBitness::X128 => context.i128_type(),
+                    _ => unreachable!("unresolved bitness in code generation"),
+                };
+
+                Some(
+                    ty.const_int_arbitrary_precision(&[value as u64, (value >> 64) as u64])
+                        .into(),
+                )
+            }
+            (TypeCtor::Float(float_ty), Literal::Float(lit)) => {
+                let value = if negated { -lit.value } else { lit.value };
+                let ty = match float_ty.bitness.resolve(&self.db.target_data_layout()) {
+                    hir::FloatBitness::X32 => context.f32_type(),
+                    hir::FloatBitness::X64 => context.f64_type(),
+                };
+
+                Some(ty.const_float(value).into())
+            }
+            _ => None,
+        }
+    }
+
     /// Constructs a `TypeTable` from all *used* types.
     pub fn build(mut self) -> TypeTable {
         let mut entries = BTreeSet::new();
@@ -303,14 +478,9 @@ impl<'a, D: IrDatabase> TypeTableBuilder<'a, D> {
             .into_iter()
             .enumerate()
             .map(|(index, type_info)| {
-                let ptr = if let Some(ir_value) = type_info_to_ir.get(&type_info) {
-                    *ir_value
-                } else {
-                    let ir_value = self.gen_type_info(&mut type_info_to_ir, &type_info);
-                    type_info_to_ir.insert(type_info.clone(), ir_value);
-                    ir_value
-                }
-                .as_pointer_value();
+                let ptr = self
+                    .gen_type_info(&mut type_info_to_ir, &type_info)
+                    .as_pointer_value();
 
                 type_info_to_index.insert(type_info, index);
                 ptr
@@ -1,6 +1,7 @@
 /// This library generates machine code from HIR using inkwell which is a safe wrapper around LLVM.
 mod code_gen;
 mod db;
+mod diagnostics;
 #[macro_use]
 mod ir;
 
@@ -17,6 +18,9 @@ pub use inkwell::{builder, context::Context, module::Module, values, Optimizatio
 pub use crate::{
     code_gen::ModuleBuilder,
     db::{IrDatabase, IrDatabaseStorage},
+    diagnostics::layout_diagnostics,
+    ir::const_eval::ConstValue,
+    type_info::{emitted_type_infos, EmittedFieldInfo, EmittedTypeInfo},
 };
 
 #[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
@@ -47,6 +47,7 @@ impl MockDatabase {
 
         db.set_source_root(source_root_id, Arc::new(source_root));
         db.set_optimization_lvl(OptimizationLevel::None);
+        db.set_emit_debug_info(false);
 
         let context = crate::Context::create();
         db.set_context(Arc::new(context));
@@ -1,9 +1,17 @@
-use crate::{mock::MockDatabase, IrDatabase, ModuleBuilder};
+use crate::{
+    emitted_type_infos, layout_diagnostics, mock::MockDatabase, ConstValue, IrDatabase,
+    ModuleBuilder,
+};
 use hir::{
-    diagnostics::DiagnosticSink, line_index::LineIndex, HirDatabase, Module, SourceDatabase,
+    diagnostics::DiagnosticSink, line_index::LineIndex, HirDatabase, Module, ModuleDef,
+    SourceDatabase,
+};
+use inkwell::{
+    attributes::{Attribute, AttributeLoc},
+    values::InstructionOpcode,
+    OptimizationLevel,
 };
-use inkwell::OptimizationLevel;
-use mun_target::spec::Target;
+use mun_target::spec::{RelocModel, Target};
 use std::cell::RefCell;
 use std::sync::Arc;
 
@@ -805,6 +813,74 @@ fn nested_structs() {
     );
 }
 
+#[test]
+fn recursive_value_struct() {
+    test_snapshot(
+        r#"
+    struct(value) Foo { a: i32, foo: Foo }
+    struct(gc) Bar { bar: Bar }
+    "#,
+    );
+}
+
+#[test]
+fn invalid_field_defaults() {
+    test_snapshot(
+        r#"
+    struct Foo {
+        a: i32 = true,
+        b: u8 = -1,
+        c: i8 = -128,
+        d: i8 = -129,
+        e: bool = -true,
+        f: f32 = 1,
+        g: i32 = 1 + 2,
+    }
+    "#,
+    );
+}
+
+#[test]
+fn field_offset_too_large() {
+    test_snapshot(
+        r#"
+    struct(value) A0 { a: i128, b: i128 }
+    struct(value) A1 { a: A0, b: A0, c: A0, d: A0 }
+    struct(value) A2 { a: A1, b: A1, c: A1, d: A1 }
+    struct(value) A3 { a: A2, b: A2, c: A2, d: A2 }
+    struct(value) A4 { a: A3, b: A3, c: A3, d: A3 }
+    struct(value) A5 { a: A4, b: A4, c: A4, d: A4 }
+    struct(gc) Foo { a: A5, b: A5, c: A5 }
+    "#,
+    );
+}
+
+#[test]
+fn non_pic_relocation_model() {
+    let (mut db, file_id) = MockDatabase::with_single_file("pub fn main() {}");
+    let mut target = Target::host_target().unwrap();
+    for relocation_model in [RelocModel::DynamicNoPic, RelocModel::Static]
+        .iter()
+        .copied()
+    {
+        target.options.relocation_model = relocation_model;
+        db.set_target(target.clone());
+
+        let result = ModuleBuilder::new(&db, file_id);
+        if target.options.is_like_windows {
+            assert!(result.is_ok());
+        } else {
+            assert_eq!(
+                result.err().map(|e| e.to_string()),
+                Some(format!(
+                    "relocation model `{:?}` cannot be used for shared libraries on target `{}`",
+                    relocation_model, target.llvm_target
+                ))
+            );
+        }
+    }
+}
+
 #[test]
 fn nested_private_fn() {
     test_snapshot(
@@ -824,6 +900,270 @@ fn nested_private_fn() {
     );
 }
 
+#[test]
+fn const_eval_arithmetic() {
+    assert_eq!(
+        const_eval("pub fn main() -> i32 { 1 + 2 * 3 - 8 / 4 }"),
+        Some(ConstValue::Int(5))
+    );
+    assert_eq!(
+        const_eval("pub fn main() -> u8 { 250 + 10 }"),
+        Some(ConstValue::UInt(4))
+    );
+    assert_eq!(
+        const_eval("pub fn main() -> i8 { 127 + 1 }"),
+        Some(ConstValue::Int(-128))
+    );
+    assert_eq!(
+        const_eval("pub fn main() -> f64 { 1.5 * 2.0 }"),
+        Some(ConstValue::Float(3.0))
+    );
+    assert_eq!(
+        const_eval("pub fn main() -> f32 { 0.1 }"),
+        Some(ConstValue::Float(f64::from(0.1f32)))
+    );
+}
+
+#[test]
+fn const_eval_logic() {
+    assert_eq!(
+        const_eval("pub fn main() -> bool { 1 < 2 && !(3 == 4) }"),
+        Some(ConstValue::Bool(true))
+    );
+    assert_eq!(
+        const_eval("pub fn main() -> i32 { if 2 > 1 { 5 } else { 6 } }"),
+        Some(ConstValue::Int(5))
+    );
+}
+
+#[test]
+fn const_eval_non_constant() {
+    assert_eq!(const_eval("pub fn main(a: i32) -> i32 { a + 1 }"), None);
+    assert_eq!(const_eval("pub fn main() -> i32 { 1 / 0 }"), None);
+    assert_eq!(const_eval("pub fn main() -> i32 { 1 << 32 }"), None);
+    assert_eq!(const_eval("pub fn main() -> i32 { let a = 1; a }"), None);
+}
+
+#[test]
+fn emitted_type_infos_layout() {
+    let (mut db, file_id) = MockDatabase::with_single_file(
+        "struct(value) Foo { a: i32, b: f64 }\npub fn foo_new() -> Foo { Foo { a: 1, b: 2.0 } }",
+    );
+    db.set_target(Target::host_target().unwrap());
+
+    let type_infos = emitted_type_infos(&db, file_id);
+    let foo = type_infos
+        .iter()
+        .find(|ty| ty.name == "Foo")
+        .expect("missing `Foo` type");
+    assert_eq!(foo.group, abi::TypeGroup::StructTypes);
+    assert_eq!(foo.memory_kind, Some(abi::StructMemoryKind::Value));
+    assert_eq!(foo.size_in_bits, 128);
+    assert_eq!(foo.alignment, 8);
+
+    let fields: Vec<(&str, &str, u64)> = foo
+        .fields
+        .iter()
+        .map(|field| (field.name.as_str(), field.type_name.as_str(), field.offset))
+        .collect();
+    assert_eq!(fields, [("a", "core::i32", 0), ("b", "core::f64", 8)]);
+
+    // The compiler and the runtime agree on the GUIDs of fundamental types
+    use abi::HasStaticTypeInfo;
+    let i32_type = type_infos
+        .iter()
+        .find(|ty| ty.name == "core::i32")
+        .expect("missing `core::i32` type");
+    assert_eq!(i32_type.guid, i32::type_info().guid);
+    assert_eq!(foo.fields[0].type_guid, i32::type_info().guid);
+    assert_eq!(i32_type.memory_kind, None);
+    assert!(i32_type.fields.is_empty());
+}
+
+#[test]
+fn modules_use_target_data_layout() {
+    let (mut db, file_id) = MockDatabase::with_single_file("pub fn main() -> i128 { 5 }");
+    db.set_target(Target::host_target().unwrap());
+
+    let data_layout = db.target().data_layout;
+    let module_data_layout = |module: &inkwell::module::Module| {
+        module
+            .get_data_layout()
+            .as_str()
+            .to_str()
+            .unwrap()
+            .to_owned()
+    };
+    assert_eq!(
+        module_data_layout(&db.file_ir(file_id).llvm_module),
+        data_layout
+    );
+    assert_eq!(
+        module_data_layout(&db.group_ir(file_id).llvm_module),
+        data_layout
+    );
+}
+
+#[test]
+fn reproducible_guids() {
+    let source = r#"
+    struct Foo { a: i32, bar: Bar, baz: Baz }
+    struct Bar { b: f64 }
+    struct(value) Baz { c: u8, d: bool }
+
+    pub fn foo_new() -> Foo { Foo { a: 1, bar: Bar { b: 2.0 }, baz: Baz { c: 3, d: true } } }
+    "#;
+    let guids = |source: &str| {
+        let (mut db, file_id) = MockDatabase::with_single_file(source);
+        db.set_target(Target::host_target().unwrap());
+        emitted_type_infos(&db, file_id)
+            .into_iter()
+            .map(|ty| (ty.name, ty.guid))
+            .collect::<std::collections::HashMap<_, _>>()
+    };
+
+    // Compiling identical source twice yields identical GUIDs
+    let first = guids(source);
+    assert_eq!(first, guids(source));
+
+    // GUIDs are derived from the fields in declaration order
+    let foo_guid = md5::compute(
+        "struct Foo{a: core::i32,bar: struct Bar,baz: struct Baz{c: core::u8,d: core::bool}}",
+    )
+    .0;
+    assert_eq!(first["Foo"].b, foo_guid);
+}
+
+#[test]
+fn type_group_abi_mapping() {
+    let (mut db, file_id) = MockDatabase::with_single_file(
+        "struct Foo { a: i32 }\npub fn foo_new() -> Foo { Foo { a: 1 } }",
+    );
+    db.set_target(Target::host_target().unwrap());
+
+    let group_ir = db.group_ir(file_id);
+    let groups: Vec<abi::TypeGroup> = group_ir
+        .type_table
+        .type_infos()
+        .into_iter()
+        .map(|type_info| {
+            let group = abi::TypeGroup::from(&type_info.group);
+            assert_eq!(u64::from(type_info.group.clone()), group as u64);
+            group
+        })
+        .collect();
+    assert!(groups.contains(&abi::TypeGroup::FundamentalTypes));
+    assert!(groups.contains(&abi::TypeGroup::StructTypes));
+}
+
+#[test]
+fn self_referential_gc_struct() {
+    let (mut db, file_id) = MockDatabase::with_single_file(
+        "struct(gc) Node { value: i32, next: Node }\npub fn node_next(node: Node) -> Node { node.next }",
+    );
+    db.set_target(Target::host_target().unwrap());
+
+    let type_infos = emitted_type_infos(&db, file_id);
+    let node = type_infos
+        .iter()
+        .find(|ty| ty.name == "Node")
+        .expect("missing `Node` type");
+    assert_eq!(node.memory_kind, Some(abi::StructMemoryKind::GC));
+
+    let fields: Vec<(&str, &str)> = node
+        .fields
+        .iter()
+        .map(|field| (field.name.as_str(), field.type_name.as_str()))
+        .collect();
+    assert_eq!(fields, [("value", "core::i32"), ("next", "Node")]);
+    assert_eq!(node.fields[1].type_guid, node.guid);
+}
+
+#[test]
+fn inline_fn_hint() {
+    let (mut db, file_id) = MockDatabase::with_single_file(
+        "inline fn add(a: i32, b: i32) -> i32 { a + b }\npub fn main() -> i32 { add(1, 2) }",
+    );
+    db.set_target(Target::host_target().unwrap());
+
+    let file_ir = db.file_ir(file_id);
+    let kind_id = Attribute::get_named_enum_kind_id("inlinehint");
+    let has_hint = |name: &str| {
+        file_ir
+            .llvm_module
+            .get_function(name)
+            .expect("missing function")
+            .get_enum_attribute(AttributeLoc::Function, kind_id)
+            .is_some()
+    };
+    assert!(has_hint("add"));
+    assert!(!has_hint("main"));
+}
+
+#[test]
+fn inline_fn_is_inlined() {
+    let (mut db, file_id) = MockDatabase::with_single_file(
+        "inline fn add(a: i32, b: i32) -> i32 { a + b }\npub fn main() -> i32 { add(1, 2) }",
+    );
+    db.set_target(Target::host_target().unwrap());
+
+    // The inline function keeps its dispatch table entry
+    let add = db
+        .module_data(file_id)
+        .definitions()
+        .iter()
+        .find_map(|def| match def {
+            ModuleDef::Function(f) if f.name(&db).to_string() == "add" => Some(*f),
+            _ => None,
+        })
+        .expect("missing `add` function");
+    assert!(db.group_ir(file_id).dispatch_table.contains(add));
+
+    // The call to it is made directly, so it is inlined
+    let module = db.file_ir(file_id).llvm_module.clone();
+    crate::code_gen::optimize_module(&module, OptimizationLevel::Default);
+    let main = module
+        .get_function("main")
+        .expect("missing `main` function");
+    let mut block = main.get_first_basic_block();
+    while let Some(current) = block {
+        let mut instruction = current.get_first_instruction();
+        while let Some(current) = instruction {
+            assert_ne!(current.get_opcode(), InstructionOpcode::Call);
+            instruction = current.get_next_instruction();
+        }
+        block = current.get_next_basic_block();
+    }
+}
+
+/// Returns the folded value of the body of the `main` function in `text`.
+fn const_eval(text: &str) -> Option<ConstValue> {
+    let (mut db, file_id) = MockDatabase::with_single_file(text);
+    db.set_target(Target::host_target().unwrap());
+
+    let main = Module::from(file_id)
+        .declarations(&db)
+        .into_iter()
+        .find_map(|def| match def {
+            ModuleDef::Function(f) if f.name(&db).to_string() == "main" => Some(f),
+            _ => None,
+        })
+        .expect("missing `main` function");
+    db.const_eval(main, main.body(&db).body_expr())
+}
+
+/// Prints the IR of a module without its data layout, which depends on the host, so snapshots are
+/// the same on every platform.
+fn print_module_ir(module: &inkwell::module::Module) -> String {
+    module
+        .print_to_string()
+        .to_string()
+        .lines()
+        .filter(|line| !line.starts_with("target datalayout"))
+        .map(|line| format!("{}\n", line))
+        .collect()
+}
+
 fn test_snapshot(text: &str) {
     test_snapshot_with_optimization(text, OptimizationLevel::Default);
 }
@@ -851,6 +1191,9 @@ fn test_snapshot_with_optimization(text: &str, opt: OptimizationLevel) {
         ));
     });
     Module::from(file_id).diagnostics(&db, &mut sink);
+    if messages.borrow().is_empty() {
+        layout_diagnostics(&db, file_id, &mut sink);
+    }
     drop(sink);
     let messages = messages.into_inner();
 
@@ -866,25 +1209,13 @@ fn test_snapshot_with_optimization(text: &str, opt: OptimizationLevel) {
     let group_ir_value = if !messages.is_empty() {
         "".to_owned()
     } else {
-        format!(
-            "{}",
-            db.group_ir(file_id)
-                .llvm_module
-                .print_to_string()
-                .to_string()
-        )
+        print_module_ir(&db.group_ir(file_id).llvm_module)
     };
 
     let file_ir_value = if !messages.is_empty() {
         messages.join("\n")
     } else {
-        format!(
-            "{}",
-            db.file_ir(file_id)
-                .llvm_module
-                .print_to_string()
-                .to_string()
-        )
+        print_module_ir(&db.file_ir(file_id).llvm_module)
     };
 
     // To ensure that we test symbol generation
@@ -12,15 +12,23 @@ pub enum TypeGroup {
     StructTypes(hir::Struct),
 }
 
-impl From<TypeGroup> for u64 {
-    fn from(group: TypeGroup) -> Self {
+impl From<&TypeGroup> for abi::TypeGroup {
+    fn from(group: &TypeGroup) -> Self {
         match group {
-            TypeGroup::FundamentalTypes => 0,
-            TypeGroup::StructTypes(_) => 1,
+            TypeGroup::FundamentalTypes => abi::TypeGroup::FundamentalTypes,
+            TypeGroup::StructTypes(_) => abi::TypeGroup::StructTypes,
         }
     }
 }
 
+/// The value of a `TypeGroup` as it is emitted in the type table, which is the discriminant of
+/// the corresponding `abi::TypeGroup`.
+impl From<TypeGroup> for u64 {
+    fn from(group: TypeGroup) -> Self {
+        abi::TypeGroup::from(&group) as u64
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct TypeSize {
     // The size of the type in bits
@@ -93,6 +101,8 @@ impl TypeInfo {
 
     pub fn new_struct<D: IrDatabase>(db: &D, s: hir::Struct, type_size: TypeSize) -> TypeInfo {
         let name = s.name(db).to_string();
+        // The fields are listed in declaration order, so identical source always yields identical
+        // GUIDs, across compilations and hot reloads.
         let guid_string = {
             let fields: Vec<String> = s
                 .fields(db)
@@ -123,6 +133,93 @@ impl TypeInfo {
     }
 }
 
+/// The ABI type information that the compiler emits for a type, as returned by
+/// [`emitted_type_infos`]. This is the compiler's view of the information that the runtime reads
+/// from an assembly's type table.
+///
+/// [`emitted_type_infos`]: fn.emitted_type_infos.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmittedTypeInfo {
+    /// The GUID of the type
+    pub guid: Guid,
+    /// The name of the type
+    pub name: String,
+    /// The group of the type
+    pub group: abi::TypeGroup,
+    /// The exact size of the type in bits, without any padding
+    pub size_in_bits: u64,
+    /// The alignment of the type in bytes
+    pub alignment: u32,
+    /// The memory kind of the type, if it is a struct
+    pub memory_kind: Option<abi::StructMemoryKind>,
+    /// The fields of the type, in declaration order. Empty for fundamental types.
+    pub fields: Vec<EmittedFieldInfo>,
+}
+
+/// The ABI information that the compiler emits for a single field of a struct.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmittedFieldInfo {
+    /// The name of the field
+    pub name: String,
+    /// The GUID of the field's type
+    pub type_guid: Guid,
+    /// The name of the field's type
+    pub type_name: String,
+    /// The offset of the field in bytes, relative to the start of the struct
+    pub offset: u64,
+}
+
+/// Returns the type information of all types in the type table that the compiler emits for the
+/// module of `file_id`, in the order in which they appear in the type table.
+pub fn emitted_type_infos<D: IrDatabase>(db: &D, file_id: hir::FileId) -> Vec<EmittedTypeInfo> {
+    let group_ir = db.group_ir(file_id);
+    let target_data = db.target_data();
+    group_ir
+        .type_table
+        .type_infos()
+        .into_iter()
+        .map(|type_info| {
+            let (memory_kind, fields) = match type_info.group {
+                TypeGroup::FundamentalTypes => (None, Vec::new()),
+                TypeGroup::StructTypes(s) => {
+                    let struct_ir = db.struct_ty(s);
+                    let memory_kind = match s.data(db).memory_kind {
+                        hir::StructMemoryKind::GC => abi::StructMemoryKind::GC,
+                        hir::StructMemoryKind::Value => abi::StructMemoryKind::Value,
+                    };
+                    let fields = s
+                        .fields(db)
+                        .into_iter()
+                        .enumerate()
+                        .map(|(idx, field)| {
+                            let field_type_info = db.type_info(field.ty(db));
+                            EmittedFieldInfo {
+                                name: field.name(db).to_string(),
+                                type_guid: field_type_info.guid,
+                                type_name: field_type_info.name,
+                                offset: target_data
+                                    .offset_of_element(&struct_ir, idx as u32)
+                                    .unwrap(),
+                            }
+                        })
+                        .collect();
+                    (Some(memory_kind), fields)
+                }
+            };
+
+            EmittedTypeInfo {
+                guid: type_info.guid,
+                name: type_info.name.clone(),
+                group: (&type_info.group).into(),
+                size_in_bits: type_info.size.bit_size,
+                alignment: type_info.size.alignment,
+                memory_kind,
+                fields,
+            }
+        })
+        .collect()
+}
+
 /// A trait that statically defines that a type can be used as an argument.
 pub trait HasStaticTypeInfo {
     fn type_info(
@@ -1,5 +1,6 @@
+use mun_codegen::{layout_diagnostics, IrDatabase};
 use mun_hir::diagnostics::DiagnosticSink;
-use mun_hir::{FileId, HirDatabase, Module};
+use mun_hir::{FileId, Module};
 
 use std::cell::RefCell;
 
@@ -8,7 +9,7 @@ use annotate_snippets::snippet::Snippet;
 use crate::diagnostics_snippets;
 
 /// Constructs diagnostic messages for the given file.
-pub fn diagnostics(db: &impl HirDatabase, file_id: FileId) -> Vec<Snippet> {
+pub fn diagnostics(db: &impl IrDatabase, file_id: FileId) -> Vec<Snippet> {
     let parse = db.parse(file_id);
 
     let mut result = Vec::new();
@@ -133,6 +134,11 @@ pub fn diagnostics(db: &impl HirDatabase, file_id: FileId) -> Vec<Snippet> {
 
     Module::from(file_id).diagnostics(db, &mut sink);
 
+    // The layout of types is only known once the file is free of errors
+    if result.borrow().is_empty() {
+        layout_diagnostics(db, file_id, &mut sink);
+    }
+
     drop(sink);
 
     result.into_inner()
@@ -43,6 +43,7 @@ impl Driver {
             .db
             .set_context(Arc::new(mun_codegen::Context::create()));
         driver.db.set_optimization_lvl(config.optimization_lvl);
+        driver.db.set_emit_debug_info(config.emit_debug_info);
 
         driver.out_dir = config.out_dir;
 
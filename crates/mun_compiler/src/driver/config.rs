@@ -12,6 +12,9 @@ pub struct Config {
     /// The optimization level to use for the IR generation.
     pub optimization_lvl: OptimizationLevel,
 
+    /// Whether or not to emit the source locations of functions into the assembly.
+    pub emit_debug_info: bool,
+
     /// The optional output directory to store all outputs. If no directory is specified all output
     /// is stored in a temporary directory.
     pub out_dir: Option<PathBuf>,
@@ -28,6 +31,7 @@ impl Default for Config {
             // triple.
             target: target.unwrap(),
             optimization_lvl: OptimizationLevel::Default,
+            emit_debug_info: false,
             out_dir: None,
             display_color: DisplayColor::Auto,
         }
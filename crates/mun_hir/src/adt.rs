@@ -3,10 +3,14 @@ use std::{fmt, sync::Arc};
 use crate::type_ref::{TypeRefBuilder, TypeRefId, TypeRefMap, TypeRefSourceMap};
 use crate::{
     arena::{Arena, RawId},
+    expr::{float_lit, integer_lit, Literal},
     ids::{AstItemDef, StructId},
     AsName, DefDatabase, Name,
 };
-use mun_syntax::ast::{self, NameOwner, TypeAscriptionOwner};
+use mun_syntax::{
+    ast::{self, DocCommentsOwner, NameOwner, TypeAscriptionOwner},
+    SmolStr, T,
+};
 
 pub use mun_syntax::ast::StructMemoryKind;
 
@@ -26,6 +30,59 @@ pub use mun_syntax::ast::StructMemoryKind;
 pub struct StructFieldData {
     pub name: Name,
     pub type_ref: TypeRefId,
+    pub default: Option<FieldDefault>,
+    pub doc: Option<String>,
+}
+
+/// The declared default value of a record field, which is used to initialize the field when it is
+/// added to an existing struct during hot reloading.
+/// ```mun
+/// struct Foo {
+///     scale: float = 1.0, // <- this
+/// }
+/// ```
+/// Only (negated) literals are supported as default values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDefault {
+    pub negated: bool,
+    pub literal: Literal,
+}
+
+impl FieldDefault {
+    /// Lowers the default value expression of a record field. Returns `None` if the expression is
+    /// not a valid (negated) literal.
+    fn from_ast(expr: ast::Expr) -> Option<FieldDefault> {
+        let (negated, expr) = match expr.kind() {
+            ast::ExprKind::PrefixExpr(e) if e.op_kind() == Some(ast::PrefixOp::Neg) => {
+                (true, e.expr()?)
+            }
+            _ => (false, expr),
+        };
+
+        let lit = match expr.kind() {
+            ast::ExprKind::Literal(lit) => lit,
+            _ => return None,
+        };
+
+        let (literal, errors) = match lit.kind() {
+            ast::LiteralKind::Bool => (Literal::Bool(lit.token().kind() == T![true]), Vec::new()),
+            ast::LiteralKind::IntNumber => {
+                let (text, suffix) = lit.text_and_suffix();
+                integer_lit(&text, suffix.as_ref().map(SmolStr::as_str))
+            }
+            ast::LiteralKind::FloatNumber => {
+                let (text, suffix) = lit.text_and_suffix();
+                float_lit(&text, suffix.as_ref().map(SmolStr::as_str))
+            }
+            ast::LiteralKind::String => return None,
+        };
+
+        if errors.is_empty() {
+            Some(FieldDefault { negated, literal })
+        } else {
+            None
+        }
+    }
 }
 
 /// An identifier for a struct's or tuple's field
@@ -84,6 +141,8 @@ impl StructData {
                     .map(|fd| StructFieldData {
                         name: fd.name().map(|n| n.as_name()).unwrap_or_else(Name::missing),
                         type_ref: type_ref_builder.alloc_from_node_opt(fd.ascribed_type().as_ref()),
+                        default: fd.default_value().and_then(FieldDefault::from_ast),
+                        doc: fd.doc_comment_text(),
                     })
                     .collect();
                 (fields, StructKind::Record)
@@ -95,6 +154,8 @@ impl StructData {
                     .map(|(index, fd)| StructFieldData {
                         name: Name::new_tuple_field(index),
                         type_ref: type_ref_builder.alloc_from_node_opt(fd.type_ref().as_ref()),
+                        default: None,
+                        doc: None,
                     })
                     .collect();
                 (fields, StructKind::Tuple)
@@ -1,25 +1,34 @@
 pub(crate) mod src;
 
 use self::src::HasSource;
-use crate::adt::{StructData, StructFieldId};
-use crate::builtin_type::BuiltinType;
+use crate::adt::{FieldDefault, StructData, StructFieldId, StructKind, StructMemoryKind};
+use crate::builtin_type::{BuiltinType, Signedness};
 use crate::code_model::diagnostics::ModuleDefinitionDiagnostic;
-use crate::diagnostics::DiagnosticSink;
+use crate::diagnostics::{
+    DiagnosticSink, FieldOffsetTooLarge, InvalidFieldDefault, LiteralOutOfRange, MismatchedType,
+    RecursiveStructType, StructTooLarge,
+};
 use crate::expr::validator::ExprValidator;
-use crate::expr::{Body, BodySourceMap};
+use crate::expr::{Body, BodySourceMap, Literal, LiteralFloatKind, LiteralIntKind};
 use crate::ids::AstItemDef;
 use crate::ids::LocationCtx;
+use crate::in_file::InFile;
 use crate::name_resolution::Namespace;
 use crate::raw::{DefKind, RawFileItem};
 use crate::resolve::{Resolution, Resolver};
-use crate::ty::{lower::LowerBatchResult, InferenceResult};
+use crate::ty::{
+    lower::LowerBatchResult, FloatTy, InferenceResult, IntTy, ResolveBitness, TypeCtor,
+};
 use crate::type_ref::{TypeRefBuilder, TypeRefId, TypeRefMap, TypeRefSourceMap};
 use crate::{
     ids::{FunctionId, StructId},
     AsName, DefDatabase, FileId, HirDatabase, Name, Ty,
 };
-use mun_syntax::ast::{ExternOwner, NameOwner, TypeAscriptionOwner, VisibilityOwner};
-use rustc_hash::FxHashMap;
+use mun_syntax::ast::{
+    self, ExternOwner, InlineOwner, NameOwner, TypeAscriptionOwner, VisibilityOwner,
+};
+use mun_syntax::{AstNode, AstPtr, SyntaxNodePtr};
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -232,6 +241,7 @@ pub struct FnData {
     type_ref_map: TypeRefMap,
     type_ref_source_map: TypeRefSourceMap,
     is_extern: bool,
+    is_inline: bool,
 }
 
 impl FnData {
@@ -267,6 +277,7 @@ impl FnData {
         let (type_ref_map, type_ref_source_map) = type_ref_builder.finish();
 
         let is_extern = src.value.is_extern();
+        let is_inline = src.value.is_inline();
 
         Arc::new(FnData {
             name,
@@ -276,6 +287,7 @@ impl FnData {
             type_ref_map,
             type_ref_source_map,
             is_extern,
+            is_inline,
         })
     }
 
@@ -339,6 +351,21 @@ impl Function {
         db.fn_data(self).is_extern
     }
 
+    /// Returns whether the function is declared `inline`, which hints that calls to it should be
+    /// inlined.
+    pub fn is_inline(self, db: &impl HirDatabase) -> bool {
+        db.fn_data(self).is_inline
+    }
+
+    /// Returns the zero-based line number at which the function is defined in its source file.
+    pub fn source_line(self, db: &impl HirDatabase) -> u32 {
+        let src = self.source(db);
+        let line_index = db.line_index(src.file_id);
+        line_index
+            .line_col(src.value.syntax().text_range().start())
+            .line
+    }
+
     pub(crate) fn body_source_map(self, db: &impl HirDatabase) -> Arc<BodySourceMap> {
         db.body_with_source_map(self.into()).1
     }
@@ -381,9 +408,97 @@ impl StructField {
         self.parent.data(db).fields[self.id].name.clone()
     }
 
+    /// Returns the declared default value of the field, if any.
+    pub fn default_value(self, db: &impl HirDatabase) -> Option<FieldDefault> {
+        self.parent.data(db).fields[self.id].default.clone()
+    }
+
+    /// Returns the documentation of the field, if any.
+    pub fn doc(self, db: &impl HirDatabase) -> Option<String> {
+        self.parent.data(db).fields[self.id].doc.clone()
+    }
+
     pub fn id(self) -> StructFieldId {
         self.id
     }
+
+    /// Reports diagnostics for the declared default value `expr` of the field, which has to be a
+    /// (negated) literal of the field's type that is in range of the type.
+    fn default_value_diagnostics(
+        self,
+        db: &impl HirDatabase,
+        file: FileId,
+        expr: &ast::Expr,
+        sink: &mut DiagnosticSink,
+    ) {
+        let invalid_default = InvalidFieldDefault {
+            file,
+            default_value: SyntaxNodePtr::new(expr.syntax()),
+        };
+        let FieldDefault { negated, literal } = match self.default_value(db) {
+            Some(default) => default,
+            None => {
+                sink.push(invalid_default);
+                return;
+            }
+        };
+
+        // Errors in the type of the field have already been reported
+        let field_ty = self.ty(db);
+        let expected = match field_ty.as_simple() {
+            Some(ctor) => ctor,
+            None => return,
+        };
+
+        // Unsuffixed literals take on the type of the field, if they can
+        let found = match &literal {
+            Literal::Bool(_) => TypeCtor::Bool,
+            Literal::Int(lit) => TypeCtor::Int(match (&lit.kind, &expected) {
+                (LiteralIntKind::Suffixed(suffix), _) => (*suffix).into(),
+                (LiteralIntKind::Unsuffixed, TypeCtor::Int(int_ty)) => *int_ty,
+                (LiteralIntKind::Unsuffixed, _) => IntTy::i32(),
+            }),
+            Literal::Float(lit) => TypeCtor::Float(match (&lit.kind, &expected) {
+                (LiteralFloatKind::Suffixed(suffix), _) => (*suffix).into(),
+                (LiteralFloatKind::Unsuffixed, TypeCtor::Float(float_ty)) => *float_ty,
+                (LiteralFloatKind::Unsuffixed, _) => FloatTy::f64(),
+            }),
+            Literal::String(_) => unreachable!("string literals are not valid default values"),
+        };
+
+        if negated && found == TypeCtor::Bool {
+            sink.push(invalid_default);
+        } else if found != expected {
+            sink.push(MismatchedType {
+                file,
+                expr: SyntaxNodePtr::new(expr.syntax()),
+                expected: field_ty,
+                found: Ty::simple(found),
+            });
+        } else if let (TypeCtor::Int(int_ty), Literal::Int(lit)) = (found, &literal) {
+            let resolved_ty = int_ty.resolve(&db.target_data_layout());
+            let in_range = match (negated, resolved_ty.signedness) {
+                (false, _) => lit.value <= resolved_ty.max(),
+                (true, Signedness::Signed) => lit.value <= resolved_ty.max() + 1,
+                (true, Signedness::Unsigned) => lit.value == 0,
+            };
+            if !in_range {
+                let literal = match expr.kind() {
+                    ast::ExprKind::PrefixExpr(e) => e.expr(),
+                    _ => Some(expr.clone()),
+                }
+                .and_then(|e| match e.kind() {
+                    ast::ExprKind::Literal(lit) => Some(lit),
+                    _ => None,
+                })
+                .expect("a default value is a literal");
+                sink.push(LiteralOutOfRange {
+                    literal: InFile::new(file, AstPtr::new(&literal)),
+                    int_ty,
+                });
+            }
+        }
+    }
 }
 
 impl Struct {
@@ -433,13 +548,104 @@ impl Struct {
     pub fn diagnostics(self, db: &impl HirDatabase, sink: &mut DiagnosticSink) {
         let data = self.data(db);
         let lower = self.lower(db);
-        lower.add_diagnostics(
-            db,
-            self.module(db).file_id,
-            data.type_ref_source_map(),
-            sink,
-        );
+        let file_id = self.module(db).file_id;
+        lower.add_diagnostics(db, file_id, data.type_ref_source_map(), sink);
+
+        // A value struct that contains itself by value would have an infinite size
+        if data.memory_kind == StructMemoryKind::Value {
+            for field in self.fields(db) {
+                let field_ty = field.ty(db);
+                let contains_self = match value_struct(db, &field_ty) {
+                    Some(s) => s.contains_by_value(db, self, &mut FxHashSet::default()),
+                    None => false,
+                };
+                if contains_self {
+                    let type_ref = data.fields[field.id].type_ref;
+                    if let Some(type_ref) = data.type_ref_source_map().type_ref_syntax(type_ref) {
+                        sink.push(RecursiveStructType {
+                            file: file_id,
+                            type_ref,
+                            name: self.name(db),
+                        });
+                    }
+                }
+            }
+        }
+
+        // Only the fields of record structs can declare default values
+        if data.kind == StructKind::Record {
+            for field in self.fields(db) {
+                if let Some(expr) = field.source(db).value.default_value() {
+                    field.default_value_diagnostics(db, file_id, &expr, sink);
+                }
+            }
+        }
     }
+
+    /// Reports diagnostics if the layout of the struct on the target cannot be described by its
+    /// type information. The layout is computed by the code generator: `size_in_bits` is the size
+    /// of the struct, which must not exceed `max_size_in_bits`, and `field_offsets` are the offsets
+    /// of its fields in bytes, which must not exceed `max_field_offset`.
+    pub fn layout_diagnostics(
+        self,
+        db: &impl HirDatabase,
+        size_in_bits: u64,
+        field_offsets: &[u64],
+        max_size_in_bits: u64,
+        max_field_offset: u64,
+        sink: &mut DiagnosticSink,
+    ) {
+        let file_id = self.module(db).file_id;
+        if size_in_bits > max_size_in_bits {
+            sink.push(StructTooLarge {
+                file: file_id,
+                struct_def: AstPtr::new(&self.source(db).value),
+                name: self.name(db),
+                size_in_bits,
+                max_size_in_bits,
+            });
+        }
+
+        for (field, offset) in self.fields(db).into_iter().zip(field_offsets.iter()) {
+            if *offset > max_field_offset {
+                sink.push(FieldOffsetTooLarge {
+                    file: file_id,
+                    field_def: AstPtr::new(&field.source(db).value),
+                    struct_name: self.name(db),
+                    field_name: field.name(db),
+                    offset: *offset,
+                    max_offset: max_field_offset,
+                });
+            }
+        }
+    }
+
+    /// Returns whether this struct is, or contains `target` by value, either directly or through
+    /// other value structs. Structs in `visited` have already been checked.
+    fn contains_by_value(
+        self,
+        db: &impl HirDatabase,
+        target: Struct,
+        visited: &mut FxHashSet<Struct>,
+    ) -> bool {
+        if self == target {
+            return true;
+        }
+        if !visited.insert(self) {
+            return false;
+        }
+        self.fields(db).into_iter().any(|field| {
+            value_struct(db, &field.ty(db))
+                .map(|s| s.contains_by_value(db, target, visited))
+                .unwrap_or(false)
+        })
+    }
+}
+
+/// Returns the struct of `ty`, if it is a value struct.
+fn value_struct(db: &impl HirDatabase, ty: &Ty) -> Option<Struct> {
+    ty.as_struct()
+        .filter(|s| s.data(db).memory_kind == StructMemoryKind::Value)
 }
 
 mod diagnostics {
@@ -520,6 +520,110 @@ impl Diagnostic for NoSuchField {
     }
 }
 
+/// An error that is emitted if a value struct contains itself, either directly or through other
+/// value structs, which would give it an infinite size
+#[derive(Debug)]
+pub struct RecursiveStructType {
+    pub file: FileId,
+    pub type_ref: AstPtr<ast::TypeRef>,
+    pub name: Name,
+}
+
+impl Diagnostic for RecursiveStructType {
+    fn message(&self) -> String {
+        format!(
+            "recursive type `{}` needs indirection, e.g. through a `struct(gc)` type",
+            self.name
+        )
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.type_ref.syntax_node_ptr())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// An error that is emitted for a default value of a field that is not a (negated) literal
+#[derive(Debug)]
+pub struct InvalidFieldDefault {
+    pub file: FileId,
+    pub default_value: SyntaxNodePtr,
+}
+
+impl Diagnostic for InvalidFieldDefault {
+    fn message(&self) -> String {
+        "default values of fields must be boolean or (negated) number literals".to_string()
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.default_value)
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// An error that is emitted if a struct is larger than the maximum size that can be described by
+/// its type information
+#[derive(Debug)]
+pub struct StructTooLarge {
+    pub file: FileId,
+    pub struct_def: AstPtr<ast::StructDef>,
+    pub name: Name,
+    pub size_in_bits: u64,
+    pub max_size_in_bits: u64,
+}
+
+impl Diagnostic for StructTooLarge {
+    fn message(&self) -> String {
+        format!(
+            "struct `{}` is {} bits large, which exceeds the maximum of {} bits",
+            self.name, self.size_in_bits, self.max_size_in_bits
+        )
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.struct_def.syntax_node_ptr())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// An error that is emitted if the offset of a field is larger than the maximum offset that can be
+/// described by the type information of its struct
+#[derive(Debug)]
+pub struct FieldOffsetTooLarge {
+    pub file: FileId,
+    pub field_def: AstPtr<ast::RecordFieldDef>,
+    pub struct_name: Name,
+    pub field_name: Name,
+    pub offset: u64,
+    pub max_offset: u64,
+}
+
+impl Diagnostic for FieldOffsetTooLarge {
+    fn message(&self) -> String {
+        format!(
+            "field `{}` of struct `{}` is at an offset of {} bytes, which exceeds the maximum of {} bytes",
+            self.field_name, self.struct_name, self.offset, self.max_offset
+        )
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.field_def.syntax_node_ptr())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
 #[derive(Debug)]
 pub struct PossiblyUninitializedVariable {
     pub file: FileId,
@@ -969,7 +969,7 @@ fn strip_underscores(s: &str) -> Cow<str> {
 }
 
 /// Parses the given string into a float literal
-fn float_lit(str: &str, suffix: Option<&str>) -> (Literal, Vec<LiteralError>) {
+pub(crate) fn float_lit(str: &str, suffix: Option<&str>) -> (Literal, Vec<LiteralError>) {
     let str = strip_underscores(str);
     filtered_float_lit(&str, suffix, 10)
 }
@@ -1000,7 +1000,7 @@ fn filtered_float_lit(str: &str, suffix: Option<&str>, base: u32) -> (Literal, V
 }
 
 /// Parses the given string into an integer literal
-fn integer_lit(str: &str, suffix: Option<&str>) -> (Literal, Vec<LiteralError>) {
+pub(crate) fn integer_lit(str: &str, suffix: Option<&str>) -> (Literal, Vec<LiteralError>) {
     let str = strip_underscores(str);
 
     let base = match str.as_bytes() {
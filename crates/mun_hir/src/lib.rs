@@ -71,5 +71,5 @@ use crate::{
     source_id::{AstIdMap, FileAstId},
 };
 
-pub use self::adt::StructMemoryKind;
-pub use self::code_model::{FnData, Function, Module, ModuleDef, Struct, Visibility};
+pub use self::adt::{FieldDefault, StructMemoryKind};
+pub use self::code_model::{FnData, Function, Module, ModuleDef, Struct, StructField, Visibility};
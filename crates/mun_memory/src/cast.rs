@@ -1,6 +1,6 @@
 use abi::HasStaticTypeInfo;
 use lazy_static::lazy_static;
-use std::{collections::HashMap, ptr::NonNull};
+use std::{collections::HashMap, convert::TryFrom, ptr::NonNull};
 
 type CastFn = fn(NonNull<u8>, NonNull<u8>);
 
@@ -51,12 +51,61 @@ lazy_static! {
     };
 }
 
+/// A conversion from `Self` into `B` of which it can be validated that it preserves the value.
+trait CheckedInto<B>: Copy + Into<B> {
+    /// Returns whether `converted` represents the same value as `self`.
+    fn is_preserved_by(self, converted: &B) -> bool;
+}
+
+macro_rules! impl_checked_into_int {
+    ($($A:ty => $($B:ty),+;)+) => {
+        $($(
+            impl CheckedInto<$B> for $A {
+                fn is_preserved_by(self, converted: &$B) -> bool {
+                    <$A>::try_from(*converted).map_or(false, |value| value == self)
+                }
+            }
+        )+)+
+    }
+}
+
+impl_checked_into_int! {
+    i8 => i16, i32, i64, i128;
+    i16 => i32, i64, i128;
+    i32 => i64, i128;
+    i64 => i128;
+    u8 => i16, u16, i32, u32, i64, u64, i128, u128;
+    u16 => i32, u32, i64, u64, i128, u128;
+    u32 => i64, u64, i128, u128;
+    u64 => i128, u128;
+}
+
+impl CheckedInto<f64> for f32 {
+    fn is_preserved_by(self, converted: &f64) -> bool {
+        // Widening is exact, so the converted value must be bitwise identical. A `NaN` is not
+        // equal to itself, nor is its payload guaranteed to be preserved.
+        f64::from(self).to_bits() == converted.to_bits() || (self.is_nan() && converted.is_nan())
+    }
+}
+
+/// Casts the `A` at `src` into a `B` at `dest`.
+///
+/// In debug builds, the cast is validated to preserve the value, so a faulty conversion rule is
+/// caught rather than silently corrupting memory during hot reloading. The validation is compiled
+/// out in release builds.
 fn cast_from_to<A, B>(src: NonNull<u8>, dest: NonNull<u8>)
 where
-    A: Copy + Into<B>,
+    A: CheckedInto<B>,
 {
     let value = unsafe { *src.cast::<A>().as_ref() };
-    unsafe { *dest.cast::<B>().as_mut() = value.into() };
+    let dest = unsafe { &mut *dest.cast::<B>().as_ptr() };
+    *dest = value.into();
+    debug_assert!(
+        value.is_preserved_by(dest),
+        "cast from `{}` to `{}` does not preserve the value",
+        std::any::type_name::<A>(),
+        std::any::type_name::<B>(),
+    );
 }
 
 pub fn try_cast_from_to(
@@ -75,7 +124,7 @@ pub fn try_cast_from_to(
 
 #[cfg(test)]
 mod tests {
-    use super::try_cast_from_to;
+    use super::{try_cast_from_to, CheckedInto};
     use abi::HasStaticTypeInfo;
     use std::ptr::NonNull;
 
@@ -93,6 +142,51 @@ mod tests {
         assert_eq!(b, a.into());
     }
 
+    #[test]
+    fn checked_into() {
+        assert!((-5i8).is_preserved_by(&-5i16));
+        assert!(!(-5i8).is_preserved_by(&251i16));
+        assert!(!5u8.is_preserved_by(&300u16));
+        assert!(200u8.is_preserved_by(&200i16));
+        assert!(!std::u64::MAX.is_preserved_by(&-1i128));
+
+        assert!(1.1f32.is_preserved_by(&f64::from(1.1f32)));
+        assert!(!1.1f32.is_preserved_by(&1.1f64));
+        assert!(!1.1f32.is_preserved_by(&(f64::from(1.1f32) + std::f64::EPSILON)));
+        assert!((-0f32).is_preserved_by(&-0f64));
+        assert!(!(-0f32).is_preserved_by(&0f64));
+        assert!(std::f32::NAN.is_preserved_by(&std::f64::NAN));
+    }
+
+    #[test]
+    fn cast_bool_unsupported() {
+        // Buffers that are large enough to hold any of the types
+        let mut src = 1u128;
+        let mut dest = 0u128;
+        let guids = [
+            u8::type_info().guid,
+            i8::type_info().guid,
+            i32::type_info().guid,
+            u128::type_info().guid,
+            f64::type_info().guid,
+        ];
+        for guid in guids.iter() {
+            assert!(!try_cast_from_to(
+                bool::type_info().guid,
+                *guid,
+                NonNull::from(&mut src).cast::<u8>(),
+                NonNull::from(&mut dest).cast::<u8>(),
+            ));
+            assert!(!try_cast_from_to(
+                *guid,
+                bool::type_info().guid,
+                NonNull::from(&mut src).cast::<u8>(),
+                NonNull::from(&mut dest).cast::<u8>(),
+            ));
+        }
+        assert_eq!(dest, 0);
+    }
+
     #[test]
     fn cast_f32_to_f64() {
         assert_cast(3.14f32, 0f64);
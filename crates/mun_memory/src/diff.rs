@@ -4,7 +4,12 @@ use crate::{TypeDesc, TypeFields};
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum FieldEditKind {
-    ConvertType,
+    ConvertType {
+        /// The `Guid` of the field's old type
+        old_guid: abi::Guid,
+        /// The `Guid` of the field's new type
+        new_guid: abi::Guid,
+    },
     Rename,
 }
 
@@ -288,7 +293,7 @@ fn append_struct_mapping<T>(
 /// Given an `old` and a `new` set of fields, calculates the difference.
 fn field_diff<T>(old: &[(&str, T)], new: &[(&str, T)]) -> Vec<FieldDiff>
 where
-    T: Eq,
+    T: Eq + TypeDesc,
 {
     let diff = myers::diff(old, new);
     let (deletions, insertions) = myers::split_diff(&diff);
@@ -319,19 +324,23 @@ where
             if let Some(new_idx) = insertion {
                 let new_ty = unsafe { new.get_unchecked(*new_idx) };
                 if old_ty.0 == new_ty.0 {
+                    let kind = FieldEditKind::ConvertType {
+                        old_guid: *old_ty.1.guid(),
+                        new_guid: *new_ty.1.guid(),
+                    };
                     // If so,
                     mapping.push(if old_idx == *new_idx {
                         // convert the type in-place.
                         FieldDiff::Edit {
                             index: old_idx,
-                            kind: FieldEditKind::ConvertType,
+                            kind,
                         }
                     } else {
                         // convert the type and move it.
                         FieldDiff::Move {
                             old_index: old_idx,
                             new_index: *new_idx,
-                            edit: Some(FieldEditKind::ConvertType),
+                            edit: Some(kind),
                         }
                     });
                     *insertion = None;
@@ -1,3 +1,5 @@
+mod allocator;
+mod arena;
 mod mark_sweep;
 mod ptr;
 mod root_ptr;
@@ -5,7 +7,10 @@ mod root_ptr;
 use crate::TypeMemory;
 use std::marker::PhantomData;
 
+pub use allocator::{Allocator, SystemAllocator};
 pub use mark_sweep::MarkSweep;
+#[cfg(any(debug_assertions, feature = "heap-validation"))]
+pub use mark_sweep::ValidationError;
 pub use ptr::{GcPtr, HasIndirectionPtr, RawGcPtr};
 pub use root_ptr::GcRootPtr;
 
@@ -15,12 +20,27 @@ pub struct Stats {
     pub allocated_memory: usize,
 }
 
+/// The memory usage of all allocated objects of a single type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TypeUsage {
+    /// The number of allocated objects of the type
+    pub count: usize,
+    /// The total number of bytes allocated for the objects of the type
+    pub bytes: usize,
+}
+
 /// A trait used to trace an object type.
 pub trait TypeTrace: Send + Sync {
     type Trace: Iterator<Item = GcPtr>;
 
     /// Returns an iterator to iterate over all GC objects that are referenced by the given object.
     fn trace(&self, obj: GcPtr) -> Self::Trace;
+
+    /// Returns whether objects of this type can reference other GC objects. If this returns
+    /// `false`, `trace` never returns any objects. Defaults to `true`.
+    fn has_references(&self) -> bool {
+        true
+    }
 }
 
 /// An object that can be used to allocate and collect memory.
@@ -63,7 +83,8 @@ pub enum Event {
     /// A GC cycle started
     Start,
 
-    /// A deallocation took place
+    /// A deallocation took place. It is emitted after the collector released its locks, so the
+    /// observer can root and unroot objects in response.
     Deallocation(GcPtr),
 
     /// A GC cycle ended
@@ -0,0 +1,99 @@
+use std::{alloc::Layout, sync::Arc};
+
+/// An allocator that a garbage collector uses for the memory of its objects and their metadata.
+///
+/// # Safety
+///
+/// Implementations must uphold the same contract as [`GlobalAlloc`]: memory returned by `alloc`
+/// and `alloc_zeroed` must be valid for `layout`, and must remain valid until it is passed to
+/// `dealloc`. A null pointer is returned when allocation fails.
+///
+/// [`GlobalAlloc`]: https://doc.rust-lang.org/std/alloc/trait.GlobalAlloc.html
+pub unsafe trait Allocator: Send + Sync {
+    /// Allocates memory as described by `layout`.
+    ///
+    /// # Safety
+    ///
+    /// `layout` must have a non-zero size.
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8;
+
+    /// Allocates zero-initialized memory as described by `layout`.
+    ///
+    /// # Safety
+    ///
+    /// `layout` must have a non-zero size.
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.alloc(layout);
+        if !ptr.is_null() {
+            std::ptr::write_bytes(ptr, 0, layout.size());
+        }
+        ptr
+    }
+
+    /// Deallocates the memory at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been allocated by this allocator with the same `layout`.
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout);
+
+    /// Returns memory that the allocator holds on to, but that is not allocated, to the operating
+    /// system - or to the allocator it is backed by. This is a hint: allocators that cannot
+    /// release memory on demand may ignore it. Does nothing by default.
+    fn trim(&self) {}
+}
+
+/// The default [`Allocator`], which allocates memory through the global allocator of the
+/// process, i.e. `std::alloc`.
+///
+/// Trimming calls `malloc_trim` on Linux with glibc, which releases the free memory of `malloc`'s
+/// heap. On other platforms the system allocator offers no such function, so trimming does
+/// nothing.
+///
+/// [`Allocator`]: trait.Allocator.html
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemAllocator;
+
+unsafe impl Allocator for SystemAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        std::alloc::alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        std::alloc::alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        std::alloc::dealloc(ptr, layout)
+    }
+
+    fn trim(&self) {
+        #[cfg(all(target_os = "linux", target_env = "gnu"))]
+        {
+            extern "C" {
+                fn malloc_trim(pad: usize) -> std::os::raw::c_int;
+            }
+
+            // Safety: `malloc_trim` only releases memory that is not allocated.
+            unsafe { malloc_trim(0) };
+        }
+    }
+}
+
+unsafe impl<A: Allocator + ?Sized> Allocator for Arc<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        (**self).alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        (**self).alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        (**self).dealloc(ptr, layout)
+    }
+
+    fn trim(&self) {
+        (**self).trim()
+    }
+}
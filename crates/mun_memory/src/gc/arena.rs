@@ -0,0 +1,72 @@
+use super::Allocator;
+use std::{alloc::Layout, ptr::NonNull};
+
+/// The size of the chunks of memory that an `Arena` allocates from.
+const CHUNK_SIZE: usize = 4096;
+
+/// The minimum alignment of the chunks of memory that an `Arena` allocates from.
+const CHUNK_ALIGN: usize = 16;
+
+/// A bump allocator that frees all of its memory at once when it is dropped. Its chunks are
+/// allocated with the allocator `A`.
+#[derive(Debug)]
+pub(crate) struct Arena<A: Allocator> {
+    allocator: A,
+    chunks: Vec<(NonNull<u8>, Layout)>,
+    cursor: usize,
+}
+
+/// An `Arena` exclusively owns its chunks, so it is thread-safe.
+unsafe impl<A: Allocator> Send for Arena<A> {}
+unsafe impl<A: Allocator> Sync for Arena<A> {}
+
+impl<A: Allocator> Arena<A> {
+    /// Constructs an empty arena that allocates its chunks with `allocator`.
+    pub fn new(allocator: A) -> Self {
+        Self {
+            allocator,
+            chunks: Vec::new(),
+            cursor: 0,
+        }
+    }
+
+    /// Allocates uninitialized memory with the specified `layout`. The memory remains valid until
+    /// the arena is dropped.
+    pub fn alloc(&mut self, layout: Layout) -> *mut u8 {
+        if let Some((chunk, chunk_layout)) = self.chunks.last() {
+            let offset = align_up(self.cursor, layout.align());
+            if layout.align() <= chunk_layout.align()
+                && offset + layout.size() <= chunk_layout.size()
+            {
+                self.cursor = offset + layout.size();
+                return unsafe { chunk.as_ptr().add(offset) };
+            }
+        }
+
+        // The object does not fit in the current chunk, so start a new one
+        let chunk_layout = Layout::from_size_align(
+            layout.size().max(CHUNK_SIZE),
+            layout.align().max(CHUNK_ALIGN),
+        )
+        .expect("invalid arena chunk layout");
+        let chunk = NonNull::new(unsafe { self.allocator.alloc(chunk_layout) })
+            .unwrap_or_else(|| std::alloc::handle_alloc_error(chunk_layout));
+
+        self.chunks.push((chunk, chunk_layout));
+        self.cursor = layout.size();
+        chunk.as_ptr()
+    }
+}
+
+impl<A: Allocator> Drop for Arena<A> {
+    fn drop(&mut self) {
+        for (chunk, layout) in self.chunks.drain(..) {
+            unsafe { self.allocator.dealloc(chunk.as_ptr(), layout) };
+        }
+    }
+}
+
+/// Rounds `offset` up to the nearest multiple of `align`, which must be a power of two.
+fn align_up(offset: usize, align: usize) -> usize {
+    (offset + align - 1) & !(align - 1)
+}
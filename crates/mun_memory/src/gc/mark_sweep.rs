@@ -1,42 +1,55 @@
+#[cfg(any(debug_assertions, feature = "heap-validation"))]
+use crate::TypeFields;
 use crate::{
     cast,
-    gc::{Event, GcPtr, GcRuntime, Observer, RawGcPtr, Stats, TypeTrace},
+    gc::{
+        arena::Arena, Allocator, Event, GcPtr, GcRuntime, HasIndirectionPtr, Observer, RawGcPtr,
+        Stats, SystemAllocator, TypeTrace, TypeUsage,
+    },
     mapping::{self, FieldMapping, MemoryMapper},
     TypeDesc, TypeMemory,
 };
 use mapping::{Conversion, Mapping};
 use parking_lot::RwLock;
 use std::{
-    collections::{HashMap, VecDeque},
-    hash::Hash,
-    ops::Deref,
-    pin::Pin,
+    alloc::Layout,
+    collections::{hash_map::DefaultHasher, HashMap, HashSet, VecDeque},
+    hash::{Hash, Hasher},
+    ops::{Deref, DerefMut},
     ptr::NonNull,
+    sync::atomic::{AtomicUsize, Ordering},
 };
 
 /// Implements a simple mark-sweep type garbage collector.
+///
+/// The memory of objects and their metadata is allocated with the allocator `A`, which defaults
+/// to the global allocator.
 #[derive(Debug)]
-pub struct MarkSweep<T, O>
+pub struct MarkSweep<T, O, A = SystemAllocator>
 where
     T: TypeMemory + TypeTrace + Clone,
     O: Observer<Event = Event>,
+    A: Allocator + Clone,
 {
-    objects: RwLock<HashMap<GcPtr, Pin<Box<ObjectInfo<T>>>>>,
+    objects: RwLock<Heap<T>>,
+    scopes: RwLock<Vec<Scope<T, A>>>,
+    interned: RwLock<HashMap<u64, Vec<GcPtr>>>,
+    intern_values: bool,
+    track_age: bool,
+    disabled: AtomicUsize,
     observer: O,
+    allocator: A,
     stats: RwLock<Stats>,
 }
 
-impl<T, O> Default for MarkSweep<T, O>
+impl<T, O, A> Default for MarkSweep<T, O, A>
 where
     T: TypeMemory + TypeTrace + Clone,
     O: Observer<Event = Event> + Default,
+    A: Allocator + Clone + Default,
 {
     fn default() -> Self {
-        MarkSweep {
-            objects: RwLock::new(HashMap::new()),
-            observer: O::default(),
-            stats: RwLock::new(Stats::default()),
-        }
+        MarkSweep::with_observer_and_allocator(O::default(), A::default())
     }
 }
 
@@ -47,13 +60,225 @@ where
 {
     /// Creates a `MarkSweep` memory collector with the specified `Observer`.
     pub fn with_observer(observer: O) -> Self {
+        Self::with_observer_and_allocator(observer, SystemAllocator)
+    }
+}
+
+impl<T, O, A> MarkSweep<T, O, A>
+where
+    T: TypeMemory + TypeTrace + Clone,
+    O: Observer<Event = Event>,
+    A: Allocator + Clone,
+{
+    /// Creates a `MarkSweep` memory collector with the specified `Observer`, which allocates
+    /// memory with the specified `Allocator`.
+    pub fn with_observer_and_allocator(observer: O, allocator: A) -> Self {
         Self {
-            objects: RwLock::new(HashMap::new()),
+            objects: RwLock::new(Heap::default()),
+            scopes: RwLock::new(Vec::new()),
+            interned: RwLock::new(HashMap::new()),
+            intern_values: false,
+            track_age: false,
+            disabled: AtomicUsize::new(0),
             observer,
+            allocator,
             stats: RwLock::new(Stats::default()),
         }
     }
 
+    /// Enables or disables copy-on-write interning of value objects. When enabled,
+    /// `alloc_interned` may return an existing object with identical contents instead of
+    /// allocating a new object.
+    pub fn with_value_interning(mut self, enabled: bool) -> Self {
+        self.intern_values = enabled;
+        self
+    }
+
+    /// Returns whether copy-on-write interning of value objects is enabled.
+    pub fn is_value_interning_enabled(&self) -> bool {
+        self.intern_values
+    }
+
+    /// Enables or disables tracking of the number of collection cycles that each object survives.
+    pub fn with_age_tracking(mut self, enabled: bool) -> Self {
+        self.track_age = enabled;
+        self
+    }
+
+    /// Returns whether tracking of object ages is enabled.
+    pub fn is_age_tracking_enabled(&self) -> bool {
+        self.track_age
+    }
+
+    /// Returns the number of collection cycles that the object `handle` has survived, or `None` if
+    /// age tracking is disabled or the object does not exist.
+    pub fn object_age(&self, handle: GcPtr) -> Option<u32> {
+        if !self.track_age {
+            return None;
+        }
+
+        let objects = self.objects.read();
+        objects.get(&handle).map(|object_info| object_info.age)
+    }
+
+    /// Disables collection until a matching call to `enable_collection`. While collection is
+    /// disabled, `collect` does nothing. Calls can be nested, in which case collection is enabled
+    /// again when every call has been matched.
+    pub fn disable_collection(&self) {
+        self.disabled.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Enables collection that was disabled by a call to `disable_collection`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if collection is not disabled.
+    pub fn enable_collection(&self) {
+        self.disabled
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |disabled| {
+                disabled.checked_sub(1)
+            })
+            .expect("collection is not disabled");
+    }
+
+    /// Returns whether collection is enabled.
+    pub fn is_collection_enabled(&self) -> bool {
+        self.disabled.load(Ordering::Acquire) == 0
+    }
+
+    /// Pins the object `handle`. If the object was allocated in an open allocation scope, it is
+    /// moved to the heap, so it is not relocated when the scope closes. Collection never relocates
+    /// objects on the heap, but mapping memory still relocates objects of converted types.
+    pub fn pin(&self, handle: GcPtr) {
+        let mut objects = self.objects.write();
+        let mut scopes = self.scopes.write();
+        for scope in scopes.iter_mut() {
+            if let Some(idx) = scope
+                .objects
+                .iter()
+                .position(|object| object.handle() == handle)
+            {
+                let mut object = scope.objects.swap_remove(idx);
+                self.move_to_heap(&mut object);
+                objects.insert(handle, object);
+                break;
+            }
+        }
+    }
+
+    /// Opens an allocation scope. Until the scope is closed with `end_scope`, objects are
+    /// bump-allocated in an arena that belongs to the scope, instead of on the heap. Scopes can be
+    /// nested, in which case objects are allocated in the innermost scope.
+    ///
+    /// Objects allocated in a scope are not collected by `collect`, but they do keep the objects
+    /// they reference alive.
+    pub fn begin_scope(&self) {
+        self.scopes.write().push(Scope::new(self.allocator.clone()));
+    }
+
+    /// Closes the innermost allocation scope, freeing all objects allocated in it at once.
+    ///
+    /// Objects of the scope that are still in use escape the scope: they are moved to the heap
+    /// instead of freed. An object is in use if it is rooted, if it is referenced by an object on
+    /// the heap or in another open scope, or if it is referenced by another escaping object. To
+    /// find these references, the objects outside of the scope whose types can reference other
+    /// objects are traced. Objects of other types, such as plain old data, are skipped without
+    /// visiting them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no allocation scope is open.
+    pub fn end_scope(&self) {
+        let mut objects = self.objects.write();
+        let (scope, outside_references) = {
+            let mut scopes = self.scopes.write();
+            let scope = scopes.pop().expect("there is no open allocation scope");
+
+            // Objects outside of the scope may hold references into it
+            let outside_references: Vec<GcPtr> = objects
+                .referencing()
+                .chain(
+                    scopes
+                        .iter()
+                        .flat_map(|scope| scope.objects.iter())
+                        .filter(|object| object.ty.has_references()),
+                )
+                .flat_map(|object| object.ty.trace(object.handle()))
+                .collect();
+            (scope, outside_references)
+        };
+
+        let handles: HashSet<GcPtr> = scope.objects.iter().map(|object| object.handle()).collect();
+
+        // Find all objects that are reachable from rooted objects of the scope, or from objects
+        // outside of it
+        let mut escaped = HashSet::new();
+        let mut queue: VecDeque<GcPtr> = scope
+            .objects
+            .iter()
+            .filter(|object| object.roots > 0)
+            .map(|object| object.handle())
+            .chain(
+                outside_references
+                    .into_iter()
+                    .filter(|reference| handles.contains(reference)),
+            )
+            .collect();
+        while let Some(handle) = queue.pop_front() {
+            if escaped.insert(handle) {
+                let object_info: *const ObjectInfo<T> = handle.into();
+                let references = unsafe { (*object_info).ty.trace(handle) };
+                queue.extend(references.filter(|reference| handles.contains(reference)));
+            }
+        }
+
+        let mut interned = self.interned.write();
+        let mut deallocated = Vec::new();
+        for mut object in scope.objects {
+            let handle = object.handle();
+            if escaped.contains(&handle) {
+                self.move_to_heap(&mut object);
+                objects.insert(handle, object);
+            } else {
+                remove_interned(&mut interned, &mut object);
+                deallocated.push(handle);
+                {
+                    let mut stats = self.stats.write();
+                    stats.allocated_memory -= object.ty.layout().size();
+                }
+                unsafe { self.free_obj_info(&object) };
+            }
+        }
+
+        // The observer is notified after the lock is released, so it can root and unroot objects
+        drop(interned);
+        drop(objects);
+        for handle in deallocated {
+            self.observer.event(Event::Deallocation(handle));
+        }
+
+        // Dropping the scope's arena frees the memory of all objects that did not escape
+    }
+
+    /// Moves the memory of `object`, which was allocated in a scope, out of the scope's arena to
+    /// the heap.
+    fn move_to_heap(&self, object: &mut ObjectBox<T>) {
+        let layout = object.ty.layout();
+        unsafe {
+            let ptr = self.allocator.alloc(layout);
+            std::ptr::copy_nonoverlapping(object.ptr, ptr, layout.size());
+            object.ptr = ptr;
+        }
+
+        // The object may have been marked while tracing the roots of the scope
+        object.color = Color::White;
+    }
+
+    /// Returns the number of open allocation scopes.
+    pub fn scope_depth(&self) -> usize {
+        self.scopes.read().len()
+    }
+
     /// Logs an allocation
     fn log_alloc(&self, handle: GcPtr, ty: T) {
         {
@@ -68,28 +293,144 @@ where
     pub fn observer(&self) -> &O {
         &self.observer
     }
+
+    /// Returns whether any allocated object is of one of the specified `types`.
+    pub fn has_objects_of_types(&self, types: &[T]) -> bool
+    where
+        T: PartialEq,
+    {
+        let objects = self.objects.read();
+        objects
+            .values()
+            .any(|object_info| types.contains(&object_info.ty))
+    }
+
+    /// Returns the memory usage of all allocated objects, including those allocated in a scope,
+    /// grouped by the name of their type.
+    ///
+    /// No collection is performed, so objects that are unreachable but have not yet been
+    /// collected are included.
+    pub fn memory_usage(&self) -> HashMap<String, TypeUsage>
+    where
+        T: TypeDesc,
+    {
+        let objects = self.objects.read();
+        let scopes = self.scopes.read();
+
+        let mut usage: HashMap<String, TypeUsage> = HashMap::new();
+        for object_info in objects
+            .values()
+            .chain(scopes.iter().flat_map(|scope| scope.objects.iter()))
+        {
+            let type_usage = usage.entry(object_info.ty.name().to_string()).or_default();
+            type_usage.count += 1;
+            type_usage.bytes += object_info.ty.layout().size();
+        }
+        usage
+    }
+
+    /// Returns the handles of all rooted objects, including those allocated in a scope, together
+    /// with the number of times that they are rooted.
+    pub fn roots(&self) -> Vec<(GcPtr, u32)> {
+        let objects = self.objects.read();
+        let scopes = self.scopes.read();
+        objects
+            .values()
+            .chain(scopes.iter().flat_map(|scope| scope.objects.iter()))
+            .filter(|object| object.roots > 0)
+            .map(|object| (object.handle(), object.roots))
+            .collect()
+    }
+
+    /// Allocates an object of type `ty` and its metadata.
+    fn alloc_obj(&self, ty: T) -> ObjectBox<T> {
+        let ptr = unsafe { self.allocator.alloc(ty.layout()) };
+        self.alloc_obj_info(ObjectInfo {
+            ptr,
+            ty,
+            roots: 0,
+            color: Color::White,
+            interned: None,
+            age: 0,
+        })
+    }
+
+    /// Moves the metadata of an object into memory allocated with the collector's allocator.
+    fn alloc_obj_info(&self, object_info: ObjectInfo<T>) -> ObjectBox<T> {
+        let layout = Layout::new::<ObjectInfo<T>>();
+        unsafe {
+            let ptr = NonNull::new(self.allocator.alloc(layout).cast::<ObjectInfo<T>>())
+                .unwrap_or_else(|| std::alloc::handle_alloc_error(layout));
+            ptr.as_ptr().write(object_info);
+            ObjectBox(ptr)
+        }
+    }
+
+    /// Frees the metadata of an object. The object's memory is not freed.
+    ///
+    /// # Safety
+    ///
+    /// `object` must not be used after calling this function.
+    unsafe fn free_obj_info(&self, object: &ObjectBox<T>) {
+        std::ptr::drop_in_place(object.0.as_ptr());
+        self.allocator.dealloc(
+            object.0.as_ptr().cast::<u8>(),
+            Layout::new::<ObjectInfo<T>>(),
+        );
+    }
 }
 
-fn alloc_obj<T: Clone + TypeMemory + TypeTrace>(ty: T) -> Pin<Box<ObjectInfo<T>>> {
-    let ptr = unsafe { std::alloc::alloc(ty.layout()) };
-    Box::pin(ObjectInfo {
-        ptr,
-        ty,
-        roots: 0,
-        color: Color::White,
-    })
+impl<T, O, A> Drop for MarkSweep<T, O, A>
+where
+    T: TypeMemory + TypeTrace + Clone,
+    O: Observer<Event = Event>,
+    A: Allocator + Clone,
+{
+    fn drop(&mut self) {
+        let objects = std::mem::take(self.objects.get_mut());
+        let scopes = std::mem::take(self.scopes.get_mut());
+        for object in objects
+            .values()
+            .chain(scopes.iter().flat_map(|scope| scope.objects.iter()))
+        {
+            unsafe { self.free_obj_info(object) };
+        }
+    }
 }
 
-impl<T, O> GcRuntime<T> for MarkSweep<T, O>
+impl<T, O, A> GcRuntime<T> for MarkSweep<T, O, A>
 where
     T: TypeMemory + TypeTrace + Clone,
     O: Observer<Event = Event>,
+    A: Allocator + Clone,
 {
     fn alloc(&self, ty: T) -> GcPtr {
-        let object = alloc_obj(ty.clone());
+        {
+            let mut scopes = self.scopes.write();
+            if let Some(scope) = scopes.last_mut() {
+                let object = self.alloc_obj_info(ObjectInfo {
+                    ptr: scope.arena.alloc(ty.layout()),
+                    ty: ty.clone(),
+                    roots: 0,
+                    color: Color::White,
+                    interned: None,
+                    age: 0,
+                });
+
+                // We want to return a pointer to the `ObjectInfo`, to be used as handle.
+                let handle = object.handle();
+                scope.objects.push(object);
+                drop(scopes);
+
+                self.log_alloc(handle, ty);
+                return handle;
+            }
+        }
+
+        let object = self.alloc_obj(ty.clone());
 
         // We want to return a pointer to the `ObjectInfo`, to be used as handle.
-        let handle = (object.as_ref().deref() as *const _ as RawGcPtr).into();
+        let handle = object.handle();
 
         {
             let mut objects = self.objects.write();
@@ -101,7 +442,7 @@ where
     }
 
     fn ptr_type(&self, handle: GcPtr) -> T {
-        let _ = self.objects.read();
+        let _objects = self.objects.read();
 
         // Convert the handle to our internal representation
         let object_info: *const ObjectInfo<T> = handle.into();
@@ -111,7 +452,7 @@ where
     }
 
     fn root(&self, handle: GcPtr) {
-        let _ = self.objects.write();
+        let _objects = self.objects.write();
 
         // Convert the handle to our internal representation
         let object_info: *mut ObjectInfo<T> = handle.into();
@@ -120,7 +461,7 @@ where
     }
 
     fn unroot(&self, handle: GcPtr) {
-        let _ = self.objects.write();
+        let _objects = self.objects.write();
 
         // Convert the handle to our internal representation
         let object_info: *mut ObjectInfo<T> = handle.into();
@@ -133,28 +474,41 @@ where
     }
 }
 
-impl<T, O> MarkSweep<T, O>
+impl<T, O, A> MarkSweep<T, O, A>
 where
     T: TypeMemory + TypeTrace + Clone,
     O: Observer<Event = Event>,
+    A: Allocator + Clone,
 {
     /// Collects all memory that is no longer referenced by rooted objects. Returns `true` if memory
     /// was reclaimed, `false` otherwise.
+    ///
+    /// Does nothing and returns `false` while collection is disabled.
     pub fn collect(&self) -> bool {
+        if !self.is_collection_enabled() {
+            return false;
+        }
+
         self.observer.event(Event::Start);
 
         let mut objects = self.objects.write();
+        let scopes = self.scopes.read();
 
-        // Get all roots
+        // Get all roots. Objects allocated in a scope are never collected, so they are roots too.
         let mut roots = objects
             .iter()
             .filter_map(|(_, obj)| {
                 if obj.roots > 0 {
-                    Some(obj.as_ref().get_ref() as *const _ as *mut ObjectInfo<T>)
+                    Some(obj.as_ptr())
                 } else {
                     None
                 }
             })
+            .chain(
+                scopes
+                    .iter()
+                    .flat_map(|scope| scope.objects.iter().map(|obj| obj.as_ptr())),
+            )
             .collect::<VecDeque<_>>();
 
         // Iterate over all roots
@@ -163,11 +517,13 @@ where
 
             // Trace all other objects
             for reference in unsafe { (*next).ty.trace(handle) } {
-                let ref_ptr = objects
-                    .get_mut(&reference)
-                    .expect("found invalid reference");
+                let ref_ptr = match objects.get_mut(&reference) {
+                    Some(ref_ptr) => ref_ptr,
+                    // Objects allocated in a scope have already been added as roots
+                    None => continue,
+                };
                 if ref_ptr.color == Color::White {
-                    let ptr = ref_ptr.as_ref().get_ref() as *const _ as *mut ObjectInfo<T>;
+                    let ptr = ref_ptr.as_ptr();
                     unsafe { (*ptr).color = Color::Gray };
                     roots.push_back(ptr);
                 }
@@ -181,38 +537,215 @@ where
 
         // Sweep all non-reachable objects
         let size_before = objects.len();
+        let mut interned = self.interned.write();
+        let mut deallocated = Vec::new();
         objects.retain(|h, obj| {
             if obj.color == Color::Black {
-                unsafe {
-                    obj.as_mut().get_unchecked_mut().color = Color::White;
+                obj.color = Color::White;
+                if self.track_age {
+                    obj.age = obj.age.saturating_add(1);
                 }
                 true
             } else {
-                unsafe { std::alloc::dealloc(obj.ptr, obj.ty.layout()) };
-                self.observer.event(Event::Deallocation(*h));
+                remove_interned(&mut interned, obj);
+                unsafe { self.allocator.dealloc(obj.ptr, obj.ty.layout()) };
+                deallocated.push(*h);
                 {
                     let mut stats = self.stats.write();
                     stats.allocated_memory -= obj.ty.layout().size();
                 }
+
+                // The object is removed from `objects` by returning `false`
+                unsafe { self.free_obj_info(obj) };
                 false
             }
         });
         let size_after = objects.len();
 
+        // The observer is notified after the locks are released, so it can root and unroot
+        // objects, e.g. by dropping the host resources of deallocated objects.
+        drop(interned);
+        drop(scopes);
+        drop(objects);
+        for handle in deallocated {
+            self.observer.event(Event::Deallocation(handle));
+        }
+
         self.observer.event(Event::End);
 
         size_before != size_after
     }
+
+    /// Releases memory that the collector reserved for its bookkeeping, but no longer uses, and
+    /// asks the allocator to return unallocated memory to the operating system. Call `collect`
+    /// beforehand to free unreachable objects first.
+    ///
+    /// Memory that is released has to be reserved again when the number of objects grows, so
+    /// this trades allocation speed for a smaller footprint. It is best called after a spike in
+    /// allocations, rather than regularly.
+    pub fn shrink_to_fit(&self) {
+        self.objects.write().shrink_to_fit();
+        {
+            let mut interned = self.interned.write();
+            interned.values_mut().for_each(Vec::shrink_to_fit);
+            interned.shrink_to_fit();
+        }
+        self.scopes.write().shrink_to_fit();
+        self.allocator.trim();
+    }
+}
+
+impl<T, O, A> MarkSweep<T, O, A>
+where
+    T: TypeMemory + TypeTrace + Clone + Eq + Hash,
+    O: Observer<Event = Event>,
+    A: Allocator + Clone,
+{
+    /// Allocates an object of type `ty` whose memory is initialized with `contents`, which must be
+    /// as large as the type. If value interning is enabled and a live object of the same type with
+    /// identical contents exists, no memory is allocated and that object is returned instead.
+    /// Otherwise, the new object is added to the index.
+    ///
+    /// Only objects of stack-allocated types that do not reference other GC objects are interned,
+    /// and only while no allocation scope is open. An interned object is shared while it is
+    /// rooted more than once, so `unshare` must be called before mutating it. Once all but one of
+    /// its roots are gone, it is no longer shared and can be mutated in place again.
+    pub fn alloc_interned(&self, ty: T, contents: &[u8]) -> GcPtr {
+        assert_eq!(
+            contents.len(),
+            ty.layout().size(),
+            "the contents must be as large as the type"
+        );
+
+        if !self.intern_values
+            || !ty.is_stack_allocated()
+            || ty.has_references()
+            || self.scope_depth() > 0
+        {
+            let mut handle = self.alloc(ty);
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    contents.as_ptr(),
+                    handle.deref_mut::<u8>(),
+                    contents.len(),
+                )
+            };
+            return handle;
+        }
+
+        let hash = content_hash(&ty, contents);
+        let mut objects = self.objects.write();
+        let mut interned = self.interned.write();
+        let candidates = interned.entry(hash).or_default();
+
+        let existing = candidates.iter().find(|candidate| {
+            objects.get(*candidate).map_or(false, |candidate| {
+                candidate.ty == ty && object_contents(candidate) == contents
+            })
+        });
+        if let Some(existing) = existing.copied() {
+            return existing;
+        }
+
+        let mut object = self.alloc_obj(ty.clone());
+        unsafe { std::ptr::copy_nonoverlapping(contents.as_ptr(), object.ptr, contents.len()) };
+        object.interned = Some(hash);
+
+        // We want to return a pointer to the `ObjectInfo`, to be used as handle.
+        let handle = object.handle();
+        candidates.push(handle);
+        objects.insert(handle, object);
+
+        drop(interned);
+        drop(objects);
+
+        self.log_alloc(handle, ty);
+        handle
+    }
+
+    /// Prepares the object `handle` for mutation. If the object is shared, i.e. it is interned
+    /// and rooted more than once, a private copy is allocated and returned; otherwise the object
+    /// is removed from the interning index and `handle` is returned.
+    pub fn unshare(&self, handle: GcPtr) -> GcPtr {
+        if !self.intern_values {
+            return handle;
+        }
+
+        let mut objects = self.objects.write();
+        let object_info = match objects.get_mut(&handle) {
+            Some(object_info) => object_info,
+            None => return handle,
+        };
+
+        if object_info.interned.is_none() || object_info.roots <= 1 {
+            remove_interned(&mut self.interned.write(), object_info);
+            return handle;
+        }
+        let ty = object_info.ty.clone();
+
+        let object = self.alloc_obj(ty.clone());
+        unsafe {
+            std::ptr::copy_nonoverlapping(objects[&handle].ptr, object.ptr, ty.layout().size())
+        };
+
+        // We want to return a pointer to the `ObjectInfo`, to be used as handle.
+        let copy = object.handle();
+        objects.insert(copy, object);
+
+        drop(objects);
+
+        self.log_alloc(copy, ty);
+        copy
+    }
+}
+
+/// Removes `object_info` from the interning index `interned`, if it is interned.
+fn remove_interned<T: TypeMemory + TypeTrace + Clone>(
+    interned: &mut HashMap<u64, Vec<GcPtr>>,
+    object_info: &mut ObjectInfo<T>,
+) {
+    let hash = match object_info.interned.take() {
+        Some(hash) => hash,
+        None => return,
+    };
+
+    let handle: GcPtr = (object_info as *const ObjectInfo<T>).into();
+    if let Some(candidates) = interned.get_mut(&hash) {
+        candidates.retain(|h| *h != handle);
+        if candidates.is_empty() {
+            interned.remove(&hash);
+        }
+    }
+}
+
+/// Computes a hash over the type and contents of an object.
+fn content_hash<T: Hash>(ty: &T, contents: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    ty.hash(&mut hasher);
+    contents.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Returns the memory of the object as a byte slice.
+fn object_contents<T: TypeMemory + TypeTrace + Clone>(object_info: &ObjectInfo<T>) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(object_info.ptr, object_info.ty.layout().size()) }
 }
 
-impl<T, O> MemoryMapper<T> for MarkSweep<T, O>
+impl<T, O, A> MemoryMapper<T> for MarkSweep<T, O, A>
 where
     T: TypeDesc + TypeMemory + TypeTrace + Clone + Eq + Hash,
     O: Observer<Event = Event>,
+    A: Allocator + Clone,
 {
     fn map_memory(&self, mapping: Mapping<T, T>) -> Vec<GcPtr> {
         let mut objects = self.objects.write();
 
+        // The contents and types of objects are about to change, invalidating the index
+        self.interned.write().clear();
+        for object_info in objects.values_mut() {
+            object_info.interned = None;
+        }
+
         // Determine which types are still allocated with deleted types
         let deleted = objects
             .iter()
@@ -229,12 +762,7 @@ where
         for (old_ty, new_ty) in mapping.identical {
             for object_info in objects.values_mut() {
                 if object_info.ty == old_ty {
-                    object_info.set(ObjectInfo {
-                        ptr: object_info.ptr,
-                        roots: object_info.roots,
-                        color: object_info.color,
-                        ty: new_ty.clone(),
-                    });
+                    object_info.ty = new_ty.clone();
                 }
             }
         }
@@ -246,7 +774,9 @@ where
                 if object_info.ty == *old_ty {
                     let src = unsafe { NonNull::new_unchecked(object_info.ptr) };
                     let dest = unsafe {
-                        NonNull::new_unchecked(std::alloc::alloc_zeroed(conversion.new_ty.layout()))
+                        NonNull::new_unchecked(
+                            self.allocator.alloc_zeroed(conversion.new_ty.layout()),
+                        )
                     };
 
                     map_fields(
@@ -258,25 +788,24 @@ where
                         dest,
                     );
 
-                    unsafe { std::alloc::dealloc(src.as_ptr(), old_ty.layout()) };
+                    unsafe { self.allocator.dealloc(src.as_ptr(), old_ty.layout()) };
 
-                    object_info.set(ObjectInfo {
-                        ptr: dest.as_ptr(),
-                        roots: object_info.roots,
-                        color: object_info.color,
-                        ty: conversion.new_ty.clone(),
-                    });
+                    object_info.ptr = dest.as_ptr();
+                    object_info.ty = conversion.new_ty.clone();
                 }
             }
         }
 
+        // The types of objects changed, which changes whether they can reference other objects
+        objects.reindex();
+
         // Retroactively store newly allocated objects
         // This cannot be done while mapping because we hold a mutable reference to objects
         for object in new_allocations {
             let ty = object.ty.clone();
             // We want to return a pointer to the `ObjectInfo`, to
             // be used as handle.
-            let handle = (object.as_ref().deref() as *const _ as RawGcPtr).into();
+            let handle = object.handle();
             objects.insert(handle, object);
 
             self.log_alloc(handle, ty);
@@ -284,9 +813,9 @@ where
 
         return deleted;
 
-        fn map_fields<T, O>(
-            gc: &MarkSweep<T, O>,
-            new_allocations: &mut Vec<Pin<Box<ObjectInfo<T>>>>,
+        fn map_fields<T, O, A>(
+            gc: &MarkSweep<T, O, A>,
+            new_allocations: &mut Vec<ObjectBox<T>>,
             conversions: &HashMap<T, Conversion<T>>,
             mapping: &[FieldMapping<T>],
             src: NonNull<u8>,
@@ -294,6 +823,7 @@ where
         ) where
             T: TypeDesc + TypeMemory + TypeTrace + Clone + Eq + Hash,
             O: Observer<Event = Event>,
+            A: Allocator + Clone,
         {
             for FieldMapping {
                 new_ty,
@@ -343,11 +873,10 @@ where
                                     }
                                 } else {
                                     // struct(value) -> struct(gc)
-                                    let object = alloc_obj(new_ty.clone());
+                                    let object = gc.alloc_obj(new_ty.clone());
 
                                     // We want to return a pointer to the `ObjectInfo`, to be used as handle.
-                                    let handle =
-                                        (object.as_ref().deref() as *const _ as RawGcPtr).into();
+                                    let handle = object.handle();
 
                                     if is_same_struct {
                                         // Map in-memory struct to heap-allocated struct
@@ -387,12 +916,11 @@ where
                                         *field_dest = *field_src;
                                     }
                                 } else {
-                                    let object = alloc_obj(new_ty.clone());
+                                    let object = gc.alloc_obj(new_ty.clone());
 
                                     // We want to return a pointer to the `ObjectInfo`, to
                                     // be used as handle.
-                                    let handle =
-                                        (object.as_ref().deref() as *const _ as RawGcPtr).into();
+                                    let handle = object.handle();
 
                                     // Zero-initialize heap-allocated object
                                     unsafe {
@@ -469,13 +997,13 @@ where
                             )
                         };
                     }
-                    mapping::Action::Insert => {
+                    mapping::Action::Insert { default } => {
                         if !new_ty.is_stack_allocated() {
-                            let object = alloc_obj(new_ty.clone());
+                            let object = gc.alloc_obj(new_ty.clone());
 
                             // We want to return a pointer to the `ObjectInfo`, to be used as
                             // handle.
-                            let handle = (object.as_ref().deref() as *const _ as RawGcPtr).into();
+                            let handle = object.handle();
 
                             // Zero-initialize heap-allocated object
                             unsafe { std::ptr::write_bytes(object.ptr, 0, new_ty.layout().size()) };
@@ -487,6 +1015,14 @@ where
                             }
 
                             new_allocations.push(object);
+                        } else if let Some(default) = default {
+                            unsafe {
+                                std::ptr::copy_nonoverlapping(
+                                    default.as_ptr(),
+                                    field_dest,
+                                    new_ty.layout().size(),
+                                )
+                            };
                         } else {
                             // Use the previously zero-initialized value
                         }
@@ -497,6 +1033,222 @@ where
     }
 }
 
+/// A violation of the integrity of the heap, as found by [`MarkSweep::validate`].
+///
+/// [`MarkSweep::validate`]: struct.MarkSweep.html#method.validate
+#[cfg(any(debug_assertions, feature = "heap-validation"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// A field of `object` references `ptr`, which is not a live object.
+    DanglingPointer {
+        /// The object that contains the field
+        object: GcPtr,
+        /// The path of the field, relative to the object
+        field: String,
+        /// The referenced memory
+        ptr: GcPtr,
+    },
+    /// A field of `object` references a live object of a different type than the field's.
+    MismatchedType {
+        /// The object that contains the field
+        object: GcPtr,
+        /// The path of the field, relative to the object
+        field: String,
+        /// The name of the field's type
+        expected: String,
+        /// The name of the referenced object's type
+        found: String,
+    },
+}
+
+#[cfg(any(debug_assertions, feature = "heap-validation"))]
+impl<T, O, A> MarkSweep<T, O, A>
+where
+    T: TypeDesc + TypeFields<T> + TypeMemory + TypeTrace + Clone + Eq,
+    O: Observer<Event = Event>,
+    A: Allocator + Clone,
+{
+    /// Walks all live objects, including those allocated in a scope, and validates that every
+    /// garbage collected struct field references a live object of the field's type. Returns all
+    /// violations that were found.
+    ///
+    /// This is only available in debug builds, or with the `heap-validation` feature.
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let objects = self.objects.read();
+        let scopes = self.scopes.read();
+        let live: HashMap<GcPtr, &ObjectInfo<T>> = objects
+            .iter()
+            .map(|(handle, object)| (*handle, object.deref()))
+            .chain(
+                scopes
+                    .iter()
+                    .flat_map(|scope| scope.objects.iter())
+                    .map(|object| (object.handle(), object.deref())),
+            )
+            .collect();
+
+        let mut errors = Vec::new();
+        for (handle, object_info) in live.iter() {
+            validate_fields(
+                &live,
+                *handle,
+                &object_info.ty,
+                object_info.ptr,
+                "",
+                &mut errors,
+            );
+        }
+        errors
+    }
+}
+
+/// Validates the fields of the struct of type `ty` at `ptr`, which is (part of) `object`. The
+/// paths of the fields are prefixed with `prefix`.
+#[cfg(any(debug_assertions, feature = "heap-validation"))]
+fn validate_fields<T>(
+    live: &HashMap<GcPtr, &ObjectInfo<T>>,
+    object: GcPtr,
+    ty: &T,
+    ptr: *const u8,
+    prefix: &str,
+    errors: &mut Vec<ValidationError>,
+) where
+    T: TypeDesc + TypeFields<T> + TypeMemory + TypeTrace + Clone + Eq,
+{
+    if !ty.group().is_struct() {
+        return;
+    }
+
+    for ((name, field_ty), offset) in ty.fields().into_iter().zip(ty.offsets().iter()) {
+        if !field_ty.group().is_struct() {
+            continue;
+        }
+
+        let field = format!("{}{}", prefix, name);
+        let field_ptr = unsafe { ptr.add(usize::from(*offset)) };
+        if field_ty.is_stack_allocated() {
+            // Value structs are stored inline
+            let prefix = format!("{}.", field);
+            validate_fields(live, object, &field_ty, field_ptr, &prefix, errors);
+        } else {
+            let reference = unsafe { *field_ptr.cast::<GcPtr>() };
+            match live.get(&reference) {
+                None => errors.push(ValidationError::DanglingPointer {
+                    object,
+                    field,
+                    ptr: reference,
+                }),
+                Some(referenced) if referenced.ty != field_ty => {
+                    errors.push(ValidationError::MismatchedType {
+                        object,
+                        field,
+                        expected: field_ty.name().to_string(),
+                        found: referenced.ty.name().to_string(),
+                    })
+                }
+                Some(_) => (),
+            }
+        }
+    }
+}
+
+/// An allocation scope, which owns the objects that were allocated while it was open.
+#[derive(Debug)]
+struct Scope<T: TypeMemory + TypeTrace + Clone, A: Allocator> {
+    arena: Arena<A>,
+    objects: Vec<ObjectBox<T>>,
+}
+
+impl<T: TypeMemory + TypeTrace + Clone, A: Allocator> Scope<T, A> {
+    /// Constructs an empty scope whose arena allocates memory with `allocator`.
+    fn new(allocator: A) -> Self {
+        Scope {
+            arena: Arena::new(allocator),
+            objects: Vec::new(),
+        }
+    }
+}
+
+/// The objects on the heap, indexed by their handles.
+///
+/// The objects whose types can reference other objects are also indexed separately, so closing
+/// an allocation scope only traces the objects on the heap that can reference objects of the
+/// scope.
+#[derive(Debug)]
+struct Heap<T: TypeMemory + TypeTrace + Clone> {
+    objects: HashMap<GcPtr, ObjectBox<T>>,
+    referencing: HashSet<GcPtr>,
+}
+
+impl<T: TypeMemory + TypeTrace + Clone> Default for Heap<T> {
+    fn default() -> Self {
+        Heap {
+            objects: HashMap::new(),
+            referencing: HashSet::new(),
+        }
+    }
+}
+
+impl<T: TypeMemory + TypeTrace + Clone> Heap<T> {
+    /// Inserts `object` into the heap.
+    fn insert(&mut self, handle: GcPtr, object: ObjectBox<T>) {
+        if object.ty.has_references() {
+            self.referencing.insert(handle);
+        }
+        self.objects.insert(handle, object);
+    }
+
+    /// Retains only the objects for which `f` returns `true`.
+    fn retain(&mut self, mut f: impl FnMut(&GcPtr, &mut ObjectBox<T>) -> bool) {
+        let referencing = &mut self.referencing;
+        self.objects.retain(|handle, object| {
+            let retain = f(handle, object);
+            if !retain {
+                referencing.remove(handle);
+            }
+            retain
+        });
+    }
+
+    /// Returns the objects whose types can reference other objects.
+    fn referencing(&self) -> impl Iterator<Item = &ObjectBox<T>> {
+        self.referencing
+            .iter()
+            .map(move |handle| &self.objects[handle])
+    }
+
+    /// Rebuilds the index of objects whose types can reference other objects. This must be called
+    /// after changing the types of objects.
+    fn reindex(&mut self) {
+        self.referencing = self
+            .objects
+            .iter()
+            .filter(|(_, object)| object.ty.has_references())
+            .map(|(handle, _)| *handle)
+            .collect();
+    }
+
+    /// Shrinks the capacity of the heap's indices as much as possible.
+    fn shrink_to_fit(&mut self) {
+        self.objects.shrink_to_fit();
+        self.referencing.shrink_to_fit();
+    }
+}
+
+impl<T: TypeMemory + TypeTrace + Clone> Deref for Heap<T> {
+    type Target = HashMap<GcPtr, ObjectBox<T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.objects
+    }
+}
+
+impl<T: TypeMemory + TypeTrace + Clone> DerefMut for Heap<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.objects
+    }
+}
+
 /// Coloring used in the Mark Sweep phase.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum Color {
@@ -519,12 +1271,51 @@ struct ObjectInfo<T: TypeMemory + TypeTrace + Clone> {
     pub roots: u32,
     pub color: Color,
     pub ty: T,
+    /// The content hash under which the object is stored in the interning index, if it is
+    pub interned: Option<u64>,
+    pub age: u32,
 }
 
 /// An `ObjectInfo` is thread-safe.
 unsafe impl<T: TypeMemory + TypeTrace + Clone> Send for ObjectInfo<T> {}
 unsafe impl<T: TypeMemory + TypeTrace + Clone> Sync for ObjectInfo<T> {}
 
+/// An owned `ObjectInfo` that was allocated with the allocator of a `MarkSweep` collector, which
+/// must free it with `free_obj_info`. The `ObjectInfo` never moves, as its address is used as the
+/// object's handle.
+#[derive(Debug)]
+struct ObjectBox<T: TypeMemory + TypeTrace + Clone>(NonNull<ObjectInfo<T>>);
+
+/// An `ObjectBox` exclusively owns its `ObjectInfo`, so it is thread-safe.
+unsafe impl<T: TypeMemory + TypeTrace + Clone> Send for ObjectBox<T> {}
+unsafe impl<T: TypeMemory + TypeTrace + Clone> Sync for ObjectBox<T> {}
+
+impl<T: TypeMemory + TypeTrace + Clone> ObjectBox<T> {
+    /// Returns the handle of the object.
+    fn handle(&self) -> GcPtr {
+        (self.0.as_ptr() as RawGcPtr).into()
+    }
+
+    /// Returns a pointer to the `ObjectInfo`.
+    fn as_ptr(&self) -> *mut ObjectInfo<T> {
+        self.0.as_ptr()
+    }
+}
+
+impl<T: TypeMemory + TypeTrace + Clone> Deref for ObjectBox<T> {
+    type Target = ObjectInfo<T>;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.0.as_ref() }
+    }
+}
+
+impl<T: TypeMemory + TypeTrace + Clone> DerefMut for ObjectBox<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { self.0.as_mut() }
+    }
+}
+
 impl<T: TypeMemory + TypeTrace + Clone> Into<*const ObjectInfo<T>> for GcPtr {
     fn into(self) -> *const ObjectInfo<T> {
         self.as_ptr() as *const ObjectInfo<T>
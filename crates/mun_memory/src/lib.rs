@@ -1,4 +1,4 @@
-use std::alloc::Layout;
+use std::{alloc::Layout, ptr::NonNull};
 
 mod cast;
 pub mod diff;
@@ -34,4 +34,9 @@ pub trait TypeFields<T>: Send + Sync {
     fn fields(&self) -> Vec<(&str, T)>;
     /// Returns the type's fields' offsets.
     fn offsets(&self) -> &[u16];
+    /// Returns a pointer to the default value of the type's field at `index`, if it has one. The
+    /// default value is used to initialize the field when it is inserted during memory mapping.
+    fn field_default(&self, _index: usize) -> Option<NonNull<u8>> {
+        None
+    }
 }
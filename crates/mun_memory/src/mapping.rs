@@ -6,6 +6,7 @@ use crate::{
 use std::{
     collections::{HashMap, HashSet},
     hash::Hash,
+    ptr::NonNull,
 };
 
 pub struct Mapping<T: Eq + Hash, U: TypeDesc + TypeMemory> {
@@ -32,7 +33,7 @@ pub struct FieldMapping<T: TypeDesc + TypeMemory> {
 pub enum Action<T: TypeDesc + TypeMemory> {
     Cast { old_offset: usize, old_ty: T },
     Copy { old_offset: usize },
-    Insert,
+    Insert { default: Option<NonNull<u8>> },
 }
 
 impl<T> Mapping<T, T>
@@ -202,7 +203,7 @@ pub unsafe fn field_mapping<T: Clone + TypeDesc + TypeFields<T> + TypeMemory>(
                 FieldMappingDesc {
                     old_index: Some(*old_index),
                     action: edit.as_ref().map_or(ActionDesc::Copy, |kind| {
-                        if *kind == FieldEditKind::ConvertType {
+                        if let FieldEditKind::ConvertType { .. } = kind {
                             ActionDesc::Cast
                         } else {
                             ActionDesc::Copy
@@ -224,7 +225,7 @@ pub unsafe fn field_mapping<T: Clone + TypeDesc + TypeFields<T> + TypeMemory>(
     for diff in diff.iter() {
         if let FieldDiff::Edit { index, kind } = diff {
             let map = mapping.get_mut(*index).unwrap();
-            map.action = if *kind == FieldEditKind::ConvertType {
+            map.action = if let FieldEditKind::ConvertType { .. } = kind {
                 ActionDesc::Cast
             } else {
                 ActionDesc::Copy
@@ -255,7 +256,9 @@ pub unsafe fn field_mapping<T: Clone + TypeDesc + TypeFields<T> + TypeMemory>(
                         ActionDesc::Copy => Action::Copy {
                             old_offset: old_offset.unwrap(),
                         },
-                        ActionDesc::Insert => Action::Insert,
+                        ActionDesc::Insert => Action::Insert {
+                            default: new_ty.field_default(new_index),
+                        },
                     },
                 }
             })
@@ -414,15 +414,24 @@ fn cast_field() {
             diff: vec![
                 FieldDiff::Edit {
                     index: 0,
-                    kind: FieldEditKind::ConvertType,
+                    kind: FieldEditKind::ConvertType {
+                        old_guid: int.guid,
+                        new_guid: float.guid,
+                    },
                 },
                 FieldDiff::Edit {
                     index: 1,
-                    kind: FieldEditKind::ConvertType,
+                    kind: FieldEditKind::ConvertType {
+                        old_guid: float.guid,
+                        new_guid: int.guid,
+                    },
                 },
                 FieldDiff::Edit {
                     index: 2,
-                    kind: FieldEditKind::ConvertType,
+                    kind: FieldEditKind::ConvertType {
+                        old_guid: float.guid,
+                        new_guid: int.guid,
+                    },
                 }
             ],
             old_index: 0,
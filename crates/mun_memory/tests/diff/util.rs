@@ -252,7 +252,11 @@ fn apply_mapping<'t>(old: &mut TypeInfo, new: &TypeInfo, mapping: &[FieldDiff])
                 new_field: &(String, TypeInfo),
             ) {
                 match *kind {
-                    FieldEditKind::ConvertType => old_field.1 = new_field.1.clone(),
+                    FieldEditKind::ConvertType { old_guid, new_guid } => {
+                        assert_eq!(old_guid, old_field.1.guid);
+                        assert_eq!(new_guid, new_field.1.guid);
+                        old_field.1 = new_field.1.clone();
+                    }
                     FieldEditKind::Rename => old_field.0 = new_field.0.clone(),
                 }
             }
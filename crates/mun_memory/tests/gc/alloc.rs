@@ -1,6 +1,12 @@
 use super::util::{EventAggregator, HasTypeInfo, TypeInfo};
-use mun_memory::gc::{Event, GcRootPtr, GcRuntime, MarkSweep};
-use std::sync::Arc;
+use mun_memory::gc::{Allocator, Event, GcRootPtr, GcRuntime, HasIndirectionPtr, MarkSweep};
+use std::{
+    alloc::Layout,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
 
 #[test]
 fn alloc() {
@@ -63,3 +69,336 @@ fn collect_rooted() {
     assert_eq!(events.next(), Some(Event::End));
     assert_eq!(events.next(), None);
 }
+
+#[test]
+fn intern_identical_values() {
+    let runtime = MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default()
+        .with_value_interning(true);
+
+    let first = runtime.alloc_interned(i64::type_info(), &5i64.to_ne_bytes());
+    let second = runtime.alloc_interned(i64::type_info(), &5i64.to_ne_bytes());
+    let third = runtime.alloc_interned(i64::type_info(), &6i64.to_ne_bytes());
+    assert_eq!(second, first);
+    assert_ne!(third, first);
+    unsafe { assert_eq!(*third.deref::<i64>(), 6) };
+
+    // No memory is allocated for identical values
+    let mut events = runtime.observer().take_all().into_iter();
+    assert_eq!(events.next(), Some(Event::Allocation(first)));
+    assert_eq!(events.next(), Some(Event::Allocation(third)));
+    assert_eq!(events.next(), None);
+}
+
+#[test]
+fn unshare_shared_value() {
+    let runtime = Arc::new(
+        MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default()
+            .with_value_interning(true),
+    );
+
+    let first = GcRootPtr::new(
+        &runtime,
+        runtime.alloc_interned(i64::type_info(), &5i64.to_ne_bytes()),
+    );
+    let shared = GcRootPtr::new(
+        &runtime,
+        runtime.alloc_interned(i64::type_info(), &5i64.to_ne_bytes()),
+    );
+    assert_eq!(shared.handle(), first.handle());
+
+    // Mutating a shared object requires a private copy
+    let mut copy = runtime.unshare(shared.handle());
+    assert_ne!(copy, first.handle());
+    unsafe {
+        assert_eq!(*copy.deref::<i64>(), 5);
+        *copy.deref_mut::<i64>() = 6;
+        assert_eq!(*first.deref::<i64>(), 5);
+    }
+
+    // Once the last other reference is gone, the object is no longer shared
+    drop(shared);
+    assert_eq!(runtime.unshare(first.handle()), first.handle());
+
+    // An object that is mutated in place is removed from the index
+    let other = runtime.alloc_interned(i64::type_info(), &5i64.to_ne_bytes());
+    assert_ne!(other, first.handle());
+}
+
+#[test]
+fn intern_free_and_reallocate() {
+    let runtime = Arc::new(
+        MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default()
+            .with_value_interning(true),
+    );
+
+    let first = runtime.alloc_interned(i64::type_info(), &5i64.to_ne_bytes());
+    let kept = GcRootPtr::new(
+        &runtime,
+        runtime.alloc_interned(i64::type_info(), &6i64.to_ne_bytes()),
+    );
+    assert!(runtime.collect());
+
+    // The freed object was removed from the index, so an identical value is allocated anew
+    let second = runtime.alloc_interned(i64::type_info(), &5i64.to_ne_bytes());
+    unsafe { assert_eq!(*second.deref::<i64>(), 5) };
+    assert_eq!(
+        runtime.alloc_interned(i64::type_info(), &5i64.to_ne_bytes()),
+        second
+    );
+    assert_eq!(
+        runtime.alloc_interned(i64::type_info(), &6i64.to_ne_bytes()),
+        kept.handle()
+    );
+
+    let mut events = runtime.observer().take_all().into_iter();
+    assert_eq!(events.next(), Some(Event::Allocation(first)));
+    assert_eq!(events.next(), Some(Event::Allocation(kept.handle())));
+    assert_eq!(events.next(), Some(Event::Start));
+    assert_eq!(events.next(), Some(Event::Deallocation(first)));
+    assert_eq!(events.next(), Some(Event::End));
+    assert_eq!(events.next(), Some(Event::Allocation(second)));
+    assert_eq!(events.next(), None);
+}
+
+#[test]
+fn intern_disabled() {
+    let runtime = MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default();
+
+    let first = runtime.alloc_interned(i64::type_info(), &5i64.to_ne_bytes());
+    let second = runtime.alloc_interned(i64::type_info(), &5i64.to_ne_bytes());
+
+    assert_ne!(first, second);
+    assert_eq!(runtime.unshare(second), second);
+}
+
+#[test]
+fn object_age() {
+    let runtime = Arc::new(
+        MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default().with_age_tracking(true),
+    );
+    assert!(runtime.is_age_tracking_enabled());
+
+    let handle = runtime.alloc(i64::type_info());
+    let rooted = GcRootPtr::new(&runtime, handle);
+    assert_eq!(runtime.object_age(handle), Some(0));
+
+    runtime.collect();
+    assert_eq!(runtime.object_age(handle), Some(1));
+
+    runtime.collect();
+    assert_eq!(runtime.object_age(handle), Some(2));
+
+    // Unreachable objects no longer have an age
+    drop(rooted);
+    runtime.collect();
+    assert_eq!(runtime.object_age(handle), None);
+}
+
+#[test]
+fn object_age_disabled() {
+    let runtime = Arc::new(MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default());
+    assert!(!runtime.is_age_tracking_enabled());
+
+    let handle = runtime.alloc(i64::type_info());
+    let _rooted = GcRootPtr::new(&runtime, handle);
+
+    runtime.collect();
+    assert_eq!(runtime.object_age(handle), None);
+}
+
+#[test]
+fn roots() {
+    let runtime = Arc::new(MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default());
+    let first = runtime.alloc(i64::type_info());
+    let second = runtime.alloc(i64::type_info());
+    let _unrooted = runtime.alloc(i64::type_info());
+
+    let first_root = GcRootPtr::new(&runtime, first);
+    let _second_roots = [
+        GcRootPtr::new(&runtime, second),
+        GcRootPtr::new(&runtime, second),
+    ];
+
+    let mut roots = runtime.roots();
+    roots.sort_by_key(|(_, count)| *count);
+    assert_eq!(roots, [(first, 1), (second, 2)]);
+
+    drop(first_root);
+    assert_eq!(runtime.roots(), [(second, 2)]);
+}
+
+#[test]
+fn unbalanced_enable_collection() {
+    let runtime = MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default();
+
+    let result =
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| runtime.enable_collection()));
+    assert!(result.is_err());
+
+    // The failed call must not have changed whether collection is disabled
+    assert!(runtime.is_collection_enabled());
+    runtime.disable_collection();
+    assert!(!runtime.is_collection_enabled());
+    runtime.enable_collection();
+    assert!(runtime.is_collection_enabled());
+}
+
+#[test]
+fn scope_bulk_free() {
+    let runtime = MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default();
+    let outside = runtime.alloc(i64::type_info());
+
+    runtime.begin_scope();
+    assert_eq!(runtime.scope_depth(), 1);
+    let first = runtime.alloc(i64::type_info());
+    let second = runtime.alloc(i32::type_info());
+
+    // Objects allocated in a scope do not participate in collection
+    runtime.collect();
+    assert_eq!(
+        runtime.stats().allocated_memory,
+        std::mem::size_of::<i64>() + std::mem::size_of::<i32>()
+    );
+
+    runtime.end_scope();
+    assert_eq!(runtime.scope_depth(), 0);
+    assert_eq!(runtime.stats().allocated_memory, 0);
+
+    let mut events = runtime.observer().take_all().into_iter();
+    assert_eq!(events.next(), Some(Event::Allocation(outside)));
+    assert_eq!(events.next(), Some(Event::Allocation(first)));
+    assert_eq!(events.next(), Some(Event::Allocation(second)));
+    assert_eq!(events.next(), Some(Event::Start));
+    assert_eq!(events.next(), Some(Event::Deallocation(outside)));
+    assert_eq!(events.next(), Some(Event::End));
+    assert_eq!(events.next(), Some(Event::Deallocation(first)));
+    assert_eq!(events.next(), Some(Event::Deallocation(second)));
+    assert_eq!(events.next(), None);
+}
+
+#[test]
+fn scope_rooted_escape() {
+    let runtime = Arc::new(MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default());
+
+    runtime.begin_scope();
+    let mut handle = runtime.alloc(i64::type_info());
+    unsafe { *handle.deref_mut::<i64>() = 42 };
+    let rooted = GcRootPtr::new(&runtime, handle);
+    let temporary = runtime.alloc(i64::type_info());
+    runtime.end_scope();
+
+    // The rooted object was moved to the heap, so it remains valid
+    assert_eq!(unsafe { *rooted.deref::<i64>() }, 42);
+
+    let rooted_handle = rooted.unroot();
+    runtime.collect();
+
+    let mut events = runtime.observer().take_all().into_iter();
+    assert_eq!(events.next(), Some(Event::Allocation(handle)));
+    assert_eq!(events.next(), Some(Event::Allocation(temporary)));
+    assert_eq!(events.next(), Some(Event::Deallocation(temporary)));
+    assert_eq!(events.next(), Some(Event::Start));
+    assert_eq!(events.next(), Some(Event::Deallocation(rooted_handle)));
+    assert_eq!(events.next(), Some(Event::End));
+    assert_eq!(events.next(), None);
+}
+
+#[test]
+fn scope_pinned_escape() {
+    let runtime = Arc::new(MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default());
+
+    runtime.begin_scope();
+    let mut handle = runtime.alloc(i64::type_info());
+    unsafe { *handle.deref_mut::<i64>() = 42 };
+    let rooted = GcRootPtr::new(&runtime, handle);
+    runtime.pin(handle);
+
+    // The pinned object was moved to the heap, so closing the scope does not relocate it
+    let ptr = unsafe { rooted.deref::<i64>() };
+    runtime.end_scope();
+    assert_eq!(unsafe { rooted.deref::<i64>() }, ptr);
+    assert_eq!(unsafe { *ptr }, 42);
+}
+
+/// An allocator that counts the number of allocations, deallocations, and trims.
+#[derive(Clone, Default)]
+struct CountingAllocator {
+    allocations: Arc<AtomicUsize>,
+    deallocations: Arc<AtomicUsize>,
+    trims: Arc<AtomicUsize>,
+}
+
+unsafe impl Allocator for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.allocations.fetch_add(1, Ordering::SeqCst);
+        std::alloc::alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.deallocations.fetch_add(1, Ordering::SeqCst);
+        std::alloc::dealloc(ptr, layout)
+    }
+
+    fn trim(&self) {
+        self.trims.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl CountingAllocator {
+    fn counts(&self) -> (usize, usize) {
+        (
+            self.allocations.load(Ordering::SeqCst),
+            self.deallocations.load(Ordering::SeqCst),
+        )
+    }
+}
+
+#[test]
+fn custom_allocator() {
+    let allocator = CountingAllocator::default();
+    let runtime =
+        MarkSweep::<&'static TypeInfo, EventAggregator<Event>, _>::with_observer_and_allocator(
+            EventAggregator::default(),
+            allocator.clone(),
+        );
+
+    // Both the object and its metadata are allocated with the allocator
+    runtime.alloc(i64::type_info());
+    assert_eq!(allocator.counts(), (2, 0));
+
+    runtime.collect();
+    assert_eq!(allocator.counts(), (2, 2));
+
+    // Objects allocated in a scope are allocated in a chunk of the scope's arena
+    runtime.begin_scope();
+    runtime.alloc(i64::type_info());
+    runtime.alloc(i64::type_info());
+    assert_eq!(allocator.counts(), (5, 2));
+    runtime.end_scope();
+    assert_eq!(allocator.counts(), (5, 5));
+}
+
+#[test]
+fn shrink_to_fit() {
+    let allocator = CountingAllocator::default();
+    let runtime =
+        MarkSweep::<&'static TypeInfo, EventAggregator<Event>, _>::with_observer_and_allocator(
+            EventAggregator::default(),
+            allocator.clone(),
+        );
+
+    let rooted = GcRootPtr::new(&runtime, runtime.alloc(i64::type_info()));
+    for _ in 0..1000 {
+        runtime.alloc(i64::type_info());
+    }
+    runtime.collect();
+    runtime.shrink_to_fit();
+    assert_eq!(allocator.trims.load(Ordering::SeqCst), 1);
+
+    // Only the rooted object remains, and the collector can still allocate
+    assert_eq!(runtime.stats().allocated_memory, std::mem::size_of::<i64>());
+    runtime.alloc(i64::type_info());
+    runtime.collect();
+    assert_eq!(runtime.stats().allocated_memory, std::mem::size_of::<i64>());
+    drop(rooted);
+}
@@ -93,3 +93,35 @@ fn trace_cycle() {
     assert_eq!(events.next(), Some(Event::End));
     assert_eq!(events.next(), None);
 }
+
+#[test]
+fn scope_referenced_escape() {
+    let runtime = Arc::new(MarkSweep::<&'static TypeInfo, EventAggregator<Event>>::default());
+    let mut foo = GcRootPtr::new(&runtime, runtime.alloc(Foo::type_info()));
+
+    runtime.begin_scope();
+    let mut bar = runtime.alloc(i64::type_info());
+    unsafe {
+        *bar.deref_mut::<i64>() = 42;
+        (*foo.deref_mut::<Foo>()).bar = bar;
+    }
+    let temporary = runtime.alloc(i64::type_info());
+    runtime.end_scope();
+
+    // bar is referenced by an object on the heap, so it was moved to the heap
+    assert_eq!(unsafe { *(*foo.deref::<Foo>()).bar.deref::<i64>() }, 42);
+
+    let foo = foo.unroot();
+    runtime.collect();
+
+    let mut events = runtime.observer().take_all().into_iter();
+    assert_eq!(events.next(), Some(Event::Allocation(foo)));
+    assert_eq!(events.next(), Some(Event::Allocation(bar)));
+    assert_eq!(events.next(), Some(Event::Allocation(temporary)));
+    assert_eq!(events.next(), Some(Event::Deallocation(temporary)));
+    assert_eq!(events.next(), Some(Event::Start));
+    assert_variant!(events.next(), Some(Event::Deallocation(..)));
+    assert_variant!(events.next(), Some(Event::Deallocation(..)));
+    assert_eq!(events.next(), Some(Event::End));
+    assert_eq!(events.next(), None);
+}
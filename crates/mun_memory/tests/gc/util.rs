@@ -10,6 +10,20 @@ pub struct TypeInfo {
     pub tracer: Option<&'static fn(handle: GcPtr) -> Vec<GcPtr>>,
 }
 
+impl PartialEq for TypeInfo {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl Eq for TypeInfo {}
+
+impl std::hash::Hash for TypeInfo {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        (self as *const TypeInfo).hash(state)
+    }
+}
+
 pub trait Trace {
     /// Called to collect all GC handles in the type
     fn trace(&self, handles: &mut Vec<GcPtr>);
@@ -95,6 +109,10 @@ impl gc::TypeTrace for &'static TypeInfo {
         };
         handles.into_iter()
     }
+
+    fn has_references(&self) -> bool {
+        self.tracer.is_some()
+    }
 }
 
 pub struct EventAggregator<T: Sync + Send + Sized> {
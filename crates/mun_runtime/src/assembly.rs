@@ -1,7 +1,7 @@
 use std::io;
 use std::path::{Path, PathBuf};
 
-use crate::DispatchTable;
+use crate::{DispatchTable, FieldRemap};
 use abi::AssemblyInfo;
 use libloading::Symbol;
 
@@ -9,8 +9,15 @@ mod temp_library;
 
 use self::temp_library::TempLibrary;
 use crate::garbage_collector::{GarbageCollector, UnsafeTypeInfo};
-use memory::mapping::{Mapping, MemoryMapper};
-use std::{collections::HashSet, ptr::NonNull, sync::Arc};
+use memory::{
+    mapping::{Action, Mapping, MemoryMapper},
+    TypeDesc, TypeFields,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    ptr::NonNull,
+    sync::Arc,
+};
 
 /// An assembly is a hot reloadable compilation unit, consisting of one or more Mun modules.
 pub struct Assembly {
@@ -18,6 +25,8 @@ pub struct Assembly {
     library: TempLibrary,
     legacy_libs: Vec<TempLibrary>,
     info: AssemblyInfo,
+    debug_info: Option<NonNull<abi::DebugInfo>>,
+    opt_level: Option<u8>,
     allocator: Arc<GarbageCollector>,
 }
 
@@ -31,6 +40,33 @@ impl Assembly {
     ) -> Result<Self, failure::Error> {
         let library = TempLibrary::new(library_path)?;
 
+        // Check whether the library was compiled against the same ABI, before reading any of its
+        // ABI types
+        let get_version: Symbol<'_, extern "C" fn() -> u32> =
+            unsafe { library.library().get(b"get_version") }.map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "Failed to load assembly `{}`: the assembly does not specify its ABI \
+                         version.",
+                        library_path.display()
+                    ),
+                )
+            })?;
+        let version = get_version();
+        if version != abi::ABI_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Failed to load assembly `{}`: ABI version mismatch. Expected: {}. Found: {}.",
+                    library_path.display(),
+                    abi::ABI_VERSION,
+                    version
+                ),
+            )
+            .into());
+        }
+
         // Check whether the library has a symbols function
         let get_info: Symbol<'_, extern "C" fn() -> AssemblyInfo> =
             unsafe { library.library().get(b"get_info") }?;
@@ -41,12 +77,24 @@ impl Assembly {
         let allocator_ptr = Arc::into_raw(gc.clone()) as *mut std::ffi::c_void;
         set_allocator_handle(allocator_ptr);
 
+        // Debug information is optional, so its absence is not an error
+        let debug_info = unsafe { library.library().get::<*mut abi::DebugInfo>(b"debug_info") }
+            .ok()
+            .and_then(|symbol| NonNull::new(*symbol));
+
+        // Assemblies compiled before the optimization level was exported do not contain it
+        let opt_level = unsafe { library.library().get::<*const u8>(b"opt_level") }
+            .ok()
+            .and_then(|symbol| unsafe { (*symbol).as_ref() }.copied());
+
         let info = get_info();
         let assembly = Assembly {
             library_path: library_path.to_path_buf(),
             library,
             legacy_libs: Vec::new(),
             info,
+            debug_info,
+            opt_level,
             allocator: gc,
         };
 
@@ -146,11 +194,14 @@ impl Assembly {
     }
 
     /// Swaps the assembly's shared library and its information for the library at `library_path`.
+    ///
+    /// Returns how the fields of struct types whose layout changed were remapped, keyed by the
+    /// name of their struct type.
     pub fn swap(
         &mut self,
         library_path: &Path,
         runtime_dispatch_table: &mut DispatchTable,
-    ) -> Result<(), failure::Error> {
+    ) -> Result<HashMap<String, Vec<(String, FieldRemap)>>, failure::Error> {
         let mut new_assembly =
             Assembly::load(library_path, self.allocator.clone(), runtime_dispatch_table)?;
 
@@ -181,6 +232,30 @@ impl Assembly {
             .collect();
 
         let mapping = Mapping::new(&old_types, &new_types);
+        let remapping = mapping
+            .conversions
+            .values()
+            .map(|conversion| {
+                let fields = conversion
+                    .new_ty
+                    .fields()
+                    .into_iter()
+                    .zip(conversion.field_mapping.iter())
+                    .map(|((name, _), field_mapping)| {
+                        let remap = match &field_mapping.action {
+                            Action::Copy { .. } => FieldRemap::Copied,
+                            Action::Cast { old_ty, .. } => FieldRemap::Converted {
+                                old_type: old_ty.name().to_string(),
+                            },
+                            Action::Insert { default: Some(_) } => FieldRemap::Defaulted,
+                            Action::Insert { default: None } => FieldRemap::ZeroInitialized,
+                        };
+                        (name.to_string(), remap)
+                    })
+                    .collect();
+                (conversion.new_ty.name().to_string(), fields)
+            })
+            .collect();
         let deleted_objects = self.allocator.map_memory(mapping);
 
         // Remove the old assembly's functions
@@ -201,7 +276,7 @@ impl Assembly {
             self.legacy_libs.push(old_assembly.into_library());
         }
 
-        Ok(())
+        Ok(remapping)
     }
 
     /// Returns the assembly's information.
@@ -209,6 +284,18 @@ impl Assembly {
         &self.info
     }
 
+    /// Returns the assembly's debug information, if it was emitted by the compiler.
+    pub fn debug_info(&self) -> Option<&abi::DebugInfo> {
+        // Safety: the debug information lives as long as the assembly's library.
+        self.debug_info.map(|ptr| unsafe { &*ptr.as_ptr() })
+    }
+
+    /// Returns the optimization level that the assembly was compiled with, if it was exported by
+    /// the compiler.
+    pub fn opt_level(&self) -> Option<u8> {
+        self.opt_level
+    }
+
     /// Returns the path corresponding to the assembly's library.
     pub fn library_path(&self) -> &Path {
         self.library_path.as_path()
@@ -0,0 +1,216 @@
+use crate::{
+    marshal::{Marshal, MarshalArgument, MarshalError},
+    ArgumentReflection, ReturnTypeReflection, Runtime,
+};
+use std::{cell::RefCell, fmt, iter::FromIterator, marker::PhantomData, mem, ptr::NonNull, rc::Rc};
+
+/// A flag that can be stored in [`BitFlags`].
+///
+/// [`BitFlags`]: struct.BitFlags.html
+pub trait Flag: Copy {
+    /// Returns the index of the bit that represents this flag, where bit `0` is the least
+    /// significant bit of the integer. Every flag of a type must have a unique index that is
+    /// smaller than the number of bits of the integer it is stored in.
+    fn bit_index(self) -> u32;
+}
+
+/// A set of flags of type `T`, which is passed to and returned from Mun as a single integer
+/// bitmask of type `B` - either `u32` or `u64`.
+///
+/// # Bit ordering
+///
+/// A flag is set if, and only if, the bit at its [`Flag::bit_index`] is set, where bit `0` is the
+/// least significant bit. In Mun, the flag with index `n` is thus tested with
+/// `flags & (1 << n) != 0`, independent of the endianness of the target. Bits that do not
+/// correspond to a flag are preserved when marshalling in either direction.
+///
+/// ```
+/// use mun_runtime::{BitFlags, Flag};
+///
+/// #[derive(Clone, Copy)]
+/// enum Options {
+///     Verbose = 0,
+///     DryRun = 1,
+/// }
+///
+/// impl Flag for Options {
+///     fn bit_index(self) -> u32 {
+///         self as u32
+///     }
+/// }
+///
+/// let options: BitFlags<Options> = [Options::DryRun].iter().copied().collect();
+/// assert!(options.contains(Options::DryRun));
+/// assert!(!options.contains(Options::Verbose));
+/// assert_eq!(options.bits(), 0b10);
+/// ```
+///
+/// [`Flag::bit_index`]: trait.Flag.html#tymethod.bit_index
+pub struct BitFlags<T: Flag, B = u32> {
+    bits: B,
+    _flag: PhantomData<T>,
+}
+
+macro_rules! impl_bit_flags {
+    ($($bits:ty),+) => {
+        $(
+            impl<T: Flag> BitFlags<T, $bits> {
+                /// Constructs a set without any flags.
+                pub fn empty() -> Self {
+                    Self::from_bits(0)
+                }
+
+                /// Constructs a set from its bitmask.
+                pub fn from_bits(bits: $bits) -> Self {
+                    Self {
+                        bits,
+                        _flag: PhantomData,
+                    }
+                }
+
+                /// Returns the bitmask of the set.
+                pub fn bits(&self) -> $bits {
+                    self.bits
+                }
+
+                /// Returns whether `flag` is set.
+                pub fn contains(&self, flag: T) -> bool {
+                    self.bits & Self::mask(flag) != 0
+                }
+
+                /// Sets `flag` if `value` is `true`, and clears it otherwise.
+                pub fn set(&mut self, flag: T, value: bool) {
+                    if value {
+                        self.insert(flag);
+                    } else {
+                        self.remove(flag);
+                    }
+                }
+
+                /// Sets `flag`.
+                pub fn insert(&mut self, flag: T) {
+                    self.bits |= Self::mask(flag);
+                }
+
+                /// Clears `flag`.
+                pub fn remove(&mut self, flag: T) {
+                    self.bits &= !Self::mask(flag);
+                }
+
+                /// Returns the bitmask of `flag`.
+                ///
+                /// # Panics
+                ///
+                /// Panics if the bit index of `flag` does not fit in the bitmask.
+                fn mask(flag: T) -> $bits {
+                    let index = flag.bit_index();
+                    assert!(
+                        (index as usize) < mem::size_of::<$bits>() * 8,
+                        "Flag index `{}` does not fit in a `{}`.",
+                        index,
+                        <$bits as ReturnTypeReflection>::type_name()
+                    );
+                    1 << index
+                }
+            }
+
+            impl<T: Flag> Default for BitFlags<T, $bits> {
+                fn default() -> Self {
+                    Self::empty()
+                }
+            }
+
+            impl<T: Flag> FromIterator<T> for BitFlags<T, $bits> {
+                fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+                    let mut flags = Self::empty();
+                    for flag in iter {
+                        flags.insert(flag);
+                    }
+                    flags
+                }
+            }
+
+            impl<T: Flag> fmt::Debug for BitFlags<T, $bits> {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    write!(f, "BitFlags({:#b})", self.bits)
+                }
+            }
+
+            impl<T: Flag> ArgumentReflection for BitFlags<T, $bits> {
+                type Marshalled = $bits;
+
+                fn type_guid(&self, _runtime: &Runtime) -> abi::Guid {
+                    <$bits as ReturnTypeReflection>::type_guid()
+                }
+
+                fn type_name(&self, _runtime: &Runtime) -> &str {
+                    <$bits as ReturnTypeReflection>::type_name()
+                }
+
+                fn marshal(self, _runtime: &Runtime, _type_info: &abi::TypeInfo) -> Self::Marshalled {
+                    self.bits
+                }
+            }
+
+            impl<T: Flag> ReturnTypeReflection for BitFlags<T, $bits> {
+                type Marshalled = $bits;
+
+                fn type_guid() -> abi::Guid {
+                    <$bits as ReturnTypeReflection>::type_guid()
+                }
+
+                fn type_name() -> &'static str {
+                    <$bits as ReturnTypeReflection>::type_name()
+                }
+            }
+
+            impl<T: Flag> Marshal<BitFlags<T, $bits>> for $bits {
+                fn marshal_value(
+                    self,
+                    _runtime: Rc<RefCell<Runtime>>,
+                ) -> Result<BitFlags<T, $bits>, MarshalError> {
+                    Ok(BitFlags::from_bits(self))
+                }
+
+                fn marshal_from_ptr(
+                    ptr: NonNull<Self>,
+                    _runtime: Rc<RefCell<Runtime>>,
+                    _type_info: Option<&abi::TypeInfo>,
+                ) -> Result<BitFlags<T, $bits>, MarshalError> {
+                    Ok(BitFlags::from_bits(unsafe { ptr.as_ptr().read() }))
+                }
+            }
+
+            impl<T: Flag> MarshalArgument<BitFlags<T, $bits>> for $bits {
+                fn marshal_to_ptr(
+                    value: Self,
+                    mut ptr: NonNull<Self>,
+                    _type_info: Option<&abi::TypeInfo>,
+                ) {
+                    unsafe { *ptr.as_mut() = value };
+                }
+            }
+        )+
+    };
+}
+
+impl_bit_flags!(u32, u64);
+
+impl<T: Flag, B: Clone> Clone for BitFlags<T, B> {
+    fn clone(&self) -> Self {
+        Self {
+            bits: self.bits.clone(),
+            _flag: PhantomData,
+        }
+    }
+}
+
+impl<T: Flag, B: Copy> Copy for BitFlags<T, B> {}
+
+impl<T: Flag, B: PartialEq> PartialEq for BitFlags<T, B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bits == other.bits
+    }
+}
+
+impl<T: Flag, B: Eq> Eq for BitFlags<T, B> {}
@@ -0,0 +1,204 @@
+use crate::{
+    marshal::Marshal,
+    reflection::{marshal_argument_to_ptr, ErasedArgument},
+    struct_ref::RawStruct,
+    ArgumentReflection, DynArgument, ReturnTypeReflection, Runtime, StructRef,
+};
+use std::{cell::RefCell, ptr::NonNull, rc::Rc};
+
+/// A dynamically typed value that can be stored in the field of a Mun struct.
+///
+/// A `DynValue` can be converted into a [`DynArgument`], which uses the same marshalling.
+///
+/// [`DynArgument`]: struct.DynArgument.html
+#[derive(Clone)]
+pub enum DynValue {
+    /// An `i8` value
+    I8(i8),
+    /// An `i16` value
+    I16(i16),
+    /// An `i32` value
+    I32(i32),
+    /// An `i64` value
+    I64(i64),
+    /// An `i128` value
+    I128(i128),
+    /// An `isize` value
+    Isize(isize),
+    /// A `u8` value
+    U8(u8),
+    /// A `u16` value
+    U16(u16),
+    /// A `u32` value
+    U32(u32),
+    /// A `u64` value
+    U64(u64),
+    /// A `u128` value
+    U128(u128),
+    /// A `usize` value
+    Usize(usize),
+    /// An `f32` value
+    F32(f32),
+    /// An `f64` value
+    F64(f64),
+    /// A `bool` value
+    Bool(bool),
+    /// A Mun struct
+    Struct(StructRef),
+}
+
+/// Evaluates `$body` for the value contained in the `DynValue`, which is bound to `$inner`.
+macro_rules! dispatch {
+    ($value:expr, $inner:ident => $body:expr) => {
+        match $value {
+            DynValue::I8($inner) => $body,
+            DynValue::I16($inner) => $body,
+            DynValue::I32($inner) => $body,
+            DynValue::I64($inner) => $body,
+            DynValue::I128($inner) => $body,
+            DynValue::Isize($inner) => $body,
+            DynValue::U8($inner) => $body,
+            DynValue::U16($inner) => $body,
+            DynValue::U32($inner) => $body,
+            DynValue::U64($inner) => $body,
+            DynValue::U128($inner) => $body,
+            DynValue::Usize($inner) => $body,
+            DynValue::F32($inner) => $body,
+            DynValue::F64($inner) => $body,
+            DynValue::Bool($inner) => $body,
+            DynValue::Struct($inner) => $body,
+        }
+    };
+}
+
+impl DynValue {
+    /// Retrieves the `Guid` of the value's type.
+    pub fn type_guid(&self, runtime: &Runtime) -> abi::Guid {
+        dispatch!(self, value => ArgumentReflection::type_guid(value, runtime))
+    }
+
+    /// Retrieves the name of the value's type.
+    pub fn type_name<'r>(&'r self, runtime: &'r Runtime) -> &'r str {
+        dispatch!(self, value => ArgumentReflection::type_name(value, runtime))
+    }
+
+    /// Marshals the value to memory location `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to memory that is valid for a value of type `type_info`, which must match
+    /// the type of the value.
+    pub(crate) unsafe fn marshal_to_ptr(
+        self,
+        ptr: NonNull<u8>,
+        runtime: &Runtime,
+        type_info: &abi::TypeInfo,
+    ) {
+        dispatch!(self, value => marshal_argument_to_ptr(value, ptr, runtime, type_info))
+    }
+
+    /// Marshals the value of type `type_info` at memory location `ptr` into a `DynValue`, or
+    /// returns `None` if values of the type cannot be represented by a `DynValue`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid value of type `type_info`.
+    pub(crate) unsafe fn marshal_from_ptr(
+        ptr: NonNull<u8>,
+        runtime: Rc<RefCell<Runtime>>,
+        type_info: &abi::TypeInfo,
+    ) -> Option<Self> {
+        macro_rules! marshal_fundamental {
+            ($($variant:ident($ty:ty)),+) => {
+                $(
+                    if type_info.guid == <$ty as ReturnTypeReflection>::type_guid() {
+                        return Some(DynValue::$variant(ptr.cast::<$ty>().as_ptr().read()));
+                    }
+                )+
+            }
+        }
+
+        match type_info.group {
+            abi::TypeGroup::FundamentalTypes => {
+                marshal_fundamental!(
+                    I8(i8),
+                    I16(i16),
+                    I32(i32),
+                    I64(i64),
+                    I128(i128),
+                    U8(u8),
+                    U16(u16),
+                    U32(u32),
+                    U64(u64),
+                    U128(u128),
+                    F32(f32),
+                    F64(f64),
+                    Bool(bool)
+                );
+                None
+            }
+            // Marshalling a `StructRef` never fails
+            abi::TypeGroup::StructTypes => {
+                Marshal::marshal_from_ptr(ptr.cast::<RawStruct>(), runtime, Some(type_info))
+                    .ok()
+                    .map(DynValue::Struct)
+            }
+        }
+    }
+}
+
+impl ErasedArgument for DynValue {
+    fn type_guid(&self, runtime: &Runtime) -> abi::Guid {
+        DynValue::type_guid(self, runtime)
+    }
+
+    fn type_name<'r>(&'r self, runtime: &'r Runtime) -> &'r str {
+        DynValue::type_name(self, runtime)
+    }
+
+    unsafe fn marshal_to_ptr(
+        self: Box<Self>,
+        ptr: NonNull<u8>,
+        runtime: &Runtime,
+        type_info: &abi::TypeInfo,
+    ) {
+        DynValue::marshal_to_ptr(*self, ptr, runtime, type_info)
+    }
+}
+
+impl From<DynValue> for DynArgument {
+    fn from(value: DynValue) -> Self {
+        DynArgument(Box::new(value))
+    }
+}
+
+macro_rules! impl_from {
+    ($($variant:ident($ty:ty)),+) => {
+        $(
+            impl From<$ty> for DynValue {
+                fn from(value: $ty) -> Self {
+                    DynValue::$variant(value)
+                }
+            }
+        )+
+    }
+}
+
+impl_from!(
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    Isize(isize),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    Usize(usize),
+    F32(f32),
+    F64(f64),
+    Bool(bool),
+    Struct(StructRef)
+);
@@ -0,0 +1,63 @@
+use crate::{garbage_collector::GcPtr, struct_ref::StructRef};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+/// A callback that is invoked when a field of a struct changes.
+pub(crate) type FieldChangedCallback = Rc<RefCell<dyn FnMut(&StructRef)>>;
+
+/// The callbacks that were registered with [`StructRef::on_field_changed`], per struct and field.
+///
+/// Fields are identified by name, so callbacks remain registered when a hot reload changes the
+/// layout of a struct's type.
+///
+/// [`StructRef::on_field_changed`]: struct.StructRef.html#method.on_field_changed
+#[derive(Default)]
+pub(crate) struct FieldObservers {
+    callbacks: HashMap<GcPtr, HashMap<String, Vec<FieldChangedCallback>>>,
+}
+
+impl FieldObservers {
+    /// Registers `callback` for the field `field_name` of the struct `obj`.
+    pub fn insert(&mut self, obj: GcPtr, field_name: &str, callback: FieldChangedCallback) {
+        self.callbacks
+            .entry(obj)
+            .or_insert_with(HashMap::new)
+            .entry(field_name.to_string())
+            .or_insert_with(Vec::new)
+            .push(callback);
+    }
+
+    /// Removes all callbacks of the field `field_name` of the struct `obj`. Returns whether the
+    /// struct is still observed.
+    pub fn remove(&mut self, obj: GcPtr, field_name: &str) -> bool {
+        if let Some(fields) = self.callbacks.get_mut(&obj) {
+            fields.remove(field_name);
+            if !fields.is_empty() {
+                return true;
+            }
+            self.callbacks.remove(&obj);
+        }
+        false
+    }
+
+    /// Removes all callbacks of the structs `objs`, which have been deallocated.
+    pub fn remove_deallocated(&mut self, objs: &[GcPtr]) {
+        for obj in objs {
+            self.callbacks.remove(obj);
+        }
+    }
+
+    /// Returns whether any field of the struct `obj` is observed.
+    pub fn is_observed(&self, obj: GcPtr) -> bool {
+        self.callbacks.contains_key(&obj)
+    }
+
+    /// Returns the callbacks of the field `field_name` of the struct `obj`, in the order in which
+    /// they were registered.
+    pub fn callbacks(&self, obj: GcPtr, field_name: &str) -> Vec<FieldChangedCallback> {
+        self.callbacks
+            .get(&obj)
+            .and_then(|fields| fields.get(field_name))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
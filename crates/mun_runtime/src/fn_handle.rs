@@ -0,0 +1,123 @@
+use crate::{reflection::ReturnTypeReflection, Runtime, SignatureError};
+use std::{cell::RefCell, ffi::c_void, marker::PhantomData, rc::Rc, slice};
+
+/// The argument types of a function that can be invoked through a [`FnHandle`].
+///
+/// This trait is implemented for tuples of up to 15 [`ArgumentReflection`] types.
+///
+/// [`ArgumentReflection`]: trait.ArgumentReflection.html
+pub trait FnHandleArgs: Sized {
+    /// The number of arguments
+    const NUM_ARGS: usize;
+
+    /// Invokes the function of `handle` once for every element of `args`. See
+    /// [`FnHandle::call_batch`].
+    ///
+    /// [`FnHandle::call_batch`]: struct.FnHandle.html#method.call_batch
+    #[doc(hidden)]
+    fn call_batch<Output: ReturnTypeReflection, I: IntoIterator<Item = Self>>(
+        handle: &mut FnHandle<Self, Output>,
+        runtime: &Rc<RefCell<Runtime>>,
+        args: I,
+    ) -> Result<Vec<Output>, SignatureError>;
+}
+
+/// A handle to a function with arguments `Args` and return type `Output`, as returned by
+/// [`Runtime::resolve`].
+///
+/// The handle caches the function pointer and signature, so invoking the function does not
+/// require a lookup by name. When the function is hot reloaded, the handle becomes stale and is
+/// automatically refreshed on its next invocation.
+///
+/// The number of arguments and the return type are validated when the handle is resolved. As the
+/// type of an argument can depend on its value, argument types are validated against the cached
+/// signature on every invocation.
+pub struct FnHandle<Args, Output> {
+    function_name: String,
+    fn_ptr: *const c_void,
+    arg_types: *const abi::TypeInfo,
+    num_args: usize,
+    generation: usize,
+    _phantom: PhantomData<fn(Args) -> Output>,
+}
+
+impl<Args: FnHandleArgs, Output: ReturnTypeReflection> FnHandle<Args, Output> {
+    /// Resolves the function `function_name` and validates its signature.
+    pub(crate) fn resolve(runtime: &Runtime, function_name: &str) -> Result<Self, SignatureError> {
+        let function_info = runtime.validate_signature_with(
+            function_name,
+            Args::NUM_ARGS,
+            |_| Ok(()),
+            |return_type| {
+                crate::reflection::equals_optional_return_type::<Output>(return_type)
+                    .map_err(|(expected, found)| (expected.to_string(), found.to_string()))
+            },
+        )?;
+
+        let arg_types = function_info.prototype.signature.arg_types();
+        Ok(FnHandle {
+            function_name: function_name.to_string(),
+            fn_ptr: function_info.fn_ptr,
+            arg_types: arg_types.as_ptr(),
+            num_args: arg_types.len(),
+            generation: runtime.generation(),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Resolves the function again if the handle is stale, i.e. if the `runtime` has loaded,
+    /// unloaded, or reloaded an assembly since the handle was last resolved.
+    pub fn refresh(&mut self, runtime: &Runtime) -> Result<(), SignatureError> {
+        if self.is_stale(runtime) {
+            *self = Self::resolve(runtime, &self.function_name)?;
+        }
+        Ok(())
+    }
+
+    /// Invokes the function once for every tuple of arguments in `args`, and returns the results
+    /// in the same order.
+    ///
+    /// The runtime is borrowed and the handle is refreshed only once for the whole batch, which
+    /// makes this cheaper than invoking the function in a loop. The argument types are still
+    /// validated for every invocation. If an argument has the wrong type or a returned value cannot
+    /// be marshalled, the batch stops and the error is returned; the invocations before it have
+    /// already run.
+    pub fn call_batch<I: IntoIterator<Item = Args>>(
+        &mut self,
+        runtime: &Rc<RefCell<Runtime>>,
+        args: I,
+    ) -> Result<Vec<Output>, SignatureError> {
+        Args::call_batch(self, runtime, args)
+    }
+}
+
+impl<Args, Output> FnHandle<Args, Output> {
+    /// Returns the name of the function.
+    pub fn function_name(&self) -> &str {
+        &self.function_name
+    }
+
+    /// Returns whether the `runtime` has loaded, unloaded, or reloaded an assembly since the handle
+    /// was last resolved.
+    pub fn is_stale(&self, runtime: &Runtime) -> bool {
+        self.generation != runtime.generation()
+    }
+
+    /// Returns the cached function pointer.
+    pub(crate) fn fn_ptr(&self) -> *const c_void {
+        self.fn_ptr
+    }
+
+    /// Returns the cached argument types of the function.
+    ///
+    /// # Safety
+    ///
+    /// The handle must not be stale, as the argument types are owned by the function's assembly.
+    pub(crate) unsafe fn arg_types(&self) -> &[abi::TypeInfo] {
+        if self.num_args == 0 {
+            &[]
+        } else {
+            slice::from_raw_parts(self.arg_types, self.num_args)
+        }
+    }
+}
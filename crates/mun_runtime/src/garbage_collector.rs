@@ -1,5 +1,14 @@
 use memory::gc::{self, HasIndirectionPtr};
-use std::{alloc::Layout, hash::Hash, ptr::NonNull};
+use parking_lot::Mutex;
+use std::{
+    alloc::Layout,
+    any::Any,
+    collections::{HashMap, HashSet, VecDeque},
+    hash::Hash,
+    mem,
+    ptr::NonNull,
+    sync::Arc,
+};
 
 /// `UnsafeTypeInfo` is a type that wraps a `NonNull<TypeInfo>` and indicates unsafe interior
 /// operations on the wrapped `TypeInfo`. The unsafety originates from uncertainty about the
@@ -78,6 +87,12 @@ impl memory::TypeFields<UnsafeTypeInfo> for UnsafeTypeInfo {
             &[]
         }
     }
+
+    fn field_default(&self, index: usize) -> Option<NonNull<u8>> {
+        unsafe { self.0.as_ref().as_struct() }
+            .and_then(|s| s.field_default(index))
+            .and_then(|default| NonNull::new(default as *mut u8))
+    }
 }
 
 unsafe impl Send for UnsafeTypeInfo {}
@@ -140,10 +155,132 @@ impl gc::TypeTrace for UnsafeTypeInfo {
             index: 0,
         }
     }
+
+    fn has_references(&self) -> bool {
+        !unsafe { self.0.as_ref() }.is_pod()
+    }
+}
+
+/// An `Observer` that owns the host resources attached to objects, dropping them when their
+/// owning object is deallocated - or queueing them to be dropped later, if drops are deferred.
+///
+/// It also records the deallocation of watched objects, so state that the runtime keeps per
+/// object can be discarded before the object's handle is reused.
+#[derive(Default)]
+pub struct ResourceObserver {
+    resources: Mutex<HashMap<GcPtr, Vec<Box<dyn Any + Send>>>>,
+    defer_drops: bool,
+    /// The resources of deallocated objects that have yet to be dropped
+    pending: Mutex<VecDeque<Box<dyn Any + Send>>>,
+    watched: Mutex<HashSet<GcPtr>>,
+    /// The watched objects that were deallocated since `take_deallocated` was last called
+    deallocated: Mutex<Vec<GcPtr>>,
+}
+
+impl ResourceObserver {
+    /// Constructs a `ResourceObserver` that, if `defer_drops` is `true`, queues the resources of
+    /// deallocated objects until they are dropped with `drop_pending`.
+    pub fn new(defer_drops: bool) -> Self {
+        Self {
+            defer_drops,
+            ..Default::default()
+        }
+    }
+
+    /// Drops at most `budget` queued resources, in the order in which their owners were
+    /// deallocated. Returns the number of resources that remain queued.
+    pub fn drop_pending(&self, budget: usize) -> usize {
+        for _ in 0..budget {
+            // The resource is dropped after the lock is released, so dropping it can attach or
+            // take other resources.
+            let resource = self.pending.lock().pop_front();
+            if resource.is_none() {
+                break;
+            }
+        }
+        self.pending.lock().len()
+    }
+
+    /// Watches `obj`, which records its deallocation until it is returned by `take_deallocated`.
+    pub fn watch(&self, obj: GcPtr) {
+        self.watched.lock().insert(obj);
+    }
+
+    /// Stops watching `obj`.
+    pub fn unwatch(&self, obj: GcPtr) {
+        self.watched.lock().remove(&obj);
+    }
+
+    /// Returns the watched objects that were deallocated since this was last called. They are no
+    /// longer watched.
+    pub fn take_deallocated(&self) -> Vec<GcPtr> {
+        mem::take(&mut *self.deallocated.lock())
+    }
+
+    /// Attaches `resource` to `owner`.
+    pub fn attach(&self, owner: GcPtr, resource: Box<dyn Any + Send>) {
+        self.resources
+            .lock()
+            .entry(owner)
+            .or_insert_with(Vec::new)
+            .push(resource);
+    }
+
+    /// Detaches and returns the first resource of type `T` that is attached to `owner`, if any.
+    pub fn take<T: Any + Send>(&self, owner: GcPtr) -> Option<T> {
+        self.take_where(owner, |_: &T| true)
+    }
+
+    /// Detaches and returns the first resource of type `T` that is attached to `owner` and
+    /// satisfies `predicate`, if any.
+    pub fn take_where<T: Any + Send>(
+        &self,
+        owner: GcPtr,
+        predicate: impl Fn(&T) -> bool,
+    ) -> Option<T> {
+        let mut resources = self.resources.lock();
+        let owned = resources.get_mut(&owner)?;
+        let index = owned.iter().position(|resource| {
+            resource
+                .downcast_ref::<T>()
+                .map_or(false, |resource| predicate(resource))
+        })?;
+        let resource = owned.remove(index);
+        if owned.is_empty() {
+            resources.remove(&owner);
+        }
+
+        // `resource` was found to be of type `T`
+        Some(*resource.downcast::<T>().unwrap())
+    }
+}
+
+impl gc::Observer for ResourceObserver {
+    type Event = gc::Event;
+
+    fn event(&self, event: gc::Event) {
+        if let gc::Event::Deallocation(obj) = event {
+            if self.watched.lock().remove(&obj) {
+                self.deallocated.lock().push(obj);
+            }
+
+            let resources = self.resources.lock().remove(&obj);
+            if self.defer_drops {
+                if let Some(resources) = resources {
+                    self.pending.lock().extend(resources);
+                }
+            } else {
+                // The resources are dropped after the lock is released, so dropping them can
+                // attach or take other resources. The collector has released its locks too, so
+                // they can also root and unroot objects.
+                mem::drop(resources);
+            }
+        }
+    }
 }
 
 /// Defines the garbage collector used by the `Runtime`.
-pub type GarbageCollector = gc::MarkSweep<UnsafeTypeInfo, gc::NoopObserver<gc::Event>>;
+pub type GarbageCollector = gc::MarkSweep<UnsafeTypeInfo, ResourceObserver, Arc<dyn gc::Allocator>>;
 
 pub use gc::GcPtr;
 pub type GcRootPtr = gc::GcRootPtr<UnsafeTypeInfo, GarbageCollector>;
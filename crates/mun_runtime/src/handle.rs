@@ -0,0 +1,53 @@
+use std::{ffi::c_void, mem, ptr, sync::Arc};
+
+/// An opaque handle to a host-owned resource that was created by a registered
+/// [`abi::HandleType`]. The handle is released with the type's `drop` callback when it is dropped,
+/// unless ownership is relinquished with `into_raw`.
+pub(crate) struct OwnedHandle {
+    handle: *mut c_void,
+    handle_type: Arc<abi::HandleType>,
+}
+
+// Safety: `abi::HandleType` requires handles to be droppable on any thread.
+unsafe impl Send for OwnedHandle {}
+
+impl OwnedHandle {
+    /// Creates a handle of type `handle_type` from `arg`. Returns `None` if the type's `create`
+    /// callback failed.
+    ///
+    /// # Safety
+    ///
+    /// `arg` must be valid for the `create` callback of `handle_type`.
+    pub unsafe fn create(handle_type: Arc<abi::HandleType>, arg: *mut c_void) -> Option<Self> {
+        let handle = handle_type.create_handle(arg)?;
+        Some(Self {
+            handle,
+            handle_type,
+        })
+    }
+
+    /// Returns the name of the handle's type.
+    pub fn type_name(&self) -> &str {
+        self.handle_type.name()
+    }
+
+    /// Returns the raw handle.
+    pub fn as_raw(&self) -> *mut c_void {
+        self.handle
+    }
+
+    /// Returns the raw handle, relinquishing ownership. The handle is no longer released when
+    /// `self` is dropped.
+    pub fn into_raw(mut self) -> *mut c_void {
+        mem::replace(&mut self.handle, ptr::null_mut())
+    }
+}
+
+impl Drop for OwnedHandle {
+    fn drop(&mut self) {
+        // The handle is null if ownership was relinquished
+        if !self.handle.is_null() {
+            unsafe { self.handle_type.drop_handle(self.handle) }
+        }
+    }
+}
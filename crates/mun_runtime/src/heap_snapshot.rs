@@ -0,0 +1,350 @@
+//! Serialization of all live structs of the garbage collected heap.
+//!
+//! A heap snapshot is laid out as follows, where integers are stored in little-endian byte order:
+//!
+//! - the magic bytes `MUNHEAP\0`, followed by the `u32` version of the format;
+//! - the pointer width, in bytes, and whether the target is little-endian, as one byte each;
+//! - the `u32` number of struct types, each of which consists of its 16-byte `Guid`, and its
+//!   name as a `u32` length followed by UTF-8 bytes;
+//! - the `u32` number of rooted structs, followed by the `u32` number of structs. The rooted
+//!   structs come first;
+//! - for each struct, the `u32` index of its type, followed by the `u32` size of its memory and
+//!   the memory itself. Fields of garbage collected struct types hold the index of the referenced
+//!   struct, or `usize::MAX` if the field is null.
+//!
+//! As the memory of structs is copied verbatim, snapshots can only be restored on targets with
+//! the same pointer width and endianness.
+
+use crate::{
+    garbage_collector::{GarbageCollector, GcPtr, UnsafeTypeInfo},
+    struct_ref::RawStruct,
+    Runtime,
+};
+use memory::gc::{GcRuntime, HasIndirectionPtr, RawGcPtr};
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::{TryFrom, TryInto},
+    fmt, mem,
+    ptr::{self, NonNull},
+    slice,
+};
+
+/// The magic bytes with which every heap snapshot starts
+const MAGIC: &[u8; 8] = b"MUNHEAP\0";
+
+/// The version of the heap snapshot format, which is incremented whenever the format changes. Only
+/// snapshots of the current version can be restored.
+const VERSION: u32 = 1;
+
+/// The value of a null garbage collected struct field
+const NULL_INDEX: usize = usize::MAX;
+
+/// An error that occurs when restoring a heap snapshot with [`Runtime::restore`].
+///
+/// [`Runtime::restore`]: struct.Runtime.html#method.restore
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HeapSnapshotError {
+    /// The data is not a heap snapshot, or it is truncated or corrupt.
+    InvalidData,
+    /// The snapshot was created with a different version of the snapshot format.
+    UnsupportedVersion {
+        /// The version of the snapshot's format
+        version: u32,
+    },
+    /// The snapshot was created on a target with a different pointer width or endianness.
+    IncompatibleTarget,
+    /// The snapshot contains a struct of a type that none of the loaded assemblies define.
+    UnknownType {
+        /// The name of the struct type
+        type_name: String,
+    },
+    /// The layout of a struct type in the snapshot differs from that of the loaded assemblies.
+    IncompatibleType {
+        /// The name of the struct type
+        type_name: String,
+    },
+}
+
+impl fmt::Display for HeapSnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeapSnapshotError::InvalidData => {
+                write!(f, "The data is not a valid heap snapshot.")
+            }
+            HeapSnapshotError::UnsupportedVersion { version } => write!(
+                f,
+                "Unsupported heap snapshot version. Expected: {}. Found: {}.",
+                VERSION, version
+            ),
+            HeapSnapshotError::IncompatibleTarget => write!(
+                f,
+                "The heap snapshot was created on a target with a different pointer width or endianness."
+            ),
+            HeapSnapshotError::UnknownType { type_name } => write!(
+                f,
+                "Struct type `{}` of the heap snapshot does not exist.",
+                type_name
+            ),
+            HeapSnapshotError::IncompatibleType { type_name } => write!(
+                f,
+                "The layout of struct type `{}` changed since the heap snapshot was created.",
+                type_name
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HeapSnapshotError {}
+
+/// Serializes all rooted structs of `gc` and the structs they transitively reference.
+pub(crate) fn write(gc: &GarbageCollector) -> Vec<u8> {
+    let roots = gc.roots();
+    let num_roots = roots.len();
+
+    // Number the structs in the order in which they are found. As the queue starts with all roots,
+    // the roots are numbered first.
+    let mut indices: HashMap<GcPtr, usize> = HashMap::new();
+    let mut handles = Vec::new();
+    let mut queue: VecDeque<GcPtr> = roots.into_iter().map(|(handle, _)| handle).collect();
+    while let Some(handle) = queue.pop_front() {
+        if indices.contains_key(&handle) {
+            continue;
+        }
+        indices.insert(handle, handles.len());
+        handles.push(handle);
+
+        let type_info = unsafe { gc.ptr_type(handle).into_inner().as_ref() };
+        let ptr = unsafe { handle.deref::<u8>() };
+        for_each_gc_field(type_info, 0, &mut |offset, _| {
+            // Safety: The offset of every garbage collected field lies within the struct's memory.
+            let reference = unsafe { ptr.add(offset).cast::<GcPtr>().read_unaligned() };
+            if !is_null(reference) {
+                queue.push_back(reference);
+            }
+        });
+    }
+
+    let mut types: Vec<&abi::TypeInfo> = Vec::new();
+    let mut type_indices: HashMap<abi::Guid, u32> = HashMap::new();
+    let mut objects = Vec::new();
+    for handle in handles.iter() {
+        let type_info = unsafe { gc.ptr_type(*handle).into_inner().as_ref() };
+        let type_index = *type_indices.entry(type_info.guid).or_insert_with(|| {
+            types.push(type_info);
+            (types.len() - 1) as u32
+        });
+
+        let mut data =
+            unsafe { slice::from_raw_parts(handle.deref::<u8>(), type_info.size_in_bytes()) }
+                .to_vec();
+        for_each_gc_field(type_info, 0, &mut |offset, _| {
+            let field = &mut data[offset..offset + mem::size_of::<usize>()];
+            let reference = unsafe { ptr::read_unaligned(field.as_ptr().cast::<GcPtr>()) };
+            let index = indices.get(&reference).copied().unwrap_or(NULL_INDEX);
+            field.copy_from_slice(&index.to_ne_bytes());
+        });
+        objects.push((type_index, data));
+    }
+
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    bytes.push(mem::size_of::<usize>() as u8);
+    bytes.push(cfg!(target_endian = "little") as u8);
+
+    write_u32(&mut bytes, types.len());
+    for type_info in types {
+        bytes.extend_from_slice(&type_info.guid.b);
+        write_u32(&mut bytes, type_info.name().len());
+        bytes.extend_from_slice(type_info.name().as_bytes());
+    }
+
+    write_u32(&mut bytes, num_roots);
+    write_u32(&mut bytes, objects.len());
+    for (type_index, data) in objects {
+        bytes.extend_from_slice(&type_index.to_le_bytes());
+        write_u32(&mut bytes, data.len());
+        bytes.extend_from_slice(&data);
+    }
+    bytes
+}
+
+/// Deserializes the structs of the heap snapshot `bytes` into the heap of `runtime`, returning
+/// the structs that were rooted when the snapshot was created.
+///
+/// The returned structs are not rooted, so they must be rooted before the next collection.
+pub(crate) fn read(runtime: &Runtime, bytes: &[u8]) -> Result<Vec<RawStruct>, HeapSnapshotError> {
+    let mut reader = Reader { bytes };
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(HeapSnapshotError::InvalidData);
+    }
+    let version = reader.read_u32()?;
+    if version != VERSION {
+        return Err(HeapSnapshotError::UnsupportedVersion { version });
+    }
+    let target = reader.take(2)?;
+    if usize::from(target[0]) != mem::size_of::<usize>()
+        || (target[1] != 0) != cfg!(target_endian = "little")
+    {
+        return Err(HeapSnapshotError::IncompatibleTarget);
+    }
+
+    // Struct types are matched by name, and are only compatible if their `Guid` - and thus their
+    // layout - is unchanged.
+    let num_types = reader.read_u32()?;
+    let mut types = Vec::new();
+    for _ in 0..num_types {
+        let guid = abi::Guid {
+            b: reader.take(16)?.try_into().unwrap(),
+        };
+        let name_len = reader.read_u32()? as usize;
+        let type_name = std::str::from_utf8(reader.take(name_len)?)
+            .map_err(|_| HeapSnapshotError::InvalidData)?;
+        let type_info =
+            runtime
+                .find_struct_type(type_name)
+                .ok_or_else(|| HeapSnapshotError::UnknownType {
+                    type_name: type_name.to_string(),
+                })?;
+        if type_info.guid != guid {
+            return Err(HeapSnapshotError::IncompatibleType {
+                type_name: type_name.to_string(),
+            });
+        }
+        types.push(type_info);
+    }
+
+    let num_roots = reader.read_u32()? as usize;
+    let num_objects = reader.read_u32()? as usize;
+    if num_roots > num_objects {
+        return Err(HeapSnapshotError::InvalidData);
+    }
+    let mut objects = Vec::new();
+    for _ in 0..num_objects {
+        let type_info = *types
+            .get(reader.read_u32()? as usize)
+            .ok_or(HeapSnapshotError::InvalidData)?;
+        let size = reader.read_u32()? as usize;
+        if size != type_info.size_in_bytes() {
+            return Err(HeapSnapshotError::InvalidData);
+        }
+        objects.push((type_info, reader.take(size)?));
+    }
+    if !reader.bytes.is_empty() {
+        return Err(HeapSnapshotError::InvalidData);
+    }
+
+    // Validate all references before allocating anything, so a corrupt snapshot leaves the heap
+    // untouched.
+    let mut valid = true;
+    for (type_info, data) in objects.iter() {
+        for_each_gc_field(type_info, 0, &mut |offset, field_type| {
+            let index = read_index(data, offset);
+            valid &= index == NULL_INDEX
+                || objects
+                    .get(index)
+                    .map_or(false, |(ty, _)| ty.guid == field_type.guid);
+        });
+    }
+    if !valid {
+        return Err(HeapSnapshotError::InvalidData);
+    }
+
+    let _guard = runtime.sync_state.write();
+    let handles: Vec<GcPtr> = objects
+        .iter()
+        .map(|(type_info, _)| {
+            runtime
+                .gc()
+                .alloc(UnsafeTypeInfo::new(NonNull::from(*type_info)))
+        })
+        .collect();
+    for (mut handle, (type_info, data)) in handles.iter().copied().zip(objects.iter()) {
+        let ptr = unsafe { handle.deref_mut::<u8>() };
+        // Safety: The size of the data was validated to match the size of the struct's type.
+        unsafe { ptr::copy_nonoverlapping(data.as_ptr(), ptr, data.len()) };
+        for_each_gc_field(type_info, 0, &mut |offset, _| {
+            let reference: GcPtr = match read_index(data, offset) {
+                NULL_INDEX => (ptr::null() as RawGcPtr).into(),
+                index => handles[index],
+            };
+            // Safety: The offset of every garbage collected field lies within the struct's memory.
+            unsafe { ptr.add(offset).cast::<GcPtr>().write_unaligned(reference) };
+        });
+    }
+
+    Ok(handles[..num_roots]
+        .iter()
+        .copied()
+        .map(RawStruct)
+        .collect())
+}
+
+/// Invokes `f` with the offset and type of every field of a garbage collected struct type in a
+/// struct of type `type_info` at `offset`, including those of nested value structs.
+fn for_each_gc_field(
+    type_info: &abi::TypeInfo,
+    offset: usize,
+    f: &mut impl FnMut(usize, &abi::TypeInfo),
+) {
+    let struct_info = match type_info.as_struct() {
+        Some(struct_info) => struct_info,
+        None => return,
+    };
+    for (field_type, field_offset) in struct_info
+        .field_types()
+        .iter()
+        .zip(struct_info.field_offsets().iter())
+    {
+        let field_offset = offset + usize::from(*field_offset);
+        match field_type.as_struct() {
+            Some(s) if s.memory_kind == abi::StructMemoryKind::GC => f(field_offset, field_type),
+            Some(_) => for_each_gc_field(field_type, field_offset, f),
+            None => (),
+        }
+    }
+}
+
+/// Returns whether `handle` is null.
+fn is_null(handle: GcPtr) -> bool {
+    let raw: RawGcPtr = handle.into();
+    raw.is_null()
+}
+
+/// Reads the struct index that is stored in a garbage collected struct field at `offset`.
+fn read_index(data: &[u8], offset: usize) -> usize {
+    usize::from_ne_bytes(
+        data[offset..offset + mem::size_of::<usize>()]
+            .try_into()
+            .unwrap(),
+    )
+}
+
+/// Appends the length `value` to `bytes` as a little-endian `u32`.
+fn write_u32(bytes: &mut Vec<u8>, value: usize) {
+    let value = u32::try_from(value).expect("heap snapshot is too large");
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Reads the sections of a heap snapshot.
+struct Reader<'b> {
+    bytes: &'b [u8],
+}
+
+impl<'b> Reader<'b> {
+    /// Reads the next `len` bytes.
+    fn take(&mut self, len: usize) -> Result<&'b [u8], HeapSnapshotError> {
+        if len > self.bytes.len() {
+            return Err(HeapSnapshotError::InvalidData);
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    /// Reads a little-endian `u32`.
+    fn read_u32(&mut self) -> Result<u32, HeapSnapshotError> {
+        let bytes = self.take(4)?.try_into().unwrap();
+        Ok(u32::from_le_bytes(bytes))
+    }
+}
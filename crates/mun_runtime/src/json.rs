@@ -0,0 +1,154 @@
+use crate::struct_visitor::{StructVisitor, Visit};
+use abi::HasStaticTypeInfo;
+use std::{convert::TryInto, io};
+
+/// How non-finite floating-point numbers, i.e. NaN and infinities, are serialized. JSON cannot
+/// represent them, so they have to be rejected or substituted to produce a valid document.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NonFinitePolicy {
+    /// Fail with an error of kind `InvalidData`
+    Error,
+    /// Substitute `null`
+    Null,
+    /// Write them as `NaN`, `Infinity`, and `-Infinity`, like JavaScript does. The result is not
+    /// valid JSON, but is accepted by many lenient parsers.
+    PassThrough,
+}
+
+impl Default for NonFinitePolicy {
+    fn default() -> Self {
+        NonFinitePolicy::Error
+    }
+}
+
+/// A [`StructVisitor`] that streams the graph of a struct to a writer as JSON.
+///
+/// Structs are written as objects, with their fields in declaration order. Cyclic references and
+/// values of unsupported types are written as `null`, while non-finite floating-point numbers are
+/// written according to a `NonFinitePolicy`.
+pub(crate) struct JsonWriter<'w, W: io::Write> {
+    writer: &'w mut W,
+    non_finite: NonFinitePolicy,
+    /// For every struct that is being written, whether any of its fields have been written
+    has_fields: Vec<bool>,
+    error: Option<io::Error>,
+}
+
+impl<'w, W: io::Write> JsonWriter<'w, W> {
+    /// Constructs a `JsonWriter` that writes to `writer`, handling non-finite floating-point
+    /// numbers according to `non_finite`.
+    pub fn new(writer: &'w mut W, non_finite: NonFinitePolicy) -> Self {
+        Self {
+            writer,
+            non_finite,
+            has_fields: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Returns the first error that occurred while writing, if any.
+    pub fn finish(self) -> io::Result<()> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes to the writer using `f`, and stops the traversal if writing fails.
+    fn write(&mut self, f: impl FnOnce(&mut W) -> io::Result<()>) -> Visit {
+        match f(&mut *self.writer) {
+            Ok(()) => Visit::Continue,
+            Err(error) => {
+                self.error = Some(error);
+                Visit::Break
+            }
+        }
+    }
+}
+
+impl<W: io::Write> StructVisitor for JsonWriter<'_, W> {
+    fn enter_struct(&mut self, _type_info: &abi::TypeInfo) -> Visit {
+        self.has_fields.push(false);
+        self.write(|w| w.write_all(b"{"))
+    }
+
+    fn enter_field(&mut self, name: &str, _type_info: &abi::TypeInfo) -> Visit {
+        let has_fields = self
+            .has_fields
+            .last_mut()
+            .expect("fields are only entered inside a struct");
+        let separator = if *has_fields { "," } else { "" };
+        *has_fields = true;
+
+        self.write(|w| write!(w, "{}\"{}\":", separator, name))
+    }
+
+    fn visit_fundamental(&mut self, name: &str, type_info: &abi::TypeInfo, bytes: &[u8]) -> Visit {
+        let non_finite = self.non_finite;
+        self.write(|w| write_fundamental(w, name, type_info, bytes, non_finite))
+    }
+
+    fn visit_cycle(&mut self, _type_info: &abi::TypeInfo, _depth: usize) -> Visit {
+        self.write(|w| w.write_all(b"null"))
+    }
+
+    fn leave_struct(&mut self, _type_info: &abi::TypeInfo) -> Visit {
+        self.has_fields.pop();
+        self.write(|w| w.write_all(b"}"))
+    }
+}
+
+/// Writes the value of field `name`, which is of fundamental type `type_info` and whose memory is
+/// `bytes`, as JSON.
+fn write_fundamental<W: io::Write>(
+    w: &mut W,
+    name: &str,
+    type_info: &abi::TypeInfo,
+    bytes: &[u8],
+    non_finite: NonFinitePolicy,
+) -> io::Result<()> {
+    macro_rules! write_integers {
+        ($($ty:ty),+) => {
+            $(
+                if type_info.guid == <$ty>::type_info().guid {
+                    let value = <$ty>::from_ne_bytes(bytes.try_into().unwrap());
+                    return write!(w, "{}", value);
+                }
+            )+
+        };
+    }
+
+    macro_rules! write_floats {
+        ($($ty:ty),+) => {
+            $(
+                if type_info.guid == <$ty>::type_info().guid {
+                    let value = <$ty>::from_ne_bytes(bytes.try_into().unwrap());
+                    if value.is_finite() {
+                        return write!(w, "{}", value);
+                    }
+                    return match non_finite {
+                        NonFinitePolicy::Error => Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!(
+                                "Field `{}` holds `{}`, which cannot be represented in JSON.",
+                                name, value
+                            ),
+                        )),
+                        NonFinitePolicy::Null => w.write_all(b"null"),
+                        NonFinitePolicy::PassThrough if value.is_nan() => w.write_all(b"NaN"),
+                        NonFinitePolicy::PassThrough if value > 0.0 => w.write_all(b"Infinity"),
+                        NonFinitePolicy::PassThrough => w.write_all(b"-Infinity"),
+                    };
+                }
+            )+
+        };
+    }
+
+    if type_info.guid == bool::type_info().guid {
+        return w.write_all(if bytes[0] != 0 { b"true" } else { b"false" });
+    }
+    write_integers!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+    write_floats!(f32, f64);
+
+    w.write_all(b"null")
+}
@@ -2,16 +2,37 @@
 //!
 //! The Mun Runtime provides functionality for automatically hot reloading Mun C ABI
 //! compliant shared libraries.
+//!
+//! With the `tracing` feature enabled, every invocation of a Mun function is wrapped in a `TRACE`
+//! level [`tracing`] span named `mun_invoke`, whose `function` field holds the function's name.
+//!
+//! [`tracing`]: https://docs.rs/tracing
 #![warn(missing_docs)]
 
 mod assembly;
+mod bit_flags;
+mod dyn_value;
+mod field_observer;
+mod fn_handle;
 #[macro_use]
 mod macros;
 #[macro_use]
 mod garbage_collector;
+mod handle;
+mod heap_snapshot;
+mod json;
 mod marshal;
+#[cfg(feature = "mutation-log")]
+mod mutation_log;
 mod reflection;
+mod result;
+mod snapshot;
+mod struct_builder;
 mod struct_ref;
+mod struct_visitor;
+mod sync_struct_ref;
+mod tuple;
+mod verify;
 
 use failure::Error;
 use garbage_collector::GarbageCollector;
@@ -20,8 +41,8 @@ use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
 use rustc_hash::FxHashMap;
 use std::{
     cell::RefCell,
-    collections::HashMap,
-    ffi, io, mem,
+    collections::{HashMap, HashSet},
+    ffi, fmt, io, mem,
     path::{Path, PathBuf},
     ptr::NonNull,
     rc::Rc,
@@ -30,17 +51,38 @@ use std::{
         mpsc::{channel, Receiver},
         Arc,
     },
+    thread,
     time::Duration,
 };
+use sync_struct_ref::SyncState;
 
+#[cfg(feature = "mutation-log")]
+pub use crate::mutation_log::Mutation;
 pub use crate::{
     assembly::Assembly,
+    bit_flags::{BitFlags, Flag},
+    dyn_value::DynValue,
+    fn_handle::{FnHandle, FnHandleArgs},
     garbage_collector::UnsafeTypeInfo,
-    marshal::Marshal,
-    reflection::{ArgumentReflection, ReturnTypeReflection},
-    struct_ref::StructRef,
+    heap_snapshot::HeapSnapshotError,
+    json::NonFinitePolicy,
+    marshal::{Marshal, MarshalArgument, MarshalError},
+    reflection::{ArgumentReflection, DynArgument, DynReturnType, OpaquePtr, ReturnTypeReflection},
+    result::{MunResult, MUN_RESULT_ERR, MUN_RESULT_OK},
+    snapshot::RuntimeSnapshot,
+    struct_builder::StructBuilder,
+    struct_ref::{FieldError, PinnedStruct, RawStruct, StructRef, TypeMismatch},
+    struct_visitor::{StructVisitor, Visit},
+    sync_struct_ref::{SyncRuntimeHandle, SyncStructRef},
+    verify::ModuleError,
 };
 pub use abi::IntoFunctionDefinition;
+// Used by `impl_enum_reflection!`
+#[doc(hidden)]
+pub use abi;
+#[cfg(any(debug_assertions, feature = "heap-validation"))]
+pub use memory::gc::ValidationError;
+pub use memory::gc::{Allocator, SystemAllocator, TypeUsage};
 
 /// Options for the construction of a [`Runtime`].
 pub struct RuntimeOptions {
@@ -50,6 +92,20 @@ pub struct RuntimeOptions {
     pub delay: Duration,
     /// Custom user injected functions
     pub user_functions: Vec<(abi::FunctionDefinition, abi::FunctionDefinitionStorage)>,
+    /// Whether identical value structs marshalled to the host share memory until mutated
+    pub intern_value_structs: bool,
+    /// Whether the garbage collector tracks the number of collection cycles objects survive
+    pub track_gc_age: bool,
+    /// The allocator used by the garbage collector for objects and their metadata
+    pub allocator: Arc<dyn Allocator>,
+    /// Whether the resources attached to collected structs are queued until they are dropped by
+    /// [`Runtime::run_pending_finalizers`], rather than dropped during collection
+    pub defer_resource_drops: bool,
+    /// The types of opaque handles to host-owned resources that can be attached to structs
+    pub handle_types: Vec<abi::HandleType>,
+    /// The maximum number of struct mutations that are kept in the [`Runtime::mutation_log`]
+    #[cfg(feature = "mutation-log")]
+    pub mutation_log_capacity: usize,
 }
 
 /// A builder for the [`Runtime`].
@@ -65,6 +121,13 @@ impl RuntimeBuilder {
                 library_path: library_path.into(),
                 delay: Duration::from_millis(10),
                 user_functions: Default::default(),
+                intern_value_structs: false,
+                track_gc_age: false,
+                allocator: Arc::new(SystemAllocator),
+                defer_resource_drops: false,
+                handle_types: Vec::new(),
+                #[cfg(feature = "mutation-log")]
+                mutation_log_capacity: 1024,
             },
         }
     }
@@ -75,6 +138,52 @@ impl RuntimeBuilder {
         self
     }
 
+    /// Sets whether identical value structs share memory until they are mutated.
+    pub fn set_value_interning(mut self, enabled: bool) -> Self {
+        self.options.intern_value_structs = enabled;
+        self
+    }
+
+    /// Sets whether the garbage collector tracks the number of collection cycles that objects
+    /// survive.
+    pub fn set_gc_age_tracking(mut self, enabled: bool) -> Self {
+        self.options.track_gc_age = enabled;
+        self
+    }
+
+    /// Sets the allocator that the garbage collector uses for objects and their metadata.
+    pub fn set_allocator(mut self, allocator: Arc<dyn Allocator>) -> Self {
+        self.options.allocator = allocator;
+        self
+    }
+
+    /// Sets whether the resources attached to collected structs are queued until they are
+    /// dropped by [`Runtime::run_pending_finalizers`], rather than dropped during collection.
+    pub fn set_deferred_resource_drops(mut self, enabled: bool) -> Self {
+        self.options.defer_resource_drops = enabled;
+        self
+    }
+
+    /// Registers a type of opaque handles to host-owned resources. Handles of the type can then be
+    /// created and attached to structs with [`StructRef::create_handle`]. A handle type that has
+    /// the same name as a previously registered type replaces it.
+    ///
+    /// The name of `handle_type` must outlive the runtime.
+    ///
+    /// [`StructRef::create_handle`]: struct.StructRef.html#method.create_handle
+    pub fn register_handle_type(mut self, handle_type: abi::HandleType) -> Self {
+        self.options.handle_types.push(handle_type);
+        self
+    }
+
+    /// Sets the maximum number of struct mutations that are kept in the
+    /// [`Runtime::mutation_log`]. A capacity of zero disables the log.
+    #[cfg(feature = "mutation-log")]
+    pub fn set_mutation_log_capacity(mut self, capacity: usize) -> Self {
+        self.options.mutation_log_capacity = capacity;
+        self
+    }
+
     /// Adds a custom user function to the dispatch table.
     pub fn insert_fn<S: AsRef<str>, F: abi::IntoFunctionDefinition>(
         mut self,
@@ -160,13 +269,205 @@ impl DispatchTable {
     }
 }
 
+/// An identifier of a library loaded by the [`Runtime`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LibraryId(usize);
+
+/// The memory layout of a single field of a struct, as returned by [`Runtime::struct_layout`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldLayout<'r> {
+    /// The name of the field
+    pub name: &'r str,
+    /// The offset of the field in bytes, relative to the start of the struct
+    pub offset: usize,
+    /// The number of bytes the field occupies in the struct
+    pub size: usize,
+    /// The alignment of the field in bytes
+    pub alignment: usize,
+    /// The name of the field's type
+    pub type_name: &'r str,
+    /// The documentation of the field, if it is documented
+    pub doc: Option<&'r str>,
+}
+
+/// An error that occurs when the signature of a function does not match the expected signature,
+/// as returned by [`Runtime::validate_signature`].
+///
+/// When invoking a function through a [`FnHandle`], this is also returned if the function returns a
+/// value that cannot be represented by the expected return type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SignatureError {
+    /// The function does not exist
+    FunctionNotFound {
+        /// The name of the function
+        function_name: String,
+    },
+    /// The number of arguments does not match
+    ArgumentCount {
+        /// The number of arguments of the function
+        expected: usize,
+        /// The number of provided arguments
+        found: usize,
+    },
+    /// The type of an argument does not match
+    ArgumentType {
+        /// The index of the mismatching argument
+        index: usize,
+        /// The name of the argument's type
+        expected: String,
+        /// The name of the provided argument's type
+        found: String,
+    },
+    /// The return type does not match
+    ReturnType {
+        /// The name of the function's return type
+        expected: String,
+        /// The name of the provided return type
+        found: String,
+    },
+    /// The returned value cannot be represented by the return type
+    InvalidReturnValue(MarshalError),
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureError::FunctionNotFound { function_name } => {
+                write!(f, "Failed to obtain function '{}'", function_name)
+            }
+            SignatureError::ArgumentCount { expected, found } => write!(
+                f,
+                "Invalid number of arguments. Expected: {}. Found: {}.",
+                expected, found
+            ),
+            SignatureError::ArgumentType {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Invalid argument type at index {}. Expected: {}. Found: {}.",
+                index, expected, found
+            ),
+            SignatureError::ReturnType { expected, found } => write!(
+                f,
+                "Invalid return type. Expected: {}. Found: {}",
+                expected, found
+            ),
+            SignatureError::InvalidReturnValue(e) => write!(f, "Invalid return value. {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SignatureError {}
+
+/// An error that occurs when the memory layout of a host type does not match the layout that a
+/// function expects, as returned by [`Runtime::check_marshal_compat`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LayoutMismatch {
+    /// The function does not exist
+    FunctionNotFound {
+        /// The name of the function
+        function_name: String,
+    },
+    /// The function does not have an argument at the index
+    ArgumentNotFound {
+        /// The index of the argument
+        index: usize,
+        /// The number of arguments of the function
+        num_args: usize,
+    },
+    /// The size or alignment of the marshalled host type differs from the argument's
+    Layout {
+        /// The index of the argument
+        index: usize,
+        /// The name of the argument's type
+        type_name: String,
+        /// The size of the argument's type, in bytes
+        expected_size: usize,
+        /// The size of the marshalled host type, in bytes
+        found_size: usize,
+        /// The alignment of the argument's type, in bytes
+        expected_alignment: usize,
+        /// The alignment of the marshalled host type, in bytes
+        found_alignment: usize,
+    },
+}
+
+impl fmt::Display for LayoutMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutMismatch::FunctionNotFound { function_name } => {
+                write!(f, "Failed to obtain function '{}'", function_name)
+            }
+            LayoutMismatch::ArgumentNotFound { index, num_args } => write!(
+                f,
+                "Invalid argument index {}. The function has {} arguments.",
+                index, num_args
+            ),
+            LayoutMismatch::Layout {
+                index,
+                type_name,
+                expected_size,
+                found_size,
+                expected_alignment,
+                found_alignment,
+            } => write!(
+                f,
+                "Invalid layout for argument {} of type {}. Expected: size {} and alignment {}. Found: size {} and alignment {}.",
+                index, type_name, expected_size, expected_alignment, found_size, found_alignment
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LayoutMismatch {}
+
+/// The phase of a hot reload, as passed to the callbacks registered with [`Runtime::on_reload`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReloadPhase {
+    /// The assembly is about to be reloaded
+    Before,
+    /// The assembly has been reloaded, or failed to reload with the contained error message
+    After(Result<(), String>),
+}
+
+/// How the value of a struct field was initialized when the layout of its struct type changed
+/// during a hot reload, as reported by [`Runtime::last_reload_remapping`].
+///
+/// [`Runtime::last_reload_remapping`]: struct.Runtime.html#method.last_reload_remapping
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FieldRemap {
+    /// The value was copied from the old field of the same type
+    Copied,
+    /// The value was converted from the old field of type `old_type`
+    Converted { old_type: String },
+    /// The field was added and initialized with its default value
+    Defaulted,
+    /// The field was added and zero-initialized, as it does not have a default value
+    ZeroInitialized,
+}
+
 /// A runtime for the Mun language.
 pub struct Runtime {
     assemblies: HashMap<PathBuf, Assembly>,
+    libraries: FxHashMap<LibraryId, PathBuf>,
+    /// The dependencies that were loaded along with each library
+    library_dependencies: FxHashMap<LibraryId, Vec<PathBuf>>,
+    next_library_id: usize,
     dispatch_table: DispatchTable,
     watcher: RecommendedWatcher,
     watcher_rx: Receiver<DebouncedEvent>,
     gc: Arc<GarbageCollector>,
+    sync_state: Arc<SyncState>,
+    reload_callbacks: Vec<Box<dyn FnMut(ReloadPhase, &Path)>>,
+    last_reload_remapping: HashMap<String, Vec<(String, FieldRemap)>>,
+    field_observers: RefCell<field_observer::FieldObservers>,
+    handle_types: HashMap<String, Arc<abi::HandleType>>,
+    /// The thread that spawned the runtime
+    thread: thread::ThreadId,
+    #[cfg(feature = "mutation-log")]
+    mutation_log: parking_lot::Mutex<mutation_log::MutationLog>,
     _user_functions: Vec<abi::FunctionDefinitionStorage>,
 }
 
@@ -220,21 +521,221 @@ impl Runtime {
         }
 
         let watcher: RecommendedWatcher = Watcher::new(tx, options.delay)?;
+        let gc = Arc::new(
+            self::garbage_collector::GarbageCollector::with_observer_and_allocator(
+                self::garbage_collector::ResourceObserver::new(options.defer_resource_drops),
+                options.allocator,
+            )
+            .with_value_interning(options.intern_value_structs)
+            .with_age_tracking(options.track_gc_age),
+        );
         let mut runtime = Runtime {
             assemblies: HashMap::new(),
+            libraries: FxHashMap::default(),
+            library_dependencies: FxHashMap::default(),
+            next_library_id: 0,
             dispatch_table,
             watcher,
             watcher_rx: rx,
-            gc: Arc::new(self::garbage_collector::GarbageCollector::default()),
+            sync_state: Arc::new(SyncState::new(gc.clone())),
+            gc,
+            reload_callbacks: Vec::new(),
+            last_reload_remapping: HashMap::new(),
+            field_observers: RefCell::default(),
+            handle_types: options
+                .handle_types
+                .into_iter()
+                .map(|handle_type| (handle_type.name().to_string(), Arc::new(handle_type)))
+                .collect(),
+            thread: thread::current().id(),
+            #[cfg(feature = "mutation-log")]
+            mutation_log: parking_lot::Mutex::new(mutation_log::MutationLog::new(
+                options.mutation_log_capacity,
+            )),
             _user_functions: storages,
         };
 
-        runtime.add_assembly(&options.library_path)?;
+        runtime.load_library(&options.library_path)?;
         Ok(runtime)
     }
 
-    /// Adds an assembly corresponding to the library at `library_path`.
-    fn add_assembly(&mut self, library_path: &Path) -> Result<(), Error> {
+    /// Loads the library at `library_path` and its dependencies, returning an identifier that
+    /// can be used to unload the library.
+    ///
+    /// Fails if the library defines a function that is already defined by another library, or
+    /// a struct type with the same name as, but a different definition than, a struct type of
+    /// another library.
+    pub fn load_library<P: AsRef<Path>>(&mut self, library_path: P) -> Result<LibraryId, Error> {
+        let library_path = library_path.as_ref().canonicalize()?;
+        let sync_state = self.sync_state.clone();
+        let _guard = sync_state.write();
+        let mut added = self.add_assembly(&library_path)?;
+        // The library itself is linked last
+        added.pop();
+
+        let id = LibraryId(self.next_library_id);
+        self.next_library_id += 1;
+        self.libraries.insert(id, library_path);
+        self.library_dependencies.insert(id, added);
+        sync_state.bump_generation();
+        Ok(id)
+    }
+
+    /// Unloads the library corresponding to `id`, removing its functions from the dispatch
+    /// table and no longer watching it for changes. Dependencies that were loaded along with the
+    /// library are unloaded as well, unless another library uses their functions, in which case
+    /// they remain loaded.
+    ///
+    /// Fails if another library depends on the library's functions, or if objects of the
+    /// unloaded struct types are still alive after collecting garbage.
+    pub fn unload_library(&mut self, id: LibraryId) -> Result<(), Error> {
+        let library_path = self
+            .libraries
+            .get(&id)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Unknown library."))?
+            .clone();
+
+        let sync_state = self.sync_state.clone();
+        let _guard = sync_state.write();
+
+        let mut removed = vec![library_path.clone()];
+        removed.extend(
+            self.library_dependencies[&id]
+                .iter()
+                .filter(|path| self.assemblies.contains_key(*path))
+                .cloned(),
+        );
+
+        // Dependencies that are used by a library that remains loaded, remain loaded themselves
+        loop {
+            let retained: Vec<PathBuf> = removed[1..]
+                .iter()
+                .filter(|path| self.find_dependent(&removed, path).is_some())
+                .cloned()
+                .collect();
+            if retained.is_empty() {
+                break;
+            }
+            removed.retain(|path| !retained.contains(path));
+        }
+
+        // Ensure that no other library links to the library's functions
+        if let Some((fn_name, path)) = self.find_dependent(&removed, &library_path) {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!(
+                    "Failed to unload: function `{}` is used by assembly '{}'.",
+                    fn_name,
+                    path.to_string_lossy()
+                ),
+            )
+            .into());
+        }
+
+        // Ensure that no objects of the unloaded types are alive
+        self.gc.collect();
+        let types: Vec<UnsafeTypeInfo> = removed
+            .iter()
+            .flat_map(|path| self.assemblies[path].info().symbols.types())
+            .filter(|ty| ty.group.is_struct())
+            .map(|ty| {
+                // Safety: `ty` is a shared reference, so is guaranteed to not be `ptr::null()`.
+                UnsafeTypeInfo::new(unsafe {
+                    NonNull::new_unchecked(*ty as *const abi::TypeInfo as *mut _)
+                })
+            })
+            .collect();
+        if self.gc.has_objects_of_types(&types) {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Failed to unload: objects of the library's types are still alive.",
+            )
+            .into());
+        }
+
+        for path in removed.iter() {
+            self.remove_assembly(path);
+        }
+        self.libraries.remove(&id);
+        self.library_dependencies.remove(&id);
+        sync_state.bump_generation();
+        Ok(())
+    }
+
+    /// Finds a function of the assembly at `library_path` that is used by an assembly that is not
+    /// part of `removed`. Returns the name of the function and the path of the assembly that
+    /// uses it.
+    fn find_dependent(
+        &self,
+        removed: &[PathBuf],
+        library_path: &Path,
+    ) -> Option<(String, PathBuf)> {
+        let fn_names: HashSet<&str> = self.assemblies[library_path]
+            .info()
+            .symbols
+            .functions()
+            .iter()
+            .map(|f| f.prototype.name())
+            .collect();
+
+        for (path, other) in self.assemblies.iter() {
+            if removed.contains(path) {
+                continue;
+            }
+
+            let own_fn_names: HashSet<&str> = other
+                .info()
+                .symbols
+                .functions()
+                .iter()
+                .map(|f| f.prototype.name())
+                .collect();
+
+            for (_, fn_prototype) in other.info().dispatch_table.iter() {
+                let fn_name = fn_prototype.name();
+                if fn_names.contains(fn_name) && !own_fn_names.contains(fn_name) {
+                    return Some((fn_name.to_string(), path.clone()));
+                }
+            }
+        }
+        None
+    }
+
+    /// Registers the host function `func` under `name`, so it can be called from Mun through an
+    /// `extern fn` declaration with the same name.
+    ///
+    /// The function is only available to libraries that are loaded, or reloaded, after its
+    /// registration. Its signature is validated against the `extern fn` declarations of those
+    /// libraries when they are linked: a library that declares the function with a different
+    /// signature fails to load, rather than failing when the function is called.
+    ///
+    /// Fails if a function with the same name already exists.
+    pub fn register_host_fn<S: AsRef<str>, F: IntoFunctionDefinition>(
+        &mut self,
+        name: S,
+        func: F,
+    ) -> Result<(), Error> {
+        let name = name.as_ref();
+        if self.dispatch_table.get_fn(name).is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("Failed to register: function `{}` already exists.", name),
+            )
+            .into());
+        }
+
+        let (info, storage) = func.into(name);
+        self.dispatch_table.insert_fn(name, info);
+        self._user_functions.push(storage);
+        Ok(())
+    }
+
+    /// Adds an assembly corresponding to the library at `library_path`, and its dependencies.
+    /// Returns the canonical paths of all assemblies that were added, in the order in which they
+    /// were linked.
+    ///
+    /// If adding the assembly fails, the runtime is left unchanged.
+    fn add_assembly(&mut self, library_path: &Path) -> Result<Vec<PathBuf>, Error> {
         let library_path = library_path.canonicalize()?;
         if self.assemblies.contains_key(&library_path) {
             return Err(io::Error::new(
@@ -245,32 +746,511 @@ impl Runtime {
         }
 
         let mut assembly = Assembly::load(&library_path, self.gc.clone(), &self.dispatch_table)?;
-        for dependency in assembly.info().dependencies() {
-            self.add_assembly(Path::new(dependency))?;
+
+        let mut added = Vec::new();
+        let result = assembly
+            .info()
+            .dependencies()
+            .try_for_each(|dependency| -> Result<(), Error> {
+                added.extend(self.add_assembly(Path::new(dependency))?);
+                Ok(())
+            })
+            .and_then(|()| self.ensure_unambiguous(&assembly))
+            .and_then(|()| {
+                self.watcher
+                    .watch(library_path.parent().unwrap(), RecursiveMode::NonRecursive)
+                    .map_err(Error::from)
+            });
+        if let Err(e) = result {
+            // Dependencies have already been linked, so they need to be removed again
+            for path in added.iter().rev() {
+                self.remove_assembly(path);
+            }
+            return Err(e);
         }
+
         assembly.link(&mut self.dispatch_table);
+        self.assemblies.insert(library_path.clone(), assembly);
+        added.push(library_path);
+        Ok(added)
+    }
 
-        self.watcher
-            .watch(library_path.parent().unwrap(), RecursiveMode::NonRecursive)?;
+    /// Ensures that function and struct type lookups remain unambiguous when `assembly` is linked.
+    fn ensure_unambiguous(&self, assembly: &Assembly) -> Result<(), Error> {
+        for function in assembly.info().symbols.functions() {
+            if self
+                .dispatch_table
+                .get_fn(function.prototype.name())
+                .is_some()
+            {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!(
+                        "Failed to load: function `{}` is already defined by another library.",
+                        function.prototype.name()
+                    ),
+                )
+                .into());
+            }
+        }
 
-        self.assemblies.insert(library_path, assembly);
+        for type_info in assembly.info().symbols.types() {
+            if !type_info.group.is_struct() {
+                continue;
+            }
+
+            let conflicting = self
+                .assemblies
+                .values()
+                .flat_map(|other| other.info().symbols.types())
+                .any(|other| other.name() == type_info.name() && other.guid != type_info.guid);
+            if conflicting {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!(
+                        "Failed to load: type `{}` is already defined by another library.",
+                        type_info.name()
+                    ),
+                )
+                .into());
+            }
+        }
         Ok(())
     }
 
+    /// Removes the assembly corresponding to the library at `library_path` and its functions.
+    /// The library's directory is no longer watched, unless another assembly resides in it.
+    fn remove_assembly(&mut self, library_path: &Path) {
+        if let Some(assembly) = self.assemblies.remove(library_path) {
+            for function in assembly.info().symbols.functions() {
+                self.dispatch_table.remove_fn(function.prototype.name());
+            }
+        }
+
+        let directory = library_path.parent().unwrap();
+        if !self
+            .assemblies
+            .keys()
+            .any(|path| path.parent() == Some(directory))
+        {
+            // Unwatching only fails if the directory no longer exists, in which case it is no
+            // longer watched either
+            let _ = self.watcher.unwatch(directory);
+        }
+    }
+
+    /// Returns whether the calling thread is the thread that spawned the runtime, on which all
+    /// of its functions must be called.
+    ///
+    /// The runtime is only accessible to other threads through unsafe code, e.g. by sending a raw
+    /// pointer to it, which is a common mistake when embedding it in a task system. In debug
+    /// builds, invoking a Mun function therefore asserts that this returns `true`, which turns
+    /// such a mistake into a panic instead of memory corruption. Use a [`RuntimeSnapshot`] to
+    /// invoke functions on worker threads.
+    pub fn is_current_thread(&self) -> bool {
+        thread::current().id() == self.thread
+    }
+
     /// Retrieves the function definition corresponding to `function_name`, if available.
     pub fn get_function_definition(&self, function_name: &str) -> Option<&abi::FunctionDefinition> {
         self.dispatch_table.get_fn(function_name)
     }
 
+    /// Resolves the function `function_name` with arguments `Args` and return type `Output`,
+    /// returning a handle that can be used to repeatedly invoke the function without looking it
+    /// up by name. See [`FnHandle`] for details.
+    pub fn resolve<Args: FnHandleArgs, Output: ReturnTypeReflection>(
+        &self,
+        function_name: &str,
+    ) -> Result<FnHandle<Args, Output>, SignatureError> {
+        FnHandle::resolve(self, function_name)
+    }
+
+    /// Invokes the function `function_name` once for every tuple of arguments in `args`, and
+    /// returns the results in the same order.
+    ///
+    /// The function is resolved and its signature is validated only once for the whole batch. See
+    /// [`FnHandle::call_batch`] for details.
+    pub fn invoke_batch<
+        Args: FnHandleArgs,
+        Output: ReturnTypeReflection,
+        I: IntoIterator<Item = Args>,
+    >(
+        runtime: &Rc<RefCell<Runtime>>,
+        function_name: &str,
+        args: I,
+    ) -> Result<Vec<Output>, SignatureError> {
+        let mut handle = runtime.borrow().resolve::<Args, Output>(function_name)?;
+        handle.call_batch(runtime, args)
+    }
+
+    /// Returns the number of times that the runtime has loaded, unloaded, or reloaded an
+    /// assembly. A change in generation invalidates cached function pointers and signatures.
+    pub(crate) fn generation(&self) -> usize {
+        self.sync_state.generation()
+    }
+
+    /// Returns a `Send + Sync` handle to the runtime that provides read-only access from worker
+    /// threads. See [`SyncStructRef`] for details.
+    pub fn sync_handle(&self) -> SyncRuntimeHandle {
+        SyncRuntimeHandle::new(self.sync_state.clone())
+    }
+
+    /// Returns an immutable, `Send + Sync` snapshot of the runtime's functions, which allows
+    /// invoking pure Mun functions from worker threads. See [`RuntimeSnapshot`] for details.
+    ///
+    /// The snapshot becomes stale when the runtime loads, unloads, or reloads an assembly.
+    pub fn clone_for_thread(&self) -> RuntimeSnapshot {
+        RuntimeSnapshot::new(&self.dispatch_table, self.sync_state.clone())
+    }
+
+    /// Validates that the function `function_name` has arguments of the types `arg_guids` and a
+    /// return type `return_guid`, where `None` represents the `()` type. On success, the
+    /// function's definition is returned, which allows the function pointer to be cached and
+    /// invoked without validating it again.
+    ///
+    /// For struct return types, the `Guid` of the `StructRef` type matches any struct.
+    pub fn validate_signature(
+        &self,
+        function_name: &str,
+        arg_guids: &[abi::Guid],
+        return_guid: Option<abi::Guid>,
+    ) -> Result<&abi::FunctionDefinition, SignatureError> {
+        self.validate_signature_with(
+            function_name,
+            arg_guids.len(),
+            |arg_types| {
+                arg_types.iter().zip(arg_guids).enumerate().try_for_each(
+                    |(idx, (arg_type, guid))| {
+                        if arg_type.guid == *guid {
+                            Ok(())
+                        } else {
+                            Err((idx, arg_type.name().to_string(), self.guid_type_name(guid)))
+                        }
+                    },
+                )
+            },
+            |return_type| {
+                let struct_guid = <StructRef as ReturnTypeReflection>::type_guid();
+                match (return_type, return_guid) {
+                    (None, None) => Ok(()),
+                    (Some(return_type), Some(guid))
+                        if return_type.guid == guid
+                            || (return_type.group.is_struct() && guid == struct_guid) =>
+                    {
+                        Ok(())
+                    }
+                    (return_type, guid) => Err((
+                        return_type.map_or_else(
+                            || <() as ReturnTypeReflection>::type_name().to_string(),
+                            |return_type| return_type.name().to_string(),
+                        ),
+                        guid.map_or_else(
+                            || <() as ReturnTypeReflection>::type_name().to_string(),
+                            |guid| self.guid_type_name(&guid),
+                        ),
+                    )),
+                }
+            },
+        )
+    }
+
+    /// Validates the signature of the function `function_name`, which must have `num_args`
+    /// arguments. On a mismatch, `validate_args` returns the index, expected type name, and found
+    /// type name of the first mismatching argument; and `validate_return` returns the expected and
+    /// found type names.
+    pub(crate) fn validate_signature_with(
+        &self,
+        function_name: &str,
+        num_args: usize,
+        validate_args: impl FnOnce(&[abi::TypeInfo]) -> Result<(), (usize, String, String)>,
+        validate_return: impl FnOnce(Option<&abi::TypeInfo>) -> Result<(), (String, String)>,
+    ) -> Result<&abi::FunctionDefinition, SignatureError> {
+        let function_info = self.get_function_definition(function_name).ok_or_else(|| {
+            SignatureError::FunctionNotFound {
+                function_name: function_name.to_string(),
+            }
+        })?;
+
+        let arg_types = function_info.prototype.signature.arg_types();
+        if arg_types.len() != num_args {
+            return Err(SignatureError::ArgumentCount {
+                expected: arg_types.len(),
+                found: num_args,
+            });
+        }
+
+        validate_args(arg_types).map_err(|(index, expected, found)| {
+            SignatureError::ArgumentType {
+                index,
+                expected,
+                found,
+            }
+        })?;
+
+        validate_return(function_info.prototype.signature.return_type())
+            .map_err(|(expected, found)| SignatureError::ReturnType { expected, found })?;
+
+        Ok(function_info)
+    }
+
+    /// Checks that the size and alignment of `T::Marshalled` match those of the argument at
+    /// `arg_index` of the function `function_name`.
+    ///
+    /// Unlike signature validation, which compares type identities, this compares the memory
+    /// representations that are passed across the boundary. Struct arguments are passed as
+    /// pointers, so their layout is that of a pointer.
+    pub fn check_marshal_compat<T: ArgumentReflection>(
+        &self,
+        function_name: &str,
+        arg_index: usize,
+    ) -> Result<(), LayoutMismatch> {
+        let function_info = self.get_function_definition(function_name).ok_or_else(|| {
+            LayoutMismatch::FunctionNotFound {
+                function_name: function_name.to_string(),
+            }
+        })?;
+
+        let arg_types = function_info.prototype.signature.arg_types();
+        let arg_type = arg_types
+            .get(arg_index)
+            .ok_or(LayoutMismatch::ArgumentNotFound {
+                index: arg_index,
+                num_args: arg_types.len(),
+            })?;
+
+        let (expected_size, expected_alignment) = if arg_type.group.is_struct() {
+            (
+                mem::size_of::<*const ffi::c_void>(),
+                mem::align_of::<*const ffi::c_void>(),
+            )
+        } else {
+            (arg_type.size_in_bytes(), arg_type.alignment())
+        };
+        let found_size = mem::size_of::<T::Marshalled>();
+        let found_alignment = mem::align_of::<T::Marshalled>();
+        if expected_size != found_size || expected_alignment != found_alignment {
+            return Err(LayoutMismatch::Layout {
+                index: arg_index,
+                type_name: arg_type.name().to_string(),
+                expected_size,
+                found_size,
+                expected_alignment,
+                found_alignment,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the name of the type with the specified `guid`, if it is known to any of the loaded
+    /// assemblies, or a textual representation of the `guid` otherwise.
+    fn guid_type_name(&self, guid: &abi::Guid) -> String {
+        self.assemblies
+            .values()
+            .flat_map(|assembly| assembly.info().symbols.types().iter())
+            .find(|type_info| type_info.guid == *guid)
+            .map_or_else(
+                || format!("{:?}", guid),
+                |type_info| type_info.name().to_string(),
+            )
+    }
+
+    /// Retrieves the type information of the struct type called `type_name`, or `None` if no such
+    /// struct type exists.
+    pub(crate) fn find_struct_type(&self, type_name: &str) -> Option<&abi::TypeInfo> {
+        self.assemblies
+            .values()
+            .flat_map(|assembly| assembly.info().symbols.types().iter())
+            .find(|type_info| type_info.group.is_struct() && type_info.name() == type_name)
+    }
+
+    /// Verifies the integrity of the ABI data of all loaded modules, returning all problems that
+    /// were found.
+    ///
+    /// This checks that the types of all function arguments, return values, and struct fields are
+    /// present, and that the struct types among them are defined by a loaded module. It also checks
+    /// that type alignments are powers of two, and that struct fields do not overlap and lie within
+    /// the size of their struct. Verifying modules after loading them catches corrupt or
+    /// mismatching ABI data before it causes a crash, e.g. when loading third-party plugins.
+    pub fn verify_module(&self) -> Result<(), Vec<ModuleError>> {
+        let infos: Vec<&abi::AssemblyInfo> = self
+            .assemblies
+            .values()
+            .map(|assembly| assembly.info())
+            .collect();
+        let struct_types: HashSet<abi::Guid> = infos
+            .iter()
+            .flat_map(|info| info.symbols.types().iter())
+            .filter(|type_info| type_info.group.is_struct())
+            .map(|type_info| type_info.guid)
+            .collect();
+
+        let mut errors = Vec::new();
+        for info in infos {
+            verify::verify_assembly(info, &struct_types, &mut errors);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Retrieves the `Guid` of the type called `type_name`, or `None` if none of the loaded
+    /// assemblies contain such a type.
+    ///
+    /// The `Guid` of a struct type changes when its layout changes, so comparing `Guid`s that were
+    /// retrieved before and after a hot reload reveals which types changed.
+    pub fn type_guid(&self, type_name: &str) -> Option<abi::Guid> {
+        self.find_type(type_name).map(|type_info| type_info.guid)
+    }
+
+    /// Returns whether the type called `type_name` is a struct type, or `false` if none of the
+    /// loaded assemblies contain such a type.
+    pub fn is_struct_type(&self, type_name: &str) -> bool {
+        self.find_type(type_name)
+            .map_or(false, |type_info| type_info.group.is_struct())
+    }
+
+    /// Returns whether the type called `type_name` is a fundamental type, or `false` if none of
+    /// the loaded assemblies contain such a type.
+    pub fn is_fundamental_type(&self, type_name: &str) -> bool {
+        self.find_type(type_name)
+            .map_or(false, |type_info| type_info.group.is_fundamental())
+    }
+
+    /// Retrieves the type information of the type called `type_name`, or `None` if none of the
+    /// loaded assemblies contain such a type.
+    fn find_type(&self, type_name: &str) -> Option<&abi::TypeInfo> {
+        self.assemblies
+            .values()
+            .flat_map(|assembly| assembly.info().symbols.types().iter())
+            .find(|type_info| type_info.name() == type_name)
+    }
+
+    /// Retrieves the byte offset of the field `field_name` of the struct type called `type_name`,
+    /// relative to the start of the struct's memory.
+    pub fn offset_of(&self, type_name: &str, field_name: &str) -> Result<usize, String> {
+        let type_info = self
+            .find_struct_type(type_name)
+            .ok_or_else(|| format!("Struct type `{}` does not exist.", type_name))?;
+
+        // Safety: `as_struct` is guaranteed to return `Some` for struct types.
+        let struct_info = type_info.as_struct().unwrap();
+        let field_idx = abi::StructInfo::find_field_index(type_name, struct_info, field_name)?;
+
+        Ok(usize::from(struct_info.field_offsets()[field_idx]))
+    }
+
+    /// Retrieves the memory layout of the fields of the struct type called `type_name`, or `None`
+    /// if no such struct type exists.
+    ///
+    /// Fields of garbage collected struct types are stored as pointers, so their size and
+    /// alignment are those of a pointer.
+    pub fn struct_layout(&self, type_name: &str) -> Option<Vec<FieldLayout>> {
+        let type_info = self.find_struct_type(type_name)?;
+
+        // Safety: `as_struct` is guaranteed to return `Some` for struct types.
+        let struct_info = type_info.as_struct().unwrap();
+        let layout = struct_info
+            .field_names()
+            .zip(struct_info.field_types().iter())
+            .zip(struct_info.field_offsets().iter())
+            .enumerate()
+            .map(|(idx, ((name, field_type), offset))| {
+                let (size, alignment) = match field_type.as_struct() {
+                    Some(s) if s.memory_kind == abi::StructMemoryKind::GC => (
+                        mem::size_of::<*const ffi::c_void>(),
+                        mem::align_of::<*const ffi::c_void>(),
+                    ),
+                    _ => (field_type.size_in_bytes(), field_type.alignment()),
+                };
+
+                FieldLayout {
+                    name,
+                    offset: usize::from(*offset),
+                    size,
+                    alignment,
+                    type_name: field_type.name(),
+                    doc: struct_info.field_doc(idx),
+                }
+            })
+            .collect();
+        Some(layout)
+    }
+
+    /// Retrieves the optimization level that the library corresponding to `id` was compiled with,
+    /// from `0` for no optimizations to `3` for aggressive optimizations, like the `-O` flag of
+    /// the compiler. This allows hosts to warn when an unoptimized library is loaded in
+    /// production.
+    ///
+    /// Returns `None` if no such library is loaded, or if it was compiled by a version of the
+    /// compiler that did not record its optimization level.
+    pub fn module_opt_level(&self, id: LibraryId) -> Option<u8> {
+        let library_path = self.libraries.get(&id)?;
+        self.assemblies.get(library_path)?.opt_level()
+    }
+
+    /// Retrieves the source location of the function that `fn_ptr` points to, if available.
+    ///
+    /// Source locations are only available for assemblies that were compiled with debug
+    /// information.
+    pub fn function_source_location(
+        &self,
+        fn_ptr: *const ffi::c_void,
+    ) -> Option<&abi::FunctionSourceLocation> {
+        self.assemblies.values().find_map(|assembly| {
+            let function = assembly
+                .info()
+                .symbols
+                .functions()
+                .iter()
+                .find(|f| f.fn_ptr == fn_ptr)?;
+            assembly
+                .debug_info()?
+                .find_location(function.prototype.name())
+        })
+    }
+
     /// Updates the state of the runtime. This includes checking for file changes, and reloading
     /// compiled assemblies.
+    ///
+    /// All pending file changes are processed, so multiple assemblies can be reloaded at once.
+    /// Returns whether any assembly was reloaded successfully.
     pub fn update(&mut self) -> bool {
+        let sync_state = self.sync_state.clone();
+        let mut reloaded = false;
         while let Ok(event) = self.watcher_rx.try_recv() {
             use notify::DebouncedEvent::*;
             match event {
                 Write(ref path) | Rename(_, ref path) | Create(ref path) => {
                     if let Some(assembly) = self.assemblies.get_mut(path) {
-                        if let Err(e) = assembly.swap(path, &mut self.dispatch_table) {
+                        for callback in self.reload_callbacks.iter_mut() {
+                            callback(ReloadPhase::Before, path);
+                        }
+
+                        let result = {
+                            let _guard = sync_state.write();
+                            let result = assembly.swap(path, &mut self.dispatch_table);
+                            if let Ok(remapping) = &result {
+                                // The remapping of an earlier reload is only replaced once
+                                if !reloaded {
+                                    self.last_reload_remapping.clear();
+                                }
+                                self.last_reload_remapping.extend(remapping.clone());
+                                sync_state.bump_generation();
+                            }
+                            result
+                        };
+
+                        let outcome = result.as_ref().map(|_| ()).map_err(|e| e.to_string());
+                        for callback in self.reload_callbacks.iter_mut() {
+                            callback(ReloadPhase::After(outcome.clone()), path);
+                        }
+
+                        if let Err(e) = result {
                             println!(
                                 "An error occured while reloading assembly '{}': {:?}",
                                 path.to_string_lossy(),
@@ -281,14 +1261,44 @@ impl Runtime {
                                 "Succesfully reloaded assembly: '{}'",
                                 path.to_string_lossy()
                             );
-                            return true;
+                            reloaded = true;
                         }
                     }
                 }
                 _ => {}
             }
         }
-        false
+        reloaded
+    }
+
+    /// Registers a `callback` that is invoked when an assembly is hot reloaded during [`update`],
+    /// with the phase of the reload and the path of the assembly.
+    ///
+    /// The callback is invoked with `ReloadPhase::Before` before the assembly is swapped, while
+    /// functions, structs, and [`SyncStructRef`]s of the old assembly are still valid. Afterwards,
+    /// it is invoked with `ReloadPhase::After`, which reports whether the reload succeeded.
+    ///
+    /// Callbacks are invoked from within [`update`], while its caller mutably borrows the runtime.
+    /// Accessing a `StructRef` or anything else that borrows the runtime from a callback therefore
+    /// panics. Use [`SyncStructRef`]s instead, or record the reload and act on it once [`update`]
+    /// returns.
+    ///
+    /// [`update`]: #method.update
+    pub fn on_reload<F: FnMut(ReloadPhase, &Path) + 'static>(&mut self, callback: F) {
+        self.reload_callbacks.push(Box::new(callback));
+    }
+
+    /// Returns how the fields of struct types were remapped during the last call to [`update`]
+    /// that successfully reloaded assemblies, keyed by the name of their struct type. This
+    /// describes what happened to the fields of all structs of those types that were alive during
+    /// the reload.
+    ///
+    /// Only struct types whose layout changed are included. The fields of each type are listed in
+    /// the order of the new type's declaration, together with how their values were initialized.
+    ///
+    /// [`update`]: #method.update
+    pub fn last_reload_remapping(&self) -> &HashMap<String, Vec<(String, FieldRemap)>> {
+        &self.last_reload_remapping
     }
 
     /// Returns a shared reference to the runtime's garbage collector.
@@ -299,16 +1309,229 @@ impl Runtime {
         self.gc.as_ref()
     }
 
+    /// Roots the raw struct `raw`, which keeps it and the structs it references alive until it is
+    /// passed to [`unroot`]. A struct can be rooted multiple times, in which case it has to be
+    /// unrooted an equal number of times before it can be collected.
+    ///
+    /// Unlike a `StructRef`, a `RawStruct` does not keep its struct alive by itself. Accessing a
+    /// `RawStruct` after its struct has been collected is undefined behavior.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must refer to a struct that was allocated by this runtime's garbage collector and
+    /// that has not been collected.
+    ///
+    /// [`unroot`]: #method.unroot
+    pub unsafe fn root(&self, raw: &RawStruct) {
+        self.gc.root(raw.0);
+    }
+
+    /// Unroots the raw struct `raw`, which was rooted with [`root`] or
+    /// [`StructRef::into_rooted_raw`]. Once a struct has as many unroots as roots, it is
+    /// collected during the next garbage collection, unless it is still referenced, after which
+    /// `raw` must no longer be used.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must refer to a struct that was allocated by this runtime's garbage collector and
+    /// that has not been collected. The struct must have been rooted more times than it has been
+    /// unrooted.
+    ///
+    /// [`root`]: #method.root
+    /// [`StructRef::into_rooted_raw`]: struct.StructRef.html#method.into_rooted_raw
+    pub unsafe fn unroot(&self, raw: &RawStruct) {
+        self.gc.unroot(raw.0);
+    }
+
     /// Collects all memory that is no longer referenced by rooted objects. Returns `true` if memory
     /// was reclaimed, `false` otherwise. This behavior will likely change in the future.
     pub fn gc_collect(&self) -> bool {
+        let _guard = self.sync_state.write();
         self.gc.collect()
     }
 
+    /// Returns the most recent writes to struct fields through [`StructRef::set`],
+    /// [`StructRef::replace`], [`StructRef::replace_with`], [`StructRef::set_all`],
+    /// [`StructRef::copy_from`], and [`StructRef::with_bytes_mut`], from oldest to newest. Writes
+    /// of multiple fields at once are recorded as one mutation per field, with equal timestamps.
+    ///
+    /// The log is a ring buffer that holds the number of mutations set with
+    /// [`RuntimeBuilder::set_mutation_log_capacity`], so the oldest mutations are discarded first.
+    /// Writes by Mun code are not recorded. Replaying the sequence of state changes that led to a
+    /// bad state helps to reproduce bugs, but copying every write makes them slower, so the log is
+    /// only available with the `mutation-log` feature.
+    ///
+    /// [`StructRef::set`]: struct.StructRef.html#method.set
+    /// [`StructRef::replace`]: struct.StructRef.html#method.replace
+    /// [`StructRef::replace_with`]: struct.StructRef.html#method.replace_with
+    /// [`StructRef::set_all`]: struct.StructRef.html#method.set_all
+    /// [`StructRef::copy_from`]: struct.StructRef.html#method.copy_from
+    /// [`StructRef::with_bytes_mut`]: struct.StructRef.html#method.with_bytes_mut
+    /// [`RuntimeBuilder::set_mutation_log_capacity`]: struct.RuntimeBuilder.html#method.set_mutation_log_capacity
+    #[cfg(feature = "mutation-log")]
+    pub fn mutation_log(&self) -> Vec<Mutation> {
+        self.mutation_log.lock().mutations().cloned().collect()
+    }
+
+    /// Removes all mutations from the [`Runtime::mutation_log`].
+    #[cfg(feature = "mutation-log")]
+    pub fn clear_mutation_log(&self) {
+        self.mutation_log.lock().clear();
+    }
+
+    /// Drops at most `budget` of the resources that were attached to collected structs, and
+    /// returns the number of resources that are still pending.
+    ///
+    /// Resources are only queued if the runtime was built with
+    /// [`RuntimeBuilder::set_deferred_resource_drops`]; otherwise they are dropped during
+    /// collection and this does nothing. Deferring the drops keeps the cost of expensive `Drop`
+    /// implementations out of collection pauses, as the host can spread the drops over multiple
+    /// frames. A queued resource remains valid until it is dropped, even though the struct that
+    /// owned it no longer exists. Resources that are still queued when the runtime is dropped are
+    /// dropped along with it.
+    pub fn run_pending_finalizers(&self, budget: usize) -> usize {
+        self.gc.observer().drop_pending(budget)
+    }
+
+    /// Collects all memory that is no longer referenced by rooted objects, and then releases
+    /// memory that the garbage collector reserved but no longer uses.
+    ///
+    /// Released memory has to be reserved again when the number of structs grows, which makes
+    /// subsequent allocations slower. This is intended for hosts that care more about their memory
+    /// footprint than allocation speed, e.g. after a spike in allocations.
+    ///
+    /// Whether freed struct memory is returned to the operating system depends on the allocator
+    /// set with [`RuntimeBuilder::set_allocator`], for which this is only a hint. The default
+    /// allocator returns it on Linux with glibc only.
+    ///
+    /// [`RuntimeBuilder::set_allocator`]: struct.RuntimeBuilder.html#method.set_allocator
+    pub fn shrink_heap(&self) {
+        let _guard = self.sync_state.write();
+        self.gc.collect();
+        self.gc.shrink_to_fit();
+    }
+
     /// Returns statistics about the garbage collector.
     pub fn gc_stats(&self) -> gc::Stats {
         self.gc.stats()
     }
+
+    /// Returns the memory usage of all allocated structs, grouped by the name of their type.
+    ///
+    /// No garbage collection is performed, so structs that are unreachable but have not yet been
+    /// collected are included.
+    pub fn memory_usage(&self) -> HashMap<String, TypeUsage> {
+        self.gc.memory_usage()
+    }
+
+    /// Serializes all rooted structs and the structs they transitively reference, which can be
+    /// deserialized into a runtime with [`Runtime::restore`], e.g. for save states or
+    /// deterministic tests.
+    ///
+    /// References between structs are preserved, including shared and cyclic references.
+    /// Resources that are attached to structs are not included.
+    pub fn snapshot(&self) -> Vec<u8> {
+        let _guard = self.sync_state.read();
+        heap_snapshot::write(&self.gc)
+    }
+
+    /// Deserializes the structs of a heap snapshot that was created with [`Runtime::snapshot`]
+    /// into the heap of `runtime`, and returns the structs that were rooted when the snapshot was
+    /// created - each rooted once. The restored structs are copies; the structs from which the
+    /// snapshot was created, if any still exist, are unaffected.
+    ///
+    /// A snapshot survives hot reloads that do not change the layout of any of its struct types.
+    /// Restoring a snapshot fails without allocating any structs if one of its types no longer
+    /// exists or has a different layout, or if the snapshot was created with a different version
+    /// of the snapshot format or on a different target.
+    pub fn restore(
+        runtime: &Rc<RefCell<Runtime>>,
+        bytes: &[u8],
+    ) -> Result<Vec<StructRef>, HeapSnapshotError> {
+        let roots = heap_snapshot::read(&runtime.borrow(), bytes)?;
+        Ok(roots
+            .into_iter()
+            .map(|raw| StructRef::new(runtime.clone(), raw))
+            .collect())
+    }
+
+    /// Validates that every garbage collected struct field references a live struct of the
+    /// field's type. Returns all violations that were found.
+    ///
+    /// This is only available in debug builds, or with the `heap-validation` feature.
+    #[cfg(any(debug_assertions, feature = "heap-validation"))]
+    pub fn validate_heap(&self) -> Vec<ValidationError> {
+        self.gc.validate()
+    }
+
+    /// Invokes `f` while garbage collection is disabled, which guarantees that no struct memory is
+    /// freed, e.g. while holding raw pointers into structs. Garbage collection is enabled again
+    /// when `f` returns or panics; call `gc_collect` afterwards to reclaim memory that became
+    /// unreachable in the meantime.
+    ///
+    /// While garbage collection is disabled, `gc_collect` does nothing and returns `false`. Calls
+    /// can be nested.
+    ///
+    /// Unreachable memory is never reclaimed while garbage collection is disabled, so sections
+    /// that allocate heavily risk running out of memory.
+    pub fn with_gc_disabled<R>(&self, f: impl FnOnce() -> R) -> R {
+        /// Enables garbage collection when dropped.
+        struct EnableCollection<'g>(&'g GarbageCollector);
+
+        impl Drop for EnableCollection<'_> {
+            fn drop(&mut self) {
+                self.0.enable_collection();
+            }
+        }
+
+        self.gc.disable_collection();
+        let _enable = EnableCollection(&self.gc);
+        f()
+    }
+
+    /// Invokes `f` within an allocation scope. Structs that are allocated while the scope is open
+    /// are bump-allocated and freed all at once when `f` returns, without participating in
+    /// garbage collection.
+    ///
+    /// Structs that are still referenced when the scope closes, either by a `StructRef` or by a
+    /// struct that was allocated outside of the scope, are moved to the garbage collected heap
+    /// instead. Closing a scope traces all structs on the heap, so its cost grows with the size of
+    /// the heap.
+    pub fn scope<R>(&self, f: impl FnOnce(&Scope) -> R) -> R {
+        self.gc.begin_scope();
+        let scope = Scope { runtime: self };
+        f(&scope)
+    }
+}
+
+/// An open allocation scope of a [`Runtime`], which is closed when dropped.
+///
+/// See [`Runtime::scope`] for more information.
+pub struct Scope<'r> {
+    runtime: &'r Runtime,
+}
+
+impl<'r> Scope<'r> {
+    /// Returns the runtime that the scope belongs to.
+    pub fn runtime(&self) -> &'r Runtime {
+        self.runtime
+    }
+}
+
+impl Drop for Scope<'_> {
+    fn drop(&mut self) {
+        let _guard = self.runtime.sync_state.write();
+        self.runtime.gc.end_scope();
+    }
+}
+
+impl Drop for Runtime {
+    fn drop(&mut self) {
+        // Invalidate `SyncStructRef`s, as the type information of their structs is dropped along
+        // with the assemblies.
+        let _guard = self.sync_state.write();
+        self.sync_state.bump_generation();
+    }
 }
 
 /// Extends a result object with functions that allow retrying of an action.
@@ -317,10 +1540,28 @@ pub trait RetryResultExt: Sized {
     type Output;
 
     /// Retries an action, resulting in a potentially mutated version of itself.
+    ///
+    /// An error that cannot be retried is returned immediately.
     fn retry(self) -> Self;
 
     /// Keeps retrying the same action until it succeeds, resulting in an output.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the action fails with an error that cannot be retried.
     fn wait(self) -> Self::Output;
+
+    /// Keeps retrying the same action until it succeeds, or until it has been retried
+    /// `max_attempts` times, resulting in the last error.
+    ///
+    /// As every retry waits for an update of the runtime, this does not return until the runtime
+    /// has been updated `max_attempts` times, unless the action succeeds. Use `wait_for` to bound
+    /// the time spent waiting instead.
+    fn wait_timeout(self, max_attempts: usize) -> Self;
+
+    /// Keeps retrying the same action until it succeeds, or until `duration` has elapsed,
+    /// resulting in the last error.
+    fn wait_for(self, duration: Duration) -> Self;
 }
 
 invoke_fn_impl! {
@@ -8,6 +8,18 @@ macro_rules! count_args {
     }
 }
 
+/// Enters a `tracing` span at the `TRACE` level named `mun_invoke`, which records the name of the
+/// invoked function, until the end of the enclosing scope. Expands to nothing if the `tracing`
+/// feature is disabled.
+macro_rules! enter_invoke_span {
+    ($function_name:expr) => {
+        #[cfg(feature = "tracing")]
+        let span = ::tracing::trace_span!("mun_invoke", function = $function_name);
+        #[cfg(feature = "tracing")]
+        let _enter = span.enter();
+    };
+}
+
 macro_rules! invoke_fn_impl {
     ($(
         fn $FnName:ident($($Arg:tt: $T:ident),*) -> $ErrName:ident;
@@ -16,11 +28,14 @@ macro_rules! invoke_fn_impl {
             /// An invocation error that contains the function name, a mutable reference to the
             /// runtime, passed arguments, and the output type. This allows the caller to retry
             /// the function invocation using the `Retriable` trait.
+            ///
+            /// If the function was invoked, but its return value could not be marshalled, the
+            /// arguments have been consumed and the invocation cannot be retried.
             pub struct $ErrName<'s, $($T: ArgumentReflection,)* Output: ReturnTypeReflection> {
                 msg: String,
                 runtime: std::rc::Rc<core::cell::RefCell<Runtime>>,
                 function_name: &'s str,
-                $($Arg: $T,)*
+                args: Option<($($T,)*)>,
                 output: core::marker::PhantomData<Output>,
             }
 
@@ -50,10 +65,35 @@ macro_rules! invoke_fn_impl {
                         msg: err_msg,
                         runtime,
                         function_name,
-                        $($Arg,)*
+                        args: Some(($($Arg,)*)),
+                        output: core::marker::PhantomData,
+                    }
+                }
+
+                /// Constructs a new invocation error that cannot be retried, because the
+                /// arguments have been consumed.
+                fn without_args(err_msg: String, runtime: std::rc::Rc<core::cell::RefCell<Runtime>>, function_name: &'s str) -> Self {
+                    Self {
+                        msg: err_msg,
+                        runtime,
+                        function_name,
+                        args: None,
                         output: core::marker::PhantomData,
                     }
                 }
+
+                /// Returns whether the invocation can be retried.
+                pub fn is_retriable(&self) -> bool {
+                    self.args.is_some()
+                }
+
+                /// Invokes the function again with the original arguments, if it can be retried.
+                fn reinvoke(self) -> core::result::Result<Output, Self> {
+                    match self.args {
+                        Some(($($Arg,)*)) => $crate::Runtime::$FnName(&self.runtime, self.function_name, $($Arg,)*),
+                        None => Err(self),
+                    }
+                }
             }
 
             impl<'s, $($T: ArgumentReflection,)* Output: ReturnTypeReflection> $crate::RetryResultExt for core::result::Result<Output, $ErrName<'s, $($T,)* Output>> {
@@ -63,21 +103,64 @@ macro_rules! invoke_fn_impl {
                     match self {
                         Ok(output) => Ok(output),
                         Err(err) => {
+                            if !err.is_retriable() {
+                                return Err(err);
+                            }
                             eprintln!("{}", err.msg);
                             while !err.runtime.borrow_mut().update() {
                                 // Wait until there has been an update that might fix the error
                             }
-                            $crate::Runtime::$FnName(&err.runtime, err.function_name, $(err.$Arg,)*)
+                            err.reinvoke()
                         }
                     }
                 }
 
                 fn wait(mut self) -> Self::Output {
                     loop {
-                        if let Ok(output) = self {
-                            return output;
-                        } else {
-                            self = self.retry();
+                        match self {
+                            Ok(output) => return output,
+                            Err(err) => {
+                                assert!(err.is_retriable(), "{}", err.msg);
+                                self = Err(err).retry();
+                            }
+                        }
+                    }
+                }
+
+                fn wait_timeout(mut self, max_attempts: usize) -> Self {
+                    for _ in 0..max_attempts {
+                        let retriable = match &self {
+                            Ok(_) => false,
+                            Err(err) => err.is_retriable(),
+                        };
+                        if !retriable {
+                            break;
+                        }
+                        self = self.retry();
+                    }
+                    self
+                }
+
+                fn wait_for(self, duration: std::time::Duration) -> Self {
+                    let deadline = std::time::Instant::now() + duration;
+                    let mut result = self;
+                    loop {
+                        match result {
+                            Ok(output) => return Ok(output),
+                            Err(err) => {
+                                if !err.is_retriable() || std::time::Instant::now() >= deadline {
+                                    return Err(err);
+                                }
+                                eprintln!("{}", err.msg);
+                                result = loop {
+                                    if err.runtime.borrow_mut().update() {
+                                        break err.reinvoke();
+                                    } else if std::time::Instant::now() >= deadline {
+                                        break Err(err);
+                                    }
+                                    // Wait until there has been an update that might fix the error
+                                };
+                            }
                         }
                     }
                 }
@@ -89,77 +172,206 @@ macro_rules! invoke_fn_impl {
                 ///
                 /// If an error occurs when invoking the method, an error message is logged. The
                 /// runtime continues looping until the cause of the error has been resolved.
-                #[allow(clippy::too_many_arguments, unused_assignments)]
+                ///
+                /// If the method returns a value that cannot be marshalled into `Output`, the
+                /// returned error cannot be retried.
+                #[allow(clippy::too_many_arguments, unused_assignments, unused_variables)]
                 pub fn $FnName<'s, $($T: ArgumentReflection,)* Output: ReturnTypeReflection>(
                     runtime: &std::rc::Rc<core::cell::RefCell<Runtime>>,
                     function_name: &'s str,
                     $($Arg: $T,)*
                 ) -> core::result::Result<Output, $ErrName<'s, $($T,)* Output>> {
                     let runtime_ref = runtime.borrow();
-                    match runtime_ref
-                        .get_function_definition(function_name)
-                        .ok_or_else(|| format!("Failed to obtain function '{}'", function_name))
-                        .and_then(|function_info| {
-                            // Validate function signature
-                            let num_args = $crate::count_args!($($T),*);
-
-                            let arg_types = function_info.prototype.signature.arg_types();
-                            if arg_types.len() != num_args {
-                                return Err(format!(
-                                    "Invalid number of arguments. Expected: {}. Found: {}.",
-                                    arg_types.len(),
-                                    num_args,
-                                ));
-                            }
-
+                    debug_assert!(runtime_ref.is_current_thread(), "Mun functions must be invoked on the thread that spawned the runtime.");
+                    match runtime_ref.validate_signature_with(
+                        function_name,
+                        $crate::count_args!($($T),*),
+                        |arg_types| {
                             #[allow(unused_mut, unused_variables)]
                             let mut idx = 0;
                             $(
                                 crate::reflection::equals_argument_type(&runtime_ref, &arg_types[idx], &$Arg)
-                                    .map_err(|(expected, found)| {
-                                        format!(
-                                            "Invalid argument type at index {}. Expected: {}. Found: {}.",
-                                            idx,
-                                            expected,
-                                            found,
-                                        )
-                                    })?;
+                                    .map_err(|(expected, found)| (idx, expected.to_string(), found.to_string()))?;
                                 idx += 1;
                             )*
-
-                            if let Some(return_type) = function_info.prototype.signature.return_type() {
-                                crate::reflection::equals_return_type::<Output>(return_type)
-                            } else if <() as ReturnTypeReflection>::type_guid() != Output::type_guid() {
-                                Err((<() as ReturnTypeReflection>::type_name(), Output::type_name()))
-                            } else {
-                                Ok(())
-                            }.map_err(|(expected, found)| {
-                                format!(
-                                    "Invalid return type. Expected: {}. Found: {}",
-                                    expected,
-                                    found,
-                                )
-                            })?;
-
-                            Ok(function_info)
-                        }) {
+                            Ok(())
+                        },
+                        |return_type| {
+                            crate::reflection::equals_optional_return_type::<Output>(return_type)
+                                .map_err(|(expected, found)| (expected.to_string(), found.to_string()))
+                        },
+                    ) {
                         Ok(function_info) => {
                             let function: fn($($T::Marshalled),*) -> Output::Marshalled = unsafe {
                                 core::mem::transmute(function_info.fn_ptr)
                             };
-                            let result = function($($Arg.marshal()),*);
+                            // Marshal the arguments
+                            let arg_types = function_info.prototype.signature.arg_types();
+                            #[allow(unused_mut, unused_variables)]
+                            let mut idx = 0;
+                            $(
+                                let $Arg = $Arg.marshal(&runtime_ref, &arg_types[idx]);
+                                idx += 1;
+                            )*
+
+                            let result = {
+                                enter_invoke_span!(function_name);
+                                function($($Arg),*)
+                            };
 
                             // Marshall the result
-                            return Ok(result.marshal_value(runtime.clone()))
+                            return result.marshal_value(runtime.clone()).map_err(|e| {
+                                $ErrName::without_args(e.to_string(), runtime.clone(), function_name)
+                            })
                         }
-                        Err(e) => Err($ErrName::new(e, runtime.clone(), function_name, $($Arg),*))
+                        Err(e) => Err($ErrName::new(e.to_string(), runtime.clone(), function_name, $($Arg),*))
                     }
                 }
             }
+
+            impl $crate::RuntimeSnapshot {
+                /// Invokes the function `function_name` with arguments `args` on the calling
+                /// thread. See [`RuntimeSnapshot`] for the restrictions that apply.
+                #[allow(clippy::too_many_arguments, unused_assignments, unused_variables)]
+                pub fn $FnName<$($T: ReturnTypeReflection<Marshalled = $T>,)* Output: ReturnTypeReflection<Marshalled = Output>>(
+                    &self,
+                    function_name: &str,
+                    $($Arg: $T,)*
+                ) -> core::result::Result<Output, String> {
+                    let (_guard, fn_ptr) = self.resolve::<Output>(
+                        function_name,
+                        &[$((<$T as ReturnTypeReflection>::type_guid(), <$T as ReturnTypeReflection>::type_name())),*],
+                    )?;
+                    let function: fn($($T),*) -> Output = unsafe { core::mem::transmute(fn_ptr) };
+                    enter_invoke_span!(function_name);
+                    Ok(function($($Arg),*))
+                }
+            }
+
+            impl<$($T: ArgumentReflection,)*> $crate::FnHandleArgs for ($($T,)*) {
+                const NUM_ARGS: usize = $crate::count_args!($($T),*);
+
+                #[allow(unused_assignments, unused_variables)]
+                fn call_batch<Output: ReturnTypeReflection, I: IntoIterator<Item = Self>>(
+                    handle: &mut $crate::FnHandle<Self, Output>,
+                    runtime: &std::rc::Rc<core::cell::RefCell<Runtime>>,
+                    args: I,
+                ) -> core::result::Result<Vec<Output>, $crate::SignatureError> {
+                    let runtime_ref = runtime.borrow();
+                    debug_assert!(runtime_ref.is_current_thread(), "Mun functions must be invoked on the thread that spawned the runtime.");
+                    handle.refresh(&runtime_ref)?;
+
+                    // Safety: The handle was refreshed, and the runtime cannot be updated while it
+                    // is borrowed, so the handle is not stale.
+                    let arg_types = unsafe { handle.arg_types() };
+                    let function: fn($($T::Marshalled),*) -> Output::Marshalled = unsafe {
+                        core::mem::transmute(handle.fn_ptr())
+                    };
+
+                    let args = args.into_iter();
+                    let mut results = Vec::with_capacity(args.size_hint().0);
+                    for ($($Arg,)*) in args {
+                        #[allow(unused_mut, unused_variables)]
+                        let mut idx = 0;
+                        $(
+                            crate::reflection::equals_argument_type(&runtime_ref, &arg_types[idx], &$Arg)
+                                .map_err(|(expected, found)| $crate::SignatureError::ArgumentType {
+                                    index: idx,
+                                    expected: expected.to_string(),
+                                    found: found.to_string(),
+                                })?;
+                            idx += 1;
+                        )*
+
+                        // Marshal the arguments
+                        #[allow(unused_mut, unused_variables)]
+                        let mut idx = 0;
+                        $(
+                            let $Arg = $Arg.marshal(&runtime_ref, &arg_types[idx]);
+                            idx += 1;
+                        )*
+
+                        let result = {
+                            enter_invoke_span!(handle.function_name());
+                            function($($Arg),*)
+                        };
+
+                        // Marshall the result
+                        results.push(
+                            result
+                                .marshal_value(runtime.clone())
+                                .map_err($crate::SignatureError::InvalidReturnValue)?,
+                        );
+                    }
+                    Ok(results)
+                }
+            }
+
+            impl<$($T: ArgumentReflection,)* Output: ReturnTypeReflection> $crate::FnHandle<($($T,)*), Output> {
+                /// Invokes the function with arguments `args`, without looking it up by name. If
+                /// the handle is stale, the function is resolved again first.
+                #[allow(clippy::too_many_arguments, unused_assignments, unused_variables)]
+                pub fn call(
+                    &mut self,
+                    runtime: &std::rc::Rc<core::cell::RefCell<Runtime>>,
+                    $($Arg: $T,)*
+                ) -> core::result::Result<Output, $crate::SignatureError> {
+                    let runtime_ref = runtime.borrow();
+                    debug_assert!(runtime_ref.is_current_thread(), "Mun functions must be invoked on the thread that spawned the runtime.");
+                    self.refresh(&runtime_ref)?;
+
+                    // Safety: The handle was refreshed, so it is not stale.
+                    let arg_types = unsafe { self.arg_types() };
+                    #[allow(unused_mut, unused_variables)]
+                    let mut idx = 0;
+                    $(
+                        crate::reflection::equals_argument_type(&runtime_ref, &arg_types[idx], &$Arg)
+                            .map_err(|(expected, found)| $crate::SignatureError::ArgumentType {
+                                index: idx,
+                                expected: expected.to_string(),
+                                found: found.to_string(),
+                            })?;
+                        idx += 1;
+                    )*
+
+                    let function: fn($($T::Marshalled),*) -> Output::Marshalled = unsafe {
+                        core::mem::transmute(self.fn_ptr())
+                    };
+                    // Marshal the arguments
+                    #[allow(unused_mut, unused_variables)]
+                    let mut idx = 0;
+                    $(
+                        let $Arg = $Arg.marshal(&runtime_ref, &arg_types[idx]);
+                        idx += 1;
+                    )*
+
+                    let result = {
+                        enter_invoke_span!(self.function_name());
+                        function($($Arg),*)
+                    };
+
+                    // Marshall the result
+                    result
+                        .marshal_value(runtime.clone())
+                        .map_err($crate::SignatureError::InvalidReturnValue)
+                }
+            }
         )+
     }
 }
 
+/// Invokes a function through a [`FnHandle`], which was obtained using [`Runtime::resolve`].
+///
+/// The first argument `invoke_handle` receives is a `Runtime` and the second argument is a
+/// mutable `FnHandle`. Additional parameters passed to `invoke_handle` are the arguments of the
+/// function in the order given.
+#[macro_export]
+macro_rules! invoke_handle {
+    ($Runtime:expr, $Handle:expr $(, $Arg:expr)* $(,)?) => {
+        $Handle.call(&$Runtime $(, $Arg)*)
+    };
+}
+
 /// Invokes a runtime function and returns a [`Result`] that implements the [`RetryResultExt`]
 /// trait.
 ///
@@ -229,3 +441,102 @@ macro_rules! invoke_fn {
         )
     };
 }
+
+/// Implements [`ArgumentReflection`] and [`ReturnTypeReflection`] for a field-less enum, which is
+/// marshalled as the integer value of its discriminant. The enum is passed to and returned from
+/// Mun as the integer type `$Repr`, e.g. `core::i64` for an `i64`.
+///
+/// All variants of the enum must be listed, and `$Repr` must be able to represent all of their
+/// discriminants - typically it is the enum's `#[repr]` type.
+///
+/// When Mun returns a value that is not the discriminant of any listed variant, marshalling fails
+/// with a [`MarshalError`].
+///
+/// ```
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// #[repr(i64)]
+/// enum Direction {
+///     North,
+///     East,
+///     South,
+///     West,
+/// }
+///
+/// mun_runtime::impl_enum_reflection!(Direction: i64 { North, East, South, West });
+/// ```
+///
+/// [`ArgumentReflection`]: trait.ArgumentReflection.html
+/// [`ReturnTypeReflection`]: trait.ReturnTypeReflection.html
+/// [`MarshalError`]: enum.MarshalError.html
+#[macro_export]
+macro_rules! impl_enum_reflection {
+    ($Enum:ident: $Repr:ty { $($Variant:ident),+ $(,)? }) => {
+        impl $crate::ArgumentReflection for $Enum {
+            type Marshalled = $Repr;
+
+            fn type_guid(&self, _runtime: &$crate::Runtime) -> $crate::abi::Guid {
+                <$Repr as $crate::ReturnTypeReflection>::type_guid()
+            }
+
+            fn type_name(&self, _runtime: &$crate::Runtime) -> &str {
+                <$Repr as $crate::ReturnTypeReflection>::type_name()
+            }
+
+            fn marshal(
+                self,
+                _runtime: &$crate::Runtime,
+                _type_info: &$crate::abi::TypeInfo,
+            ) -> Self::Marshalled {
+                self as $Repr
+            }
+        }
+
+        impl $crate::ReturnTypeReflection for $Enum {
+            type Marshalled = $Repr;
+
+            fn type_guid() -> $crate::abi::Guid {
+                <$Repr as $crate::ReturnTypeReflection>::type_guid()
+            }
+
+            fn type_name() -> &'static str {
+                <$Repr as $crate::ReturnTypeReflection>::type_name()
+            }
+        }
+
+        impl $crate::Marshal<$Enum> for $Repr {
+            fn marshal_value(
+                self,
+                _runtime: ::std::rc::Rc<::std::cell::RefCell<$crate::Runtime>>,
+            ) -> ::std::result::Result<$Enum, $crate::MarshalError> {
+                $(
+                    if self == $Enum::$Variant as $Repr {
+                        return ::std::result::Result::Ok($Enum::$Variant);
+                    }
+                )+
+                ::std::result::Result::Err($crate::MarshalError::InvalidDiscriminant {
+                    type_name: ::std::string::ToString::to_string(::std::stringify!($Enum)),
+                    discriminant: ::std::string::ToString::to_string(&self),
+                })
+            }
+
+            fn marshal_from_ptr(
+                ptr: ::std::ptr::NonNull<Self>,
+                runtime: ::std::rc::Rc<::std::cell::RefCell<$crate::Runtime>>,
+                _type_info: Option<&$crate::abi::TypeInfo>,
+            ) -> ::std::result::Result<$Enum, $crate::MarshalError> {
+                let value = unsafe { ptr.as_ptr().read() };
+                $crate::Marshal::<$Enum>::marshal_value(value, runtime)
+            }
+        }
+
+        impl $crate::MarshalArgument<$Enum> for $Repr {
+            fn marshal_to_ptr(
+                value: Self,
+                mut ptr: ::std::ptr::NonNull<Self>,
+                _type_info: Option<&$crate::abi::TypeInfo>,
+            ) {
+                unsafe { *ptr.as_mut() = value };
+            }
+        }
+    };
+}
@@ -1,42 +1,101 @@
 use crate::Runtime;
 use std::cell::RefCell;
+use std::fmt;
 use std::ptr::NonNull;
 use std::rc::Rc;
 
+/// An error that occurs when a value returned from Mun cannot be represented by the requested
+/// host type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MarshalError {
+    /// The discriminant of an enum-like value does not correspond to any of its variants
+    InvalidDiscriminant {
+        /// The name of the requested type
+        type_name: String,
+        /// The value of the discriminant
+        discriminant: String,
+    },
+    /// The fields of a struct do not match the fields of the requested type
+    LayoutMismatch {
+        /// The name of the requested type
+        expected: String,
+        /// The name of the struct's type
+        found: String,
+    },
+}
+
+impl fmt::Display for MarshalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarshalError::InvalidDiscriminant {
+                type_name,
+                discriminant,
+            } => write!(
+                f,
+                "Invalid discriminant `{}` of type `{}`.",
+                discriminant, type_name
+            ),
+            MarshalError::LayoutMismatch { expected, found } => write!(
+                f,
+                "Mismatched struct layout. Expected: `{}`. Found: `{}`.",
+                expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MarshalError {}
+
 /// Used to do value-to-value conversions that require runtime type information while consuming the
 /// input value.
 ///
+/// Marshalling a value from Mun fails if the value cannot be represented by `T`, e.g. if it is not
+/// a valid discriminant of an enum.
+///
 /// If no `TypeInfo` is provided, the type is `()`.
 pub trait Marshal<T>: Sized {
     /// Marshals itself into a `T`.
-    fn marshal_value(self, runtime: Rc<RefCell<Runtime>>) -> T;
+    fn marshal_value(self, runtime: Rc<RefCell<Runtime>>) -> Result<T, MarshalError>;
 
     /// Marshals the value at memory location `ptr` into a `T`.
     fn marshal_from_ptr(
         ptr: NonNull<Self>,
         runtime: Rc<RefCell<Runtime>>,
         type_info: Option<&abi::TypeInfo>,
-    ) -> T;
+    ) -> Result<T, MarshalError>;
+}
 
+/// Used to do value-to-value conversions from a `T` that is passed to Mun, e.g. as an argument or
+/// the value of a struct field.
+///
+/// Unlike [`Marshal`], this does not require that a `T` can be marshalled back from Mun, which
+/// allows types that borrow host data to be passed to Mun.
+///
+/// If no `TypeInfo` is provided, the type is `()`.
+///
+/// [`Marshal`]: trait.Marshal.html
+pub trait MarshalArgument<T>: Sized {
     /// Marshals `value` to memory location `ptr`.
     fn marshal_to_ptr(value: Self, ptr: NonNull<Self>, type_info: Option<&abi::TypeInfo>);
 }
 
 impl<T> Marshal<T> for T {
-    fn marshal_value(self, _runtime: Rc<RefCell<Runtime>>) -> T {
-        self
+    fn marshal_value(self, _runtime: Rc<RefCell<Runtime>>) -> Result<T, MarshalError> {
+        Ok(self)
     }
 
     fn marshal_from_ptr(
         ptr: NonNull<Self>,
         _runtime: Rc<RefCell<Runtime>>,
         _type_info: Option<&abi::TypeInfo>,
-    ) -> T {
+    ) -> Result<T, MarshalError> {
         // TODO: Avoid unsafe `read` fn by using adding `Clone` trait to T.
         // This also requires changes to the `impl Struct`
-        unsafe { ptr.as_ptr().read() }
+        Ok(unsafe { ptr.as_ptr().read() })
     }
+}
 
+impl<T> MarshalArgument<T> for T {
     fn marshal_to_ptr(value: T, mut ptr: NonNull<Self>, _type_info: Option<&abi::TypeInfo>) {
         unsafe { *ptr.as_mut() = value };
     }
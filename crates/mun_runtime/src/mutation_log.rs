@@ -0,0 +1,58 @@
+use std::{collections::VecDeque, time::Instant};
+
+/// A write to a struct field through a [`StructRef`], as recorded in the
+/// [`Runtime::mutation_log`].
+///
+/// [`StructRef`]: struct.StructRef.html
+/// [`Runtime::mutation_log`]: struct.Runtime.html#method.mutation_log
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mutation {
+    /// The name of the struct type
+    pub type_name: String,
+    /// The name of the field
+    pub field_name: String,
+    /// The memory of the field before it was written. For fields of garbage collected struct
+    /// types this is the address of the referenced struct.
+    pub old: Vec<u8>,
+    /// The memory of the field after it was written
+    pub new: Vec<u8>,
+    /// When the field was written
+    pub timestamp: Instant,
+}
+
+/// A ring buffer of the most recent `Mutation`s.
+pub(crate) struct MutationLog {
+    mutations: VecDeque<Mutation>,
+    capacity: usize,
+}
+
+impl MutationLog {
+    /// Constructs a `MutationLog` that holds at most `capacity` mutations.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            mutations: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Appends `mutation` to the log, evicting the oldest mutation if the log is full.
+    pub fn record(&mut self, mutation: Mutation) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.mutations.len() == self.capacity {
+            self.mutations.pop_front();
+        }
+        self.mutations.push_back(mutation);
+    }
+
+    /// Returns the logged mutations, from oldest to newest.
+    pub fn mutations(&self) -> impl Iterator<Item = &Mutation> {
+        self.mutations.iter()
+    }
+
+    /// Removes all logged mutations.
+    pub fn clear(&mut self) {
+        self.mutations.clear();
+    }
+}
@@ -1,5 +1,12 @@
-use crate::{marshal::Marshal, Runtime, StructRef};
+use crate::{
+    marshal::{Marshal, MarshalArgument, MarshalError},
+    Runtime, StructRef,
+};
 use abi::HasStaticTypeInfo;
+use std::{
+    any::Any, cell::RefCell, cmp::Ordering, convert::TryFrom, ffi::c_void, ptr::NonNull, rc::Rc,
+    time::Duration,
+};
 
 /// Returns whether the specified argument type matches the `type_info`.
 pub fn equals_argument_type<'e, 'f, T: ArgumentReflection>(
@@ -7,7 +14,7 @@ pub fn equals_argument_type<'e, 'f, T: ArgumentReflection>(
     type_info: &'e abi::TypeInfo,
     arg: &'f T,
 ) -> Result<(), (&'e str, &'f str)> {
-    if type_info.guid != arg.type_guid(runtime) {
+    if !arg.is_compatible(runtime, type_info) {
         Err((type_info.name(), arg.type_name(runtime)))
     } else {
         Ok(())
@@ -25,7 +32,7 @@ pub fn equals_return_type<T: ReturnTypeReflection>(
             }
         }
         abi::TypeGroup::StructTypes => {
-            if <StructRef as ReturnTypeReflection>::type_guid() != T::type_guid() {
+            if !T::accepts_struct(type_info) {
                 return Err(("struct", T::type_name()));
             }
         }
@@ -33,6 +40,23 @@ pub fn equals_return_type<T: ReturnTypeReflection>(
     Ok(())
 }
 
+/// Returns whether the specified return type matches the optional `type_info`, where `None`
+/// represents the `()` type.
+pub fn equals_optional_return_type<T: ReturnTypeReflection>(
+    type_info: Option<&abi::TypeInfo>,
+) -> Result<(), (&str, &str)> {
+    match type_info {
+        Some(type_info) => equals_return_type::<T>(type_info),
+        None => {
+            if <() as ReturnTypeReflection>::type_guid() != T::type_guid() {
+                Err((<() as ReturnTypeReflection>::type_name(), T::type_name()))
+            } else {
+                Ok(())
+            }
+        }
+    }
+}
+
 /// A type to emulate dynamic typing across compilation units for static types.
 pub trait ReturnTypeReflection: Sized {
     /// The resulting type after marshaling.
@@ -47,12 +71,19 @@ pub trait ReturnTypeReflection: Sized {
 
     /// Retrieves the type's name.
     fn type_name() -> &'static str;
+
+    /// Returns whether a value of the struct type `type_info` can be marshalled into this type.
+    ///
+    /// By default, only `StructRef` accepts struct types.
+    fn accepts_struct(_type_info: &abi::TypeInfo) -> bool {
+        Self::type_guid() == <StructRef as ReturnTypeReflection>::type_guid()
+    }
 }
 
 /// A type to emulate dynamic typing across compilation units for statically typed values.
 pub trait ArgumentReflection: Sized {
     /// The resulting type after dereferencing.
-    type Marshalled: Marshal<Self>;
+    type Marshalled: MarshalArgument<Self>;
 
     /// Retrieves the `Guid` of the value's type.
     fn type_guid(&self, runtime: &Runtime) -> abi::Guid;
@@ -60,8 +91,16 @@ pub trait ArgumentReflection: Sized {
     /// Retrieves the name of the value's type.
     fn type_name<'r>(&'r self, runtime: &'r Runtime) -> &'r str;
 
-    /// Marshals the value.
-    fn marshal(self) -> Self::Marshalled;
+    /// Marshals the value to a value of type `type_info`, allocating any memory it requires in
+    /// `runtime`.
+    fn marshal(self, runtime: &Runtime, type_info: &abi::TypeInfo) -> Self::Marshalled;
+
+    /// Returns whether the value can be marshalled to a value of type `type_info`.
+    ///
+    /// By default, this compares the `Guid` of the value's type to that of `type_info`.
+    fn is_compatible(&self, runtime: &Runtime, type_info: &abi::TypeInfo) -> bool {
+        self.type_guid(runtime) == type_info.guid
+    }
 }
 
 macro_rules! impl_primitive_type {
@@ -78,7 +117,7 @@ macro_rules! impl_primitive_type {
                     Self::type_info().name()
                 }
 
-                fn marshal(self) -> Self::Marshalled {
+                fn marshal(self, _runtime: &Runtime, _type_info: &abi::TypeInfo) -> Self::Marshalled {
                     self
                 }
             }
@@ -110,6 +149,202 @@ impl ReturnTypeReflection for () {
     }
 }
 
+/// A `Duration` is marshalled as a `u64` number of nanoseconds. Durations longer than `u64::MAX`
+/// nanoseconds (roughly 584 years) saturate to `u64::MAX` nanoseconds.
+impl ArgumentReflection for Duration {
+    type Marshalled = u64;
+
+    fn type_guid(&self, _runtime: &Runtime) -> abi::Guid {
+        <u64 as ReturnTypeReflection>::type_guid()
+    }
+
+    fn type_name(&self, _runtime: &Runtime) -> &str {
+        <u64 as ReturnTypeReflection>::type_name()
+    }
+
+    fn marshal(self, _runtime: &Runtime, _type_info: &abi::TypeInfo) -> Self::Marshalled {
+        u64::try_from(self.as_nanos()).unwrap_or(u64::MAX)
+    }
+}
+
+/// A `Duration` is marshalled from a `u64` number of nanoseconds.
+impl ReturnTypeReflection for Duration {
+    type Marshalled = u64;
+
+    fn type_guid() -> abi::Guid {
+        <u64 as ReturnTypeReflection>::type_guid()
+    }
+
+    fn type_name() -> &'static str {
+        <u64 as ReturnTypeReflection>::type_name()
+    }
+}
+
+impl Marshal<Duration> for u64 {
+    fn marshal_value(self, _runtime: Rc<RefCell<Runtime>>) -> Result<Duration, MarshalError> {
+        Ok(Duration::from_nanos(self))
+    }
+
+    fn marshal_from_ptr(
+        ptr: NonNull<Self>,
+        _runtime: Rc<RefCell<Runtime>>,
+        _type_info: Option<&abi::TypeInfo>,
+    ) -> Result<Duration, MarshalError> {
+        Ok(Duration::from_nanos(unsafe { ptr.as_ptr().read() }))
+    }
+}
+
+impl MarshalArgument<Duration> for u64 {
+    fn marshal_to_ptr(value: Self, mut ptr: NonNull<Self>, _type_info: Option<&abi::TypeInfo>) {
+        unsafe { *ptr.as_mut() = value };
+    }
+}
+
+/// An `Ordering` is marshalled as an `i8` that is `-1`, `0`, or `1` for `Less`, `Equal`, and
+/// `Greater`, respectively.
+impl ArgumentReflection for Ordering {
+    type Marshalled = i8;
+
+    fn type_guid(&self, _runtime: &Runtime) -> abi::Guid {
+        <i8 as ReturnTypeReflection>::type_guid()
+    }
+
+    fn type_name(&self, _runtime: &Runtime) -> &str {
+        <i8 as ReturnTypeReflection>::type_name()
+    }
+
+    fn marshal(self, _runtime: &Runtime, _type_info: &abi::TypeInfo) -> Self::Marshalled {
+        self as i8
+    }
+}
+
+/// An `Ordering` is marshalled from an `i8` that is `-1`, `0`, or `1`. Marshalling fails for any
+/// other value.
+impl ReturnTypeReflection for Ordering {
+    type Marshalled = i8;
+
+    fn type_guid() -> abi::Guid {
+        <i8 as ReturnTypeReflection>::type_guid()
+    }
+
+    fn type_name() -> &'static str {
+        <i8 as ReturnTypeReflection>::type_name()
+    }
+}
+
+/// Converts the `i8` value of an ordering into an `Ordering`.
+fn ordering_from_i8(value: i8) -> Result<Ordering, MarshalError> {
+    match value {
+        -1 => Ok(Ordering::Less),
+        0 => Ok(Ordering::Equal),
+        1 => Ok(Ordering::Greater),
+        _ => Err(MarshalError::InvalidDiscriminant {
+            type_name: "core::cmp::Ordering".to_string(),
+            discriminant: value.to_string(),
+        }),
+    }
+}
+
+impl Marshal<Ordering> for i8 {
+    fn marshal_value(self, _runtime: Rc<RefCell<Runtime>>) -> Result<Ordering, MarshalError> {
+        ordering_from_i8(self)
+    }
+
+    fn marshal_from_ptr(
+        ptr: NonNull<Self>,
+        _runtime: Rc<RefCell<Runtime>>,
+        _type_info: Option<&abi::TypeInfo>,
+    ) -> Result<Ordering, MarshalError> {
+        ordering_from_i8(unsafe { ptr.as_ptr().read() })
+    }
+}
+
+impl MarshalArgument<Ordering> for i8 {
+    fn marshal_to_ptr(value: Self, mut ptr: NonNull<Self>, _type_info: Option<&abi::TypeInfo>) {
+        unsafe { *ptr.as_mut() = value };
+    }
+}
+
+/// A type-erased pointer that is passed to and returned from Mun as an opaque `core::usize`
+/// address, e.g. a handle to an engine entity.
+///
+/// Mun has no pointer types, so a Mun function receives an `OpaquePtr` as a `usize`. Mun code
+/// must treat the value as opaque: it may only store it and pass it back to the host. As Mun
+/// cannot dereference the address, the pointer is never accessed on the Mun side, and the host
+/// remains responsible for the validity of the memory it points to.
+///
+/// Unlike the typed pointers `*const T` and `*mut T`, whose types are distinct ABI types, an
+/// `OpaquePtr` is indistinguishable from any other `usize` to Mun.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OpaquePtr(pub *mut c_void);
+
+impl OpaquePtr {
+    /// Returns the wrapped pointer.
+    pub fn as_ptr(self) -> *mut c_void {
+        self.0
+    }
+}
+
+impl From<*mut c_void> for OpaquePtr {
+    fn from(ptr: *mut c_void) -> Self {
+        Self(ptr)
+    }
+}
+
+impl From<*const c_void> for OpaquePtr {
+    fn from(ptr: *const c_void) -> Self {
+        Self(ptr as *mut c_void)
+    }
+}
+
+impl ArgumentReflection for OpaquePtr {
+    type Marshalled = usize;
+
+    fn type_guid(&self, _runtime: &Runtime) -> abi::Guid {
+        <usize as ReturnTypeReflection>::type_guid()
+    }
+
+    fn type_name(&self, _runtime: &Runtime) -> &str {
+        <usize as ReturnTypeReflection>::type_name()
+    }
+
+    fn marshal(self, _runtime: &Runtime, _type_info: &abi::TypeInfo) -> Self::Marshalled {
+        self.0 as usize
+    }
+}
+
+impl ReturnTypeReflection for OpaquePtr {
+    type Marshalled = usize;
+
+    fn type_guid() -> abi::Guid {
+        <usize as ReturnTypeReflection>::type_guid()
+    }
+
+    fn type_name() -> &'static str {
+        <usize as ReturnTypeReflection>::type_name()
+    }
+}
+
+impl Marshal<OpaquePtr> for usize {
+    fn marshal_value(self, _runtime: Rc<RefCell<Runtime>>) -> Result<OpaquePtr, MarshalError> {
+        Ok(OpaquePtr(self as *mut c_void))
+    }
+
+    fn marshal_from_ptr(
+        ptr: NonNull<Self>,
+        _runtime: Rc<RefCell<Runtime>>,
+        _type_info: Option<&abi::TypeInfo>,
+    ) -> Result<OpaquePtr, MarshalError> {
+        Ok(OpaquePtr(unsafe { ptr.as_ptr().read() } as *mut c_void))
+    }
+}
+
+impl MarshalArgument<OpaquePtr> for usize {
+    fn marshal_to_ptr(value: Self, mut ptr: NonNull<Self>, _type_info: Option<&abi::TypeInfo>) {
+        unsafe { *ptr.as_mut() = value };
+    }
+}
+
 impl<T> ArgumentReflection for *const T
 where
     *const T: HasStaticTypeInfo,
@@ -124,7 +359,7 @@ where
         Self::type_info().name()
     }
 
-    fn marshal(self) -> Self::Marshalled {
+    fn marshal(self, _runtime: &Runtime, _type_info: &abi::TypeInfo) -> Self::Marshalled {
         self
     }
 }
@@ -158,7 +393,7 @@ where
         Self::type_info().name()
     }
 
-    fn marshal(self) -> Self::Marshalled {
+    fn marshal(self, _runtime: &Runtime, _type_info: &abi::TypeInfo) -> Self::Marshalled {
         self
     }
 }
@@ -177,3 +412,192 @@ where
         Self::type_info().name()
     }
 }
+
+impl<T> ArgumentReflection for NonNull<T>
+where
+    NonNull<T>: HasStaticTypeInfo,
+{
+    type Marshalled = Self;
+
+    fn type_guid(&self, _runtime: &Runtime) -> abi::Guid {
+        Self::type_info().guid
+    }
+
+    fn type_name(&self, _runtime: &Runtime) -> &str {
+        Self::type_info().name()
+    }
+
+    fn marshal(self, _runtime: &Runtime, _type_info: &abi::TypeInfo) -> Self::Marshalled {
+        self
+    }
+}
+
+impl<'a, T> ArgumentReflection for &'a T
+where
+    &'a T: HasStaticTypeInfo,
+{
+    type Marshalled = Self;
+
+    fn type_guid(&self, _runtime: &Runtime) -> abi::Guid {
+        Self::type_info().guid
+    }
+
+    fn type_name(&self, _runtime: &Runtime) -> &str {
+        Self::type_info().name()
+    }
+
+    fn marshal(self, _runtime: &Runtime, _type_info: &abi::TypeInfo) -> Self::Marshalled {
+        self
+    }
+}
+
+/// Marshals the argument `value` to memory location `ptr`.
+///
+/// # Safety
+///
+/// `ptr` must point to memory that is valid for a value of type `type_info`, which must match the
+/// type of the value.
+pub(crate) unsafe fn marshal_argument_to_ptr<T: ArgumentReflection>(
+    value: T,
+    ptr: NonNull<u8>,
+    runtime: &Runtime,
+    type_info: &abi::TypeInfo,
+) {
+    MarshalArgument::marshal_to_ptr(
+        value.marshal(runtime, type_info),
+        ptr.cast::<T::Marshalled>(),
+        Some(type_info),
+    )
+}
+
+/// An object-safe counterpart of `ArgumentReflection`, which is implemented for all argument
+/// types and for `DynValue`.
+pub(crate) trait ErasedArgument {
+    fn type_guid(&self, runtime: &Runtime) -> abi::Guid;
+    fn type_name<'r>(&'r self, runtime: &'r Runtime) -> &'r str;
+    unsafe fn marshal_to_ptr(
+        self: Box<Self>,
+        ptr: NonNull<u8>,
+        runtime: &Runtime,
+        type_info: &abi::TypeInfo,
+    );
+}
+
+impl<T: ArgumentReflection> ErasedArgument for T {
+    fn type_guid(&self, runtime: &Runtime) -> abi::Guid {
+        ArgumentReflection::type_guid(self, runtime)
+    }
+
+    fn type_name<'r>(&'r self, runtime: &'r Runtime) -> &'r str {
+        ArgumentReflection::type_name(self, runtime)
+    }
+
+    unsafe fn marshal_to_ptr(
+        self: Box<Self>,
+        ptr: NonNull<u8>,
+        runtime: &Runtime,
+        type_info: &abi::TypeInfo,
+    ) {
+        marshal_argument_to_ptr(*self, ptr, runtime, type_info)
+    }
+}
+
+/// A type-erased argument value, which allows values of different types to be stored in the same
+/// collection.
+///
+/// Unlike a [`DynValue`], which is limited to fundamental types and structs, a `DynArgument` can
+/// hold a value of any argument type. A `DynValue` can be converted into a `DynArgument`.
+///
+/// [`DynValue`]: enum.DynValue.html
+pub struct DynArgument(pub(crate) Box<dyn ErasedArgument>);
+
+impl DynArgument {
+    /// Erases the type of the argument `value`.
+    pub fn new<T: ArgumentReflection + 'static>(value: T) -> Self {
+        DynArgument(Box::new(value))
+    }
+
+    /// Retrieves the `Guid` of the value's type.
+    pub fn type_guid(&self, runtime: &Runtime) -> abi::Guid {
+        self.0.type_guid(runtime)
+    }
+
+    /// Retrieves the name of the value's type.
+    pub fn type_name<'r>(&'r self, runtime: &'r Runtime) -> &'r str {
+        self.0.type_name(runtime)
+    }
+
+    /// Marshals the value to memory location `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to memory that is valid for a value of type `type_info`, which must match
+    /// the type of the value.
+    pub unsafe fn marshal_to_ptr(
+        self,
+        ptr: NonNull<u8>,
+        runtime: &Runtime,
+        type_info: &abi::TypeInfo,
+    ) {
+        self.0.marshal_to_ptr(ptr, runtime, type_info)
+    }
+}
+
+/// A type-erased return type, which carries the type's `Guid`, name, and a function to marshal a
+/// value of the type.
+#[derive(Clone, Copy)]
+pub struct DynReturnType {
+    type_guid: abi::Guid,
+    type_name: &'static str,
+    marshal_from_ptr: unsafe fn(
+        NonNull<u8>,
+        Rc<RefCell<Runtime>>,
+        Option<&abi::TypeInfo>,
+    ) -> Result<Box<dyn Any>, MarshalError>,
+}
+
+impl DynReturnType {
+    /// Erases the return type `T`.
+    pub fn of<T: ReturnTypeReflection + 'static>() -> Self {
+        unsafe fn marshal_from_ptr<T: ReturnTypeReflection + 'static>(
+            ptr: NonNull<u8>,
+            runtime: Rc<RefCell<Runtime>>,
+            type_info: Option<&abi::TypeInfo>,
+        ) -> Result<Box<dyn Any>, MarshalError> {
+            let value: T =
+                Marshal::marshal_from_ptr(ptr.cast::<T::Marshalled>(), runtime, type_info)?;
+            Ok(Box::new(value))
+        }
+
+        DynReturnType {
+            type_guid: T::type_guid(),
+            type_name: T::type_name(),
+            marshal_from_ptr: marshal_from_ptr::<T>,
+        }
+    }
+
+    /// Retrieves the type's `Guid`.
+    pub fn type_guid(&self) -> abi::Guid {
+        self.type_guid
+    }
+
+    /// Retrieves the type's name.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Marshals the value at memory location `ptr` into a boxed value of the erased type, or
+    /// returns an error if the value cannot be represented by the erased type.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid value of type `type_info`, which must match the erased type.
+    pub unsafe fn marshal_from_ptr(
+        &self,
+        ptr: NonNull<u8>,
+        runtime: Rc<RefCell<Runtime>>,
+        type_info: Option<&abi::TypeInfo>,
+    ) -> Result<Box<dyn Any>, MarshalError> {
+        (self.marshal_from_ptr)(ptr, runtime, type_info)
+    }
+}
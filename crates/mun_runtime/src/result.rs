@@ -0,0 +1,151 @@
+use crate::{
+    marshal::{Marshal, MarshalError},
+    reflection::{equals_return_type, ReturnTypeReflection},
+    struct_ref::RawStruct,
+    tuple::{struct_data_ptr, tuple_fields},
+    Runtime,
+};
+use memory::gc::GcRuntime;
+use std::{cell::RefCell, ptr::NonNull, rc::Rc};
+
+/// The name of the type that results report to the reflection system.
+const RESULT_TYPE_NAME: &str = "result";
+
+/// The discriminant of a result that contains a success value.
+pub const MUN_RESULT_OK: u8 = abi::RESULT_TAG_OK;
+
+/// The discriminant of a result that contains an error value.
+pub const MUN_RESULT_ERR: u8 = abi::RESULT_TAG_ERR;
+
+/// A success or error value returned from Mun.
+///
+/// Until Mun supports enums, a result is represented by a Mun struct with the layout of the ABI's
+/// result type, as described by [`abi::TypeInfo::is_result`]: a `u8` field `tag`, which is either
+/// [`MUN_RESULT_OK`] or [`MUN_RESULT_ERR`], followed by the success payload `ok` of type `T`, and
+/// the error payload `err` of type `E`. For example:
+///
+/// ```mun
+/// struct ParseResult { tag: u8, ok: i32, err: u8 }
+/// ```
+///
+/// Only the payload selected by the tag is marshalled; the other payload must still be
+/// initialized, but its value is ignored.
+///
+/// [`abi::TypeInfo::is_result`]: ../mun_abi/struct.TypeInfo.html#method.is_result
+/// [`MUN_RESULT_OK`]: constant.MUN_RESULT_OK.html
+/// [`MUN_RESULT_ERR`]: constant.MUN_RESULT_ERR.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MunResult<T, E> {
+    /// Contains the success value
+    Ok(T),
+    /// Contains the error value
+    Err(E),
+}
+
+impl<T, E> MunResult<T, E> {
+    /// Converts the `MunResult` into a `Result`.
+    pub fn into_result(self) -> Result<T, E> {
+        self.into()
+    }
+}
+
+impl<T, E> From<MunResult<T, E>> for Result<T, E> {
+    fn from(result: MunResult<T, E>) -> Self {
+        match result {
+            MunResult::Ok(value) => Ok(value),
+            MunResult::Err(error) => Err(error),
+        }
+    }
+}
+
+impl<T: ReturnTypeReflection, E: ReturnTypeReflection> ReturnTypeReflection for MunResult<T, E> {
+    type Marshalled = RawStruct;
+
+    fn type_name() -> &'static str {
+        RESULT_TYPE_NAME
+    }
+
+    fn accepts_struct(type_info: &abi::TypeInfo) -> bool {
+        match tuple_fields(type_info, 3) {
+            Some((field_types, _)) if type_info.is_result() => {
+                equals_return_type::<u8>(field_types[0]).is_ok()
+                    && equals_return_type::<T>(field_types[1]).is_ok()
+                    && equals_return_type::<E>(field_types[2]).is_ok()
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<T: ReturnTypeReflection, E: ReturnTypeReflection> MunResult<T, E> {
+    /// Reads the result from the memory of a struct of type `type_info`, which starts at `ptr`.
+    /// Returns an error if the fields of the struct do not match those of the result, or if the
+    /// discriminant is neither `MUN_RESULT_OK` nor `MUN_RESULT_ERR`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to the memory of a struct of type `type_info`.
+    unsafe fn from_struct_data(
+        ptr: *const u8,
+        runtime: Rc<RefCell<Runtime>>,
+        type_info: &abi::TypeInfo,
+    ) -> Result<Self, MarshalError> {
+        let (field_types, field_offsets) = match tuple_fields(type_info, 3) {
+            Some(fields) if Self::accepts_struct(type_info) => fields,
+            _ => {
+                return Err(MarshalError::LayoutMismatch {
+                    expected: format!(
+                        "{{ tag: {}, ok: {}, err: {} }}",
+                        <u8 as ReturnTypeReflection>::type_name(),
+                        T::type_name(),
+                        E::type_name()
+                    ),
+                    found: type_info.name().to_string(),
+                })
+            }
+        };
+        let field_ptr = |idx: usize| {
+            NonNull::new_unchecked(ptr.add(usize::from(field_offsets[idx])) as *mut u8)
+        };
+
+        match field_ptr(0).as_ptr().read() {
+            MUN_RESULT_OK => Marshal::marshal_from_ptr(
+                field_ptr(1).cast::<T::Marshalled>(),
+                runtime,
+                Some(field_types[1]),
+            )
+            .map(MunResult::Ok),
+            MUN_RESULT_ERR => Marshal::marshal_from_ptr(
+                field_ptr(2).cast::<E::Marshalled>(),
+                runtime,
+                Some(field_types[2]),
+            )
+            .map(MunResult::Err),
+            discriminant => Err(MarshalError::InvalidDiscriminant {
+                type_name: type_info.name().to_string(),
+                discriminant: discriminant.to_string(),
+            }),
+        }
+    }
+}
+
+impl<T: ReturnTypeReflection, E: ReturnTypeReflection> Marshal<MunResult<T, E>> for RawStruct {
+    fn marshal_value(self, runtime: Rc<RefCell<Runtime>>) -> Result<MunResult<T, E>, MarshalError> {
+        // Safety: The type returned from `ptr_type` is guaranteed to live at least as long as
+        // `Runtime` does not change, which it does not during marshalling.
+        let type_info = unsafe { &*runtime.borrow().gc().ptr_type(self.0).into_inner().as_ptr() };
+
+        // A returned struct is always passed as a `GcPtr`, regardless of its memory kind
+        unsafe { MunResult::from_struct_data(self.get_ptr(), runtime, type_info) }
+    }
+
+    fn marshal_from_ptr(
+        ptr: NonNull<Self>,
+        runtime: Rc<RefCell<Runtime>>,
+        type_info: Option<&abi::TypeInfo>,
+    ) -> Result<MunResult<T, E>, MarshalError> {
+        // `type_info` is only `None` for the `()` type
+        let type_info = type_info.unwrap();
+        unsafe { MunResult::from_struct_data(struct_data_ptr(ptr, type_info), runtime, type_info) }
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{reflection::ReturnTypeReflection, sync_struct_ref::SyncState, DispatchTable};
+use parking_lot::RwLockReadGuard;
+use rustc_hash::FxHashMap;
+use std::{ffi::c_void, sync::Arc};
+
+/// A type in the signature of a function in a [`RuntimeSnapshot`].
+struct SnapshotType {
+    guid: abi::Guid,
+    name: String,
+}
+
+impl SnapshotType {
+    fn new(type_info: &abi::TypeInfo) -> Self {
+        Self {
+            guid: type_info.guid,
+            name: type_info.name().to_string(),
+        }
+    }
+
+    fn empty() -> Self {
+        Self {
+            guid: <() as ReturnTypeReflection>::type_guid(),
+            name: <() as ReturnTypeReflection>::type_name().to_string(),
+        }
+    }
+}
+
+/// A function in a [`RuntimeSnapshot`].
+struct SnapshotFunction {
+    fn_ptr: *const c_void,
+    arg_types: Vec<SnapshotType>,
+    return_type: SnapshotType,
+}
+
+/// An immutable, point-in-time snapshot of the functions of a [`Runtime`] that is `Send + Sync`,
+/// which allows invoking Mun functions from worker threads.
+///
+/// Created by [`Runtime::clone_for_thread`]. Only functions whose arguments and return type are
+/// all fundamental types are included in the snapshot.
+///
+/// # Restrictions
+///
+/// * Functions invoked through a snapshot must be pure: they must not allocate memory, or read or
+///   write memory of garbage collected structs. This is not validated.
+/// * Invocations block while the runtime collects garbage, closes an allocation scope, reloads an
+///   assembly, or sets fields through a [`StructRef`].
+/// * When the runtime loads, unloads, or reloads an assembly, or is dropped, the snapshot becomes
+///   stale and all invocations fail. A stale snapshot must be discarded and a new snapshot
+///   created.
+///
+/// [`Runtime`]: struct.Runtime.html
+/// [`Runtime::clone_for_thread`]: struct.Runtime.html#method.clone_for_thread
+/// [`StructRef`]: struct.StructRef.html
+pub struct RuntimeSnapshot {
+    functions: FxHashMap<String, SnapshotFunction>,
+    generation: usize,
+    state: Arc<SyncState>,
+}
+
+// Safety: The function pointers of a snapshot are only invoked while holding a read lock of the
+// runtime's synchronization state, for as long as the snapshot is not stale. The runtime holds
+// the write lock while it unloads the assemblies that contain the functions.
+unsafe impl Send for RuntimeSnapshot {}
+unsafe impl Sync for RuntimeSnapshot {}
+
+impl RuntimeSnapshot {
+    /// Creates a snapshot of the functions in `dispatch_table`, in the current generation of the
+    /// runtime.
+    pub(crate) fn new(dispatch_table: &DispatchTable, state: Arc<SyncState>) -> Self {
+        let functions = dispatch_table
+            .functions
+            .iter()
+            .filter_map(|(name, function_info)| {
+                let signature = &function_info.prototype.signature;
+                let arg_types = signature.arg_types();
+                let is_fundamental = arg_types
+                    .iter()
+                    .chain(signature.return_type().iter())
+                    .all(|type_info| type_info.group.is_fundamental());
+                if !is_fundamental {
+                    return None;
+                }
+
+                let function = SnapshotFunction {
+                    fn_ptr: function_info.fn_ptr,
+                    arg_types: arg_types
+                        .iter()
+                        .map(|type_info| SnapshotType::new(type_info))
+                        .collect(),
+                    return_type: signature
+                        .return_type()
+                        .map_or_else(SnapshotType::empty, SnapshotType::new),
+                };
+                Some((name.clone(), function))
+            })
+            .collect();
+
+        Self {
+            functions,
+            generation: state.generation(),
+            state,
+        }
+    }
+
+    /// Returns whether the runtime has loaded, unloaded, or reloaded an assembly since the
+    /// snapshot was created.
+    pub fn is_stale(&self) -> bool {
+        self.generation != self.state.generation()
+    }
+
+    /// Returns whether the snapshot contains the function `function_name`.
+    pub fn contains_fn(&self, function_name: &str) -> bool {
+        self.functions.contains_key(function_name)
+    }
+
+    /// Looks up the function `function_name` and validates that its signature matches the
+    /// `(guid, name)` pairs of `arg_types` and `Output`. On success, returns the function pointer
+    /// together with the read lock that must be held while invoking it.
+    pub(crate) fn resolve<Output: ReturnTypeReflection>(
+        &self,
+        function_name: &str,
+        arg_types: &[(abi::Guid, &str)],
+    ) -> Result<(RwLockReadGuard<'_, ()>, *const c_void), String> {
+        let guard = self.state.read();
+        if self.is_stale() {
+            return Err(
+                "The snapshot is stale, as the runtime has loaded, unloaded, or reloaded an assembly."
+                    .to_string(),
+            );
+        }
+
+        let function = self.functions.get(function_name).ok_or_else(|| {
+            format!(
+                "Failed to obtain function '{}', or its signature is not fundamental",
+                function_name
+            )
+        })?;
+
+        if function.arg_types.len() != arg_types.len() {
+            return Err(format!(
+                "Invalid number of arguments. Expected: {}. Found: {}.",
+                function.arg_types.len(),
+                arg_types.len()
+            ));
+        }
+        for (index, (expected, (guid, name))) in
+            function.arg_types.iter().zip(arg_types).enumerate()
+        {
+            if expected.guid != *guid {
+                return Err(format!(
+                    "Invalid argument type at index {}. Expected: {}. Found: {}.",
+                    index, expected.name, name
+                ));
+            }
+        }
+
+        if function.return_type.guid != Output::type_guid() {
+            return Err(format!(
+                "Invalid return type. Expected: {}. Found: {}",
+                function.return_type.name,
+                Output::type_name()
+            ));
+        }
+
+        Ok((guard, function.fn_ptr))
+    }
+}
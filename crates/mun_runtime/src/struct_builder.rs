@@ -0,0 +1,210 @@
+use crate::garbage_collector::UnsafeTypeInfo;
+use crate::{
+    dyn_value::DynValue,
+    struct_ref::{RawStruct, StructRef},
+    Runtime,
+};
+use memory::gc::{GcRuntime, HasIndirectionPtr};
+use std::{
+    cell::RefCell,
+    ptr::{self, NonNull},
+    rc::Rc,
+};
+
+/// Constructs a Mun struct field by field, while tracking which fields have been set.
+///
+/// Finishing the struct fails if any field has not been set, unless the field has a default
+/// value, in which case it is initialized with its default value. As such, a struct can never be
+/// constructed with a field whose value is accidentally left uninitialized.
+pub struct StructBuilder {
+    runtime: Rc<RefCell<Runtime>>,
+    type_name: String,
+    type_guid: abi::Guid,
+    values: Vec<Option<DynValue>>,
+}
+
+impl StructBuilder {
+    /// Starts constructing a struct of the struct type called `type_name`, without any of its
+    /// fields set.
+    pub fn new(runtime: Rc<RefCell<Runtime>>, type_name: &str) -> Result<Self, String> {
+        let (type_guid, num_fields) = {
+            let runtime_ref = runtime.borrow();
+            let type_info = runtime_ref
+                .find_struct_type(type_name)
+                .ok_or_else(|| format!("Struct type `{}` does not exist.", type_name))?;
+
+            // Safety: `find_struct_type` only returns struct types.
+            let struct_info = type_info.as_struct().unwrap();
+            (type_info.guid, struct_info.field_types().len())
+        };
+
+        Ok(Self {
+            runtime,
+            type_name: type_name.to_string(),
+            type_guid,
+            values: vec![None; num_fields],
+        })
+    }
+
+    /// Sets the value of the field corresponding to the specified `field_name`. Setting a field
+    /// that was already set replaces its value.
+    pub fn set<T: Into<DynValue>>(mut self, field_name: &str, value: T) -> Result<Self, String> {
+        let value = value.into();
+        {
+            let runtime_ref = self.runtime.borrow();
+            let type_info = self.type_info(&runtime_ref)?;
+
+            // Safety: `find_struct_type` only returns struct types.
+            let struct_info = type_info.as_struct().unwrap();
+            let field_idx =
+                abi::StructInfo::find_field_index(type_info.name(), struct_info, field_name)?;
+
+            // Safety: If we found the `field_idx`, we are guaranteed to also have the `field_type`.
+            let field_type = unsafe { struct_info.field_types().get_unchecked(field_idx) };
+            if field_type.guid != value.type_guid(&runtime_ref) {
+                return Err(format!(
+                    "Mismatched types for `{}::{}`. Expected: `{}`. Found: `{}`.",
+                    type_info.name(),
+                    field_name,
+                    field_type.name(),
+                    value.type_name(&runtime_ref),
+                ));
+            }
+
+            self.values[field_idx] = Some(value);
+        }
+        Ok(self)
+    }
+
+    /// Sets the value of the struct field corresponding to the specified `field_name` to a new
+    /// struct, which is constructed by `build` using a builder for the field's struct type. This
+    /// allows constructing a tree of nested structs in a single expression, regardless of the
+    /// memory kinds of its structs.
+    pub fn set_nested<F>(self, field_name: &str, build: F) -> Result<Self, String>
+    where
+        F: FnOnce(StructBuilder) -> Result<StructBuilder, String>,
+    {
+        let field_type_name = {
+            let runtime_ref = self.runtime.borrow();
+            let type_info = self.type_info(&runtime_ref)?;
+
+            // Safety: `find_struct_type` only returns struct types.
+            let struct_info = type_info.as_struct().unwrap();
+            let field_idx =
+                abi::StructInfo::find_field_index(type_info.name(), struct_info, field_name)?;
+
+            // Safety: If we found the `field_idx`, we are guaranteed to also have the `field_type`.
+            let field_type = unsafe { struct_info.field_types().get_unchecked(field_idx) };
+            if field_type.as_struct().is_none() {
+                return Err(format!(
+                    "Mismatched types for `{}::{}`. Expected: `struct`. Found: `{}`.",
+                    type_info.name(),
+                    field_name,
+                    field_type.name(),
+                ));
+            }
+            field_type.name().to_string()
+        };
+
+        let value = build(StructBuilder::new(self.runtime.clone(), &field_type_name)?)?.finish()?;
+        self.set(field_name, value)
+    }
+
+    /// Returns the names of the fields that have neither been set, nor have a default value.
+    pub fn unset_fields(&self) -> Vec<String> {
+        let runtime_ref = self.runtime.borrow();
+        self.type_info(&runtime_ref)
+            .map(|type_info| unset_fields(type_info, &self.values))
+            .unwrap_or_default()
+    }
+
+    /// Constructs the struct. Fields that have not been set are initialized with their default
+    /// value.
+    ///
+    /// Returns an error if any field has neither been set, nor has a default value.
+    pub fn finish(self) -> Result<StructRef, String> {
+        let raw = {
+            let runtime_ref = self.runtime.borrow();
+            let type_info = self.type_info(&runtime_ref)?;
+            let unset = unset_fields(type_info, &self.values);
+            if !unset.is_empty() {
+                return Err(format!(
+                    "Missing fields for `{}`: `{}`.",
+                    type_info.name(),
+                    unset.join("`, `")
+                ));
+            }
+
+            let mut handle = runtime_ref
+                .gc()
+                .alloc(UnsafeTypeInfo::new(NonNull::from(type_info)));
+            let ptr = unsafe { handle.deref_mut::<u8>() };
+
+            // Safety: `find_struct_type` only returns struct types.
+            let struct_info = type_info.as_struct().unwrap();
+            unsafe { ptr::write_bytes(ptr, 0, type_info.size_in_bytes()) };
+            for (field_idx, ((field_type, offset), value)) in struct_info
+                .field_types()
+                .iter()
+                .zip(struct_info.field_offsets().iter())
+                .zip(self.values.into_iter())
+                .enumerate()
+            {
+                // Safety: The offset of every field lies within the struct's memory, and the type
+                // of every value was validated to match the field's type.
+                unsafe {
+                    let field_ptr = ptr.add(usize::from(*offset));
+                    match value {
+                        Some(value) => value.marshal_to_ptr(
+                            NonNull::new_unchecked(field_ptr),
+                            &runtime_ref,
+                            field_type,
+                        ),
+                        None => {
+                            // Fields that are not set are guaranteed to have a default value
+                            let default = struct_info.field_default(field_idx).unwrap();
+                            ptr::copy_nonoverlapping(
+                                default,
+                                field_ptr,
+                                field_type.size_in_bytes(),
+                            );
+                        }
+                    }
+                }
+            }
+            RawStruct(handle)
+        };
+
+        Ok(StructRef::new(self.runtime, raw))
+    }
+
+    /// Retrieves the type information of the struct that is being constructed, or an error if
+    /// the struct type has been changed or unloaded since the builder was created.
+    fn type_info<'r>(&self, runtime_ref: &'r Runtime) -> Result<&'r abi::TypeInfo, String> {
+        runtime_ref
+            .find_struct_type(&self.type_name)
+            .filter(|type_info| type_info.guid == self.type_guid)
+            .ok_or_else(|| {
+                format!(
+                    "Struct type `{}` has been changed or unloaded since construction started.",
+                    self.type_name
+                )
+            })
+    }
+}
+
+/// Returns the names of the fields of struct type `type_info` whose `values` are not set, and that
+/// do not have a default value.
+fn unset_fields(type_info: &abi::TypeInfo, values: &[Option<DynValue>]) -> Vec<String> {
+    // Safety: `unset_fields` is only called for struct types.
+    let struct_info = type_info.as_struct().unwrap();
+    struct_info
+        .field_names()
+        .zip(values.iter())
+        .enumerate()
+        .filter(|(field_idx, (_, value))| {
+            value.is_none() && struct_info.field_default(*field_idx).is_none()
+        })
+        .map(|(_, (name, _))| name.to_string())
+        .collect()
+}
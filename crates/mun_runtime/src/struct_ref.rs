@@ -1,30 +1,119 @@
 use crate::garbage_collector::{GcPtr, GcRootPtr, UnsafeTypeInfo};
 use crate::{
-    marshal::Marshal,
+    dyn_value::DynValue,
+    field_observer::FieldChangedCallback,
+    handle::OwnedHandle,
+    json::{JsonWriter, NonFinitePolicy},
+    marshal::{Marshal, MarshalArgument, MarshalError},
     reflection::{
         equals_argument_type, equals_return_type, ArgumentReflection, ReturnTypeReflection,
     },
+    struct_visitor::{visit_struct, StructVisitor, Visit},
+    sync_struct_ref::SyncStructRef,
     Runtime,
 };
-use memory::gc::{GcRuntime, HasIndirectionPtr};
+use memory::gc::{GcRuntime, HasIndirectionPtr, RawGcPtr};
 use std::cell::RefCell;
 use std::{
+    any::Any,
+    collections::hash_map::DefaultHasher,
+    ffi::c_void,
+    fmt,
+    hash::{Hash, Hasher},
+    io,
+    ops::Range,
     ptr::{self, NonNull},
     rc::Rc,
+    slice,
 };
 
 /// Represents a Mun struct pointer.
 #[repr(transparent)]
 #[derive(Clone)]
-pub struct RawStruct(GcPtr);
+pub struct RawStruct(pub(crate) GcPtr);
 
 impl RawStruct {
     /// Returns a pointer to the struct memory.
+    ///
+    /// # Safety
+    ///
+    /// The struct handle must be non-null, and the struct must not have been freed.
     pub unsafe fn get_ptr(&self) -> *const u8 {
+        debug_assert!(!self.is_null(), "the struct handle is null");
         self.0.deref()
     }
+
+    /// Returns a pointer to the struct memory, or `None` if the struct handle is null.
+    ///
+    /// # Safety
+    ///
+    /// A freed struct cannot be detected, so a non-null struct handle must not have been freed.
+    pub unsafe fn try_get_ptr(&self) -> Option<*const u8> {
+        if self.is_null() {
+            None
+        } else {
+            Some(self.0.deref())
+        }
+    }
+
+    /// Returns whether the struct handle is null.
+    fn is_null(&self) -> bool {
+        let raw: RawGcPtr = self.0.into();
+        raw.is_null()
+    }
 }
 
+/// An error that occurs when the type of a struct field does not match the requested type, as
+/// returned by [`StructRef::try_get`].
+///
+/// [`StructRef::try_get`]: struct.StructRef.html#method.try_get
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypeMismatch {
+    /// The name of the struct's type
+    pub struct_name: String,
+    /// The name of the field
+    pub field_name: String,
+    /// The name of the requested type
+    pub expected: String,
+    /// The name of the field's type
+    pub found: String,
+}
+
+impl fmt::Display for TypeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Mismatched types for `{}::{}`. Expected: `{}`. Found: `{}`.",
+            self.struct_name, self.field_name, self.expected, self.found
+        )
+    }
+}
+
+impl std::error::Error for TypeMismatch {}
+
+/// An error that occurs when the value of a struct field cannot be retrieved as the requested
+/// type, as returned by [`StructRef::try_get`].
+///
+/// [`StructRef::try_get`]: struct.StructRef.html#method.try_get
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FieldError {
+    /// The type of the field does not match the requested type
+    TypeMismatch(TypeMismatch),
+    /// The value of the field cannot be represented by the requested type
+    Marshal(MarshalError),
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldError::TypeMismatch(e) => e.fmt(f),
+            FieldError::Marshal(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
+
 /// Type-agnostic wrapper for interoperability with a Mun struct.
 #[derive(Clone)]
 pub struct StructRef {
@@ -34,7 +123,7 @@ pub struct StructRef {
 
 impl StructRef {
     /// Creates a `StructRef` that wraps a raw Mun struct.
-    fn new(runtime: Rc<RefCell<Runtime>>, raw: RawStruct) -> Self {
+    pub(crate) fn new(runtime: Rc<RefCell<Runtime>>, raw: RawStruct) -> Self {
         let handle = {
             let runtime_ref = runtime.borrow();
             // Safety: The type returned from `ptr_type` is guaranteed to live at least as long as
@@ -60,6 +149,46 @@ impl StructRef {
         RawStruct(self.handle.handle())
     }
 
+    /// Consumes the `StructRef`, returning a raw Mun struct that remains rooted until it is passed
+    /// to [`Runtime::unroot`]. This allows a host to keep many structs alive without holding a
+    /// `StructRef` for each of them.
+    ///
+    /// [`Runtime::unroot`]: struct.Runtime.html#method.unroot
+    pub fn into_rooted_raw(self) -> RawStruct {
+        self.runtime.borrow().gc.root(self.handle.handle());
+        self.into_raw()
+    }
+
+    /// Constructs a `StructRef` from a raw Mun struct that was rooted with [`into_rooted_raw`] or
+    /// [`Runtime::root`], taking over its root.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a struct of `runtime` that is rooted, and the caller must not unroot it
+    /// afterwards.
+    ///
+    /// [`into_rooted_raw`]: #method.into_rooted_raw
+    /// [`Runtime::root`]: struct.Runtime.html#method.root
+    pub unsafe fn from_rooted_raw(runtime: Rc<RefCell<Runtime>>, raw: RawStruct) -> Self {
+        let struct_ref = Self::new(runtime, raw);
+        struct_ref
+            .runtime
+            .borrow()
+            .gc
+            .unroot(struct_ref.handle.handle());
+        struct_ref
+    }
+
+    /// Consumes the `StructRef`, returning a `Send + Sync` wrapper for read-only access from
+    /// worker threads. See [`SyncStructRef`] for its restrictions.
+    ///
+    /// [`SyncStructRef`]: struct.SyncStructRef.html
+    pub fn into_sync(self) -> SyncStructRef {
+        let runtime_ref = self.runtime.borrow();
+        let type_info = runtime_ref.gc.ptr_type(self.handle.handle());
+        SyncStructRef::new(self.handle, type_info, runtime_ref.sync_state.clone())
+    }
+
     /// Returns the type information of the struct.
     pub fn type_info<'r>(struct_ref: &Self, runtime_ref: &'r Runtime) -> &'r abi::TypeInfo {
         // Safety: The type returned from `ptr_type` is guaranteed to live at least as long as
@@ -74,6 +203,414 @@ impl StructRef {
         }
     }
 
+    /// Returns whether the struct's type is called `type_name`.
+    pub fn is_a(&self, type_name: &str) -> bool {
+        let runtime_ref = self.runtime.borrow();
+        Self::type_info(self, &runtime_ref).name() == type_name
+    }
+
+    /// Checks that the struct's type is called `type_name`, so it can be treated as a struct of
+    /// that type.
+    pub fn downcast_check(&self, type_name: &str) -> Result<(), String> {
+        let runtime_ref = self.runtime.borrow();
+        let type_info = Self::type_info(self, &runtime_ref);
+        if type_info.name() == type_name {
+            Ok(())
+        } else {
+            Err(format!(
+                "Mismatched struct types. Expected: `{}`. Found: `{}`.",
+                type_name,
+                type_info.name()
+            ))
+        }
+    }
+
+    /// Returns the memory kind of the struct, which determines whether the struct has value or
+    /// reference semantics.
+    pub fn memory_kind(&self) -> abi::StructMemoryKind {
+        let runtime_ref = self.runtime.borrow();
+        let type_info = Self::type_info(self, &runtime_ref);
+
+        // Safety: `as_struct` is guaranteed to return `Some` for `StructRef`s.
+        type_info.as_struct().unwrap().memory_kind.clone()
+    }
+
+    /// Returns the number of garbage collection cycles that the struct's memory has survived, or
+    /// `None` if the runtime does not track object ages.
+    pub fn gc_age(&self) -> Option<u32> {
+        self.runtime.borrow().gc.object_age(self.handle.handle())
+    }
+
+    /// Attaches a host-owned `resource` to the struct, e.g. a database connection that a Mun
+    /// script holds on to. The runtime drops the resource when the struct is collected, unless it
+    /// is detached first with [`take_resource`].
+    ///
+    /// The resource is dropped while the garbage collector is collecting, after it released its
+    /// locks. Its `Drop` implementation can drop `StructRef`s, but must not mutably borrow the
+    /// runtime. Alternatively, the runtime can queue the
+    /// resource when the struct is collected, until the host drops it with
+    /// [`Runtime::run_pending_finalizers`]. Only garbage collected structs can own
+    /// resources, as value structs are copied whenever they are passed to or from Mun.
+    ///
+    /// [`take_resource`]: #method.take_resource
+    /// [`Runtime::run_pending_finalizers`]: struct.Runtime.html#method.run_pending_finalizers
+    pub fn attach_resource<T: Any + Send>(&self, resource: T) -> Result<(), String> {
+        let runtime_ref = self.runtime.borrow();
+        let type_info = Self::type_info(self, &runtime_ref);
+
+        // Safety: `as_struct` is guaranteed to return `Some` for `StructRef`s.
+        if type_info.as_struct().unwrap().memory_kind == abi::StructMemoryKind::Value {
+            return Err(format!(
+                "Cannot attach a resource to value struct `{}`.",
+                type_info.name()
+            ));
+        }
+
+        runtime_ref
+            .gc
+            .observer()
+            .attach(self.handle.handle(), Box::new(resource));
+        Ok(())
+    }
+
+    /// Detaches and returns the first resource of type `T` that was attached to the struct with
+    /// [`attach_resource`], relinquishing the runtime's ownership. Returns `None` if no such
+    /// resource is attached.
+    ///
+    /// [`attach_resource`]: #method.attach_resource
+    pub fn take_resource<T: Any + Send>(&self) -> Option<T> {
+        self.runtime
+            .borrow()
+            .gc
+            .observer()
+            .take(self.handle.handle())
+    }
+
+    /// Creates an opaque handle to a host-owned resource with the `create` callback of the handle
+    /// type `type_name`, which was registered with [`RuntimeBuilder::register_handle_type`], and
+    /// attaches it to the struct. Returns the raw handle, which remains valid until the struct is
+    /// collected, at which point the runtime releases it with the type's `drop` callback. Like
+    /// any other resource, the handle can be dropped later if drops are deferred, and it cannot be
+    /// attached to a value struct, in which case it is released immediately.
+    ///
+    /// # Safety
+    ///
+    /// `arg` must be valid for the `create` callback of the handle type.
+    ///
+    /// [`RuntimeBuilder::register_handle_type`]:
+    /// struct.RuntimeBuilder.html#method.register_handle_type
+    pub unsafe fn create_handle(
+        &self,
+        type_name: &str,
+        arg: *mut c_void,
+    ) -> Result<*mut c_void, String> {
+        let handle_type = self
+            .runtime
+            .borrow()
+            .handle_types
+            .get(type_name)
+            .cloned()
+            .ok_or_else(|| format!("Unknown handle type `{}`.", type_name))?;
+        let handle = OwnedHandle::create(handle_type, arg)
+            .ok_or_else(|| format!("Failed to create a handle of type `{}`.", type_name))?;
+
+        let raw = handle.as_raw();
+        self.attach_resource(handle)?;
+        Ok(raw)
+    }
+
+    /// Detaches and returns the first handle of type `type_name` that was created with
+    /// [`create_handle`], relinquishing the runtime's ownership: the host is responsible for
+    /// releasing it. Returns `None` if no such handle is attached.
+    ///
+    /// [`create_handle`]: #method.create_handle
+    pub fn take_handle(&self, type_name: &str) -> Option<*mut c_void> {
+        self.runtime
+            .borrow()
+            .gc
+            .observer()
+            .take_where(self.handle.handle(), |handle: &OwnedHandle| {
+                handle.type_name() == type_name
+            })
+            .map(OwnedHandle::into_raw)
+    }
+
+    /// Registers `callback` to be invoked with the struct whenever the field `field_name` is
+    /// written through [`set`], [`replace`], [`replace_with`], [`set_all`], or [`copy_from`] of
+    /// any `StructRef` to the struct, even if its value did not change, or whenever its bytes are
+    /// changed through [`with_bytes_mut`]. Writes by Mun code are not observed.
+    ///
+    /// Callbacks are invoked after the write, in the order in which they were registered. When
+    /// `set_all` or `copy_from` writes multiple fields, all fields are written before any
+    /// callback is invoked, after which the callbacks of every field are invoked in the order in
+    /// which the fields were passed to `set_all`, or declared for `copy_from`. A callback that
+    /// writes an observed field invokes that field's callbacks immediately, except for callbacks
+    /// that are already running. Callbacks remain registered until they are cleared with
+    /// [`clear_field_observers`], or the struct is collected. As such, a callback should access
+    /// the struct through its argument rather than capture a `StructRef` to it, which would
+    /// keep the struct alive.
+    ///
+    /// Only the fields of garbage collected structs can be observed, as value structs are copied
+    /// whenever they are passed to or from Mun.
+    ///
+    /// [`set`]: #method.set
+    /// [`replace`]: #method.replace
+    /// [`replace_with`]: #method.replace_with
+    /// [`set_all`]: #method.set_all
+    /// [`copy_from`]: #method.copy_from
+    /// [`with_bytes_mut`]: #method.with_bytes_mut
+    /// [`clear_field_observers`]: #method.clear_field_observers
+    pub fn on_field_changed<F: FnMut(&StructRef) + 'static>(
+        &self,
+        field_name: &str,
+        callback: F,
+    ) -> Result<(), String> {
+        let runtime_ref = self.runtime.borrow();
+        let type_info = Self::type_info(self, &runtime_ref);
+
+        // Safety: `as_struct` is guaranteed to return `Some` for `StructRef`s.
+        let struct_info = type_info.as_struct().unwrap();
+        if struct_info.memory_kind == abi::StructMemoryKind::Value {
+            return Err(format!(
+                "Cannot observe the fields of value struct `{}`.",
+                type_info.name()
+            ));
+        }
+        abi::StructInfo::find_field_index(type_info.name(), struct_info, field_name)?;
+
+        let handle = self.handle.handle();
+        let mut observers = runtime_ref.field_observers.borrow_mut();
+        observers.remove_deallocated(&runtime_ref.gc.observer().take_deallocated());
+        runtime_ref.gc.observer().watch(handle);
+        let callback: FieldChangedCallback = Rc::new(RefCell::new(callback));
+        observers.insert(handle, field_name, callback);
+        Ok(())
+    }
+
+    /// Removes all callbacks that were registered with [`on_field_changed`] for the field
+    /// `field_name` of the struct.
+    ///
+    /// [`on_field_changed`]: #method.on_field_changed
+    pub fn clear_field_observers(&self, field_name: &str) {
+        let runtime_ref = self.runtime.borrow();
+        let handle = self.handle.handle();
+        let mut observers = runtime_ref.field_observers.borrow_mut();
+        observers.remove_deallocated(&runtime_ref.gc.observer().take_deallocated());
+        if !observers.remove(handle, field_name) {
+            runtime_ref.gc.observer().unwatch(handle);
+        }
+    }
+
+    /// Returns whether the struct is structurally equal to `other`.
+    ///
+    /// Two structs are equal if they have the same type and all of their fields are equal.
+    /// Fundamental fields are compared bitwise, so for example `NaN` equals `NaN`, but `0.0` does
+    /// not equal `-0.0`. Struct fields are compared recursively by value, regardless of their
+    /// memory kind. Cyclic references are equal if both structs refer back to the same
+    /// position in their respective chains of references.
+    pub fn equals(&self, other: &StructRef) -> bool {
+        let mut visitor = EqualityVisitor::new(&[]);
+        self.visit(&mut visitor);
+        visitor.start_comparing();
+        other.visit(&mut visitor) && visitor.is_equal()
+    }
+
+    /// Returns whether the struct is structurally equal to `other`, disregarding the values of
+    /// the top-level fields named in `ignore`. See [`equals`] for the definition of equality.
+    ///
+    /// Returns an error if the struct does not contain a field named in `ignore`.
+    ///
+    /// [`equals`]: #method.equals
+    pub fn equals_ignoring(&self, other: &StructRef, ignore: &[&str]) -> Result<bool, String> {
+        {
+            let runtime_ref = self.runtime.borrow();
+            let type_info = Self::type_info(self, &runtime_ref);
+
+            // Safety: `as_struct` is guaranteed to return `Some` for `StructRef`s.
+            let struct_info = type_info.as_struct().unwrap();
+            for field_name in ignore {
+                abi::StructInfo::find_field_index(type_info.name(), struct_info, field_name)?;
+            }
+        }
+
+        let mut visitor = EqualityVisitor::new(ignore);
+        self.visit(&mut visitor);
+        visitor.start_comparing();
+        Ok(other.visit(&mut visitor) && visitor.is_equal())
+    }
+
+    /// Computes a hash of the struct that is consistent with [`equals`]: structs that are equal
+    /// have the same hash.
+    ///
+    /// The hash is computed from the struct's type and the values of its fields, so mutating the
+    /// struct changes its hash. Structs should therefore not be mutated while used as the key of
+    /// a hash map.
+    ///
+    /// [`equals`]: #method.equals
+    pub fn structural_hash(&self) -> u64 {
+        let mut visitor = HashVisitor {
+            state: DefaultHasher::new(),
+        };
+        self.visit(&mut visitor);
+        visitor.state.finish()
+    }
+
+    /// Traverses the graph of the struct with `visitor`. See [`StructVisitor`] for details.
+    ///
+    /// Returns `false` if the visitor stopped the traversal, `true` otherwise.
+    ///
+    /// [`StructVisitor`]: trait.StructVisitor.html
+    pub fn visit(&self, visitor: &mut dyn StructVisitor) -> bool {
+        let runtime_ref = self.runtime.borrow();
+        let type_info = Self::type_info(self, &runtime_ref);
+
+        let mut path = vec![self.handle.handle()];
+        // Safety: The `GcRootPtr` keeps the object alive, and is of type `type_info`.
+        unsafe { visit_struct(self.handle.deref::<u8>(), type_info, &mut path, visitor) }
+    }
+
+    /// Writes the graph of the struct to `writer` as JSON, streaming it without building an
+    /// intermediate representation.
+    ///
+    /// Fields are written in declaration order. Cyclic references and values of unsupported types
+    /// are written as `null`. As JSON cannot represent them, non-finite floating-point numbers
+    /// result in an error; use [`write_json_with`] to substitute them instead.
+    ///
+    /// [`write_json_with`]: #method.write_json_with
+    pub fn write_json<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.write_json_with(writer, NonFinitePolicy::Error)
+    }
+
+    /// Writes the graph of the struct to `writer` as JSON, like [`write_json`], handling
+    /// non-finite floating-point numbers according to `non_finite`. When an error occurs, a
+    /// partial document may have been written.
+    ///
+    /// [`write_json`]: #method.write_json
+    pub fn write_json_with<W: io::Write>(
+        &self,
+        writer: &mut W,
+        non_finite: NonFinitePolicy,
+    ) -> io::Result<()> {
+        let mut json_writer = JsonWriter::new(writer, non_finite);
+        self.visit(&mut json_writer);
+        json_writer.finish()
+    }
+
+    /// Calls `f` with the memory of the struct, so it can be written in bulk, e.g. to fill the
+    /// struct from a network buffer. Returns the result of `f`.
+    ///
+    /// This is only permitted if the struct is plain old data, as overwriting references to
+    /// garbage collected structs would corrupt the heap. For other structs an error is returned,
+    /// and their fields have to be written with [`set`] instead. The memory is laid out as given
+    /// by [`Runtime::struct_layout`].
+    ///
+    /// `f` writes a copy of the struct's memory, so the runtime is not borrowed while it runs.
+    /// Afterwards, the fields whose bytes were changed by `f` are written back to the struct, and
+    /// their observers are notified. If the struct's type changed while `f` ran, e.g. due to a
+    /// hot reload, nothing is written back and an error is returned.
+    ///
+    /// # Safety
+    ///
+    /// The bytes of every field must be valid for the field's type after `f` returns, e.g. a
+    /// `bool` must be either `0` or `1`.
+    ///
+    /// [`set`]: #method.set
+    /// [`Runtime::struct_layout`]: struct.Runtime.html#method.struct_layout
+    pub unsafe fn with_bytes_mut<R, F: FnOnce(&mut [u8]) -> R>(
+        &mut self,
+        f: F,
+    ) -> Result<R, String> {
+        let (type_guid, old_bytes) = {
+            let runtime_ref = self.runtime.borrow();
+            let type_info = Self::type_info(self, &runtime_ref);
+            if !type_info.is_pod() {
+                return Err(format!(
+                    "Struct `{}` references garbage collected structs, so its bytes cannot be \
+                     written directly. Use `set` to write its fields instead.",
+                    type_info.name()
+                ));
+            }
+
+            // The `GcRootPtr` keeps the object alive, and its memory is `type_info`'s size.
+            let bytes = slice::from_raw_parts(self.handle.deref::<u8>(), type_info.size_in_bytes());
+            (type_info.guid, bytes.to_vec())
+        };
+
+        let mut bytes = old_bytes.clone();
+        let result = f(&mut bytes);
+
+        self.unshare();
+
+        let runtime_ref = self.runtime.borrow();
+        let type_info = Self::type_info(self, &runtime_ref);
+        if type_info.guid != type_guid {
+            return Err(format!(
+                "The type of struct `{}` changed while its bytes were written.",
+                type_info.name()
+            ));
+        }
+
+        // `as_struct` is guaranteed to return `Some` for `StructRef`s.
+        let struct_info = type_info.as_struct().unwrap();
+        let changed_fields: Vec<(usize, Range<usize>)> = struct_info
+            .field_offsets()
+            .iter()
+            .zip(struct_info.field_types())
+            .map(|(offset, field_type)| {
+                let offset = usize::from(*offset);
+                offset..offset + field_type.size_in_bytes()
+            })
+            .enumerate()
+            .filter(|(_, range)| old_bytes[range.clone()] != bytes[range.clone()])
+            .collect();
+
+        // Prevent worker threads from reading the fields while they are written
+        let guard = runtime_ref.sync_state.write();
+        for (field_idx, range) in changed_fields.iter() {
+            let field_ptr = self.field_offset_unchecked::<u8>(struct_info, *field_idx);
+            self.write_field(&runtime_ref, type_info, *field_idx, || {
+                ptr::copy_nonoverlapping(
+                    bytes[range.clone()].as_ptr(),
+                    field_ptr.as_ptr(),
+                    range.len(),
+                )
+            });
+        }
+        drop(guard);
+
+        let field_names: Vec<String> = struct_info
+            .field_names()
+            .enumerate()
+            .filter(|(field_idx, _)| changed_fields.iter().any(|(idx, _)| idx == field_idx))
+            .map(|(_, field_name)| field_name.to_string())
+            .collect();
+        drop(runtime_ref);
+
+        let field_names: Vec<&str> = field_names.iter().map(String::as_str).collect();
+        self.notify_field_changed(&field_names);
+        Ok(result)
+    }
+
+    /// Pins the struct's memory, documenting that host code holds pointers into it for as long as
+    /// the returned guard is alive.
+    ///
+    /// The garbage collector does not move structs, but a struct that was allocated in an open
+    /// [`Runtime::scope`] is moved to the heap, so it is not freed or relocated when the scope
+    /// closes. Pinning does not defer hot reloads: [`Runtime::update`] still relocates a struct
+    /// whose type changed, which invalidates pointers obtained through the guard.
+    ///
+    /// [`Runtime::scope`]: struct.Runtime.html#method.scope
+    /// [`Runtime::update`]: struct.Runtime.html#method.update
+    pub fn pin(&self) -> PinnedStruct<'_> {
+        let runtime_ref = self.runtime.borrow();
+
+        // Prevent worker threads from reading the struct while it is moved out of its scope
+        let _guard = runtime_ref.sync_state.write();
+        runtime_ref.gc.pin(self.handle.handle());
+
+        PinnedStruct { struct_ref: self }
+    }
+
     ///
     ///
     /// # Safety
@@ -89,6 +626,132 @@ impl StructRef {
         NonNull::new_unchecked(self.handle.deref::<u8>().add(offset as usize).cast::<T>() as *mut _)
     }
 
+    /// Writes the field `field_idx` of the struct, whose type is `type_info`, using `write`. With
+    /// the `mutation-log` feature enabled, the write is recorded in the runtime's mutation log.
+    fn write_field<W: FnOnce()>(
+        &self,
+        runtime_ref: &Runtime,
+        type_info: &abi::TypeInfo,
+        field_idx: usize,
+        write: W,
+    ) {
+        self.write_fields(runtime_ref, type_info, &[field_idx], write)
+    }
+
+    /// Writes the fields `field_indices` of the struct, whose type is `type_info`, at once using
+    /// `write`. With the `mutation-log` feature enabled, the write of every field is recorded in
+    /// the runtime's mutation log.
+    #[cfg_attr(not(feature = "mutation-log"), allow(unused_variables))]
+    fn write_fields<W: FnOnce()>(
+        &self,
+        runtime_ref: &Runtime,
+        type_info: &abi::TypeInfo,
+        field_indices: &[usize],
+        write: W,
+    ) {
+        #[cfg(feature = "mutation-log")]
+        {
+            // Safety: `as_struct` is guaranteed to return `Some` for `StructRef`s.
+            let struct_info = type_info.as_struct().unwrap();
+            let field_bytes = |field_idx: usize| {
+                let field_type = struct_info.field_types()[field_idx];
+                let field_size = match field_type.as_struct() {
+                    Some(s) if s.memory_kind == abi::StructMemoryKind::GC => {
+                        std::mem::size_of::<GcPtr>()
+                    }
+                    _ => field_type.size_in_bytes(),
+                };
+                // Safety: `field_idx` is a valid field index.
+                let field_ptr =
+                    unsafe { self.field_offset_unchecked::<u8>(struct_info, field_idx) };
+                unsafe { slice::from_raw_parts(field_ptr.as_ptr(), field_size) }.to_vec()
+            };
+
+            let old: Vec<Vec<u8>> = field_indices.iter().map(|idx| field_bytes(*idx)).collect();
+            write();
+            let timestamp = std::time::Instant::now();
+            let mut mutation_log = runtime_ref.mutation_log.lock();
+            for (field_idx, old) in field_indices.iter().zip(old) {
+                mutation_log.record(crate::mutation_log::Mutation {
+                    type_name: type_info.name().to_string(),
+                    // Safety: `field_idx` is a valid field index.
+                    field_name: struct_info
+                        .field_names()
+                        .nth(*field_idx)
+                        .unwrap()
+                        .to_string(),
+                    old,
+                    new: field_bytes(*field_idx),
+                    timestamp,
+                });
+            }
+        }
+        #[cfg(not(feature = "mutation-log"))]
+        write();
+    }
+
+    /// Ensures that the struct's memory is not shared with other `StructRef`s, so it can safely
+    /// be mutated.
+    fn unshare(&mut self) {
+        let runtime_ref = self.runtime.borrow();
+        let handle = runtime_ref.gc.unshare(self.handle.handle());
+        if handle != self.handle.handle() {
+            self.handle = GcRootPtr::new(&runtime_ref.gc, handle);
+        }
+    }
+
+    /// Invokes the callbacks that observe the fields `field_names` of the struct, field by field.
+    /// The runtime must not be mutably borrowed, so the callbacks can access it.
+    fn notify_field_changed(&self, field_names: &[&str]) {
+        let handle = self.handle.handle();
+        for field_name in field_names {
+            let callbacks = {
+                let runtime_ref = self.runtime.borrow();
+                let mut observers = runtime_ref.field_observers.borrow_mut();
+                observers.remove_deallocated(&runtime_ref.gc.observer().take_deallocated());
+                if !observers.is_observed(handle) {
+                    return;
+                }
+                observers.callbacks(handle, field_name)
+            };
+
+            for callback in callbacks {
+                // A callback that is already running is not invoked recursively
+                if let Ok(mut callback) = callback.try_borrow_mut() {
+                    (&mut *callback)(self);
+                }
+            }
+        }
+    }
+
+    /// Retrieves the documentation of the field corresponding to the specified `field_name`, if it
+    /// is documented.
+    pub fn field_doc(&self, field_name: &str) -> Result<Option<String>, String> {
+        let runtime_ref = self.runtime.borrow();
+        let type_info = Self::type_info(self, &runtime_ref);
+
+        // Safety: `as_struct` is guaranteed to return `Some` for `StructRef`s.
+        let struct_info = type_info.as_struct().unwrap();
+        let field_idx =
+            abi::StructInfo::find_field_index(type_info.name(), struct_info, field_name)?;
+
+        Ok(struct_info.field_doc(field_idx).map(ToString::to_string))
+    }
+
+    /// Retrieves the byte offset of the field corresponding to the specified `field_name`, relative
+    /// to the start of the struct's memory.
+    pub fn offset_of(&self, field_name: &str) -> Result<usize, String> {
+        let runtime_ref = self.runtime.borrow();
+        let type_info = Self::type_info(self, &runtime_ref);
+
+        // Safety: `as_struct` is guaranteed to return `Some` for `StructRef`s.
+        let struct_info = type_info.as_struct().unwrap();
+        let field_idx =
+            abi::StructInfo::find_field_index(type_info.name(), struct_info, field_name)?;
+
+        Ok(usize::from(struct_info.field_offsets()[field_idx]))
+    }
+
     /// Retrieves the value of the field corresponding to the specified `field_name`.
     pub fn get<T: ReturnTypeReflection>(&self, field_name: &str) -> Result<T, String> {
         let runtime_ref = self.runtime.borrow();
@@ -115,20 +778,88 @@ impl StructRef {
         // If we found the `field_idx`, we are guaranteed to also have the `field_offset`
         let field_ptr =
             unsafe { self.field_offset_unchecked::<T::Marshalled>(struct_info, field_idx) };
-        Ok(Marshal::marshal_from_ptr(
-            field_ptr,
-            self.runtime.clone(),
-            Some(field_type),
-        ))
+        Marshal::marshal_from_ptr(field_ptr, self.runtime.clone(), Some(field_type))
+            .map_err(|e| e.to_string())
+    }
+
+    /// Retrieves the value of the field corresponding to the specified `field_name`, if the struct
+    /// contains it.
+    ///
+    /// Unlike [`get`], this distinguishes a missing field, for which `None` is returned, from a
+    /// field of a different type or a value that cannot be represented by `T`, for which
+    /// `Some(Err(_))` is returned.
+    ///
+    /// [`get`]: #method.get
+    pub fn try_get<T: ReturnTypeReflection>(
+        &self,
+        field_name: &str,
+    ) -> Option<Result<T, FieldError>> {
+        let runtime_ref = self.runtime.borrow();
+        let type_info = Self::type_info(self, &runtime_ref);
+
+        // Safety: `as_struct` is guaranteed to return `Some` for `StructRef`s.
+        let struct_info = type_info.as_struct().unwrap();
+        let field_idx = struct_info
+            .field_names()
+            .position(|name| name == field_name)?;
+
+        // Safety: If we found the `field_idx`, we are guaranteed to also have the `field_type` and
+        // `field_offset`.
+        let field_type = unsafe { struct_info.field_types().get_unchecked(field_idx) };
+        if let Err((expected, found)) = equals_return_type::<T>(field_type) {
+            return Some(Err(FieldError::TypeMismatch(TypeMismatch {
+                struct_name: type_info.name().to_string(),
+                field_name: field_name.to_string(),
+                expected: expected.to_string(),
+                found: found.to_string(),
+            })));
+        }
+
+        let field_ptr =
+            unsafe { self.field_offset_unchecked::<T::Marshalled>(struct_info, field_idx) };
+        Some(
+            Marshal::marshal_from_ptr(field_ptr, self.runtime.clone(), Some(field_type))
+                .map_err(FieldError::Marshal),
+        )
+    }
+
+    /// Retrieves the value of the field corresponding to the specified `field_name`, without
+    /// knowing its type statically.
+    ///
+    /// Struct fields are returned as a `StructRef`, which keeps the struct alive.
+    pub fn get_dyn(&self, field_name: &str) -> Result<DynValue, String> {
+        let runtime_ref = self.runtime.borrow();
+        let type_info = Self::type_info(self, &runtime_ref);
+
+        // Safety: `as_struct` is guaranteed to return `Some` for `StructRef`s.
+        let struct_info = type_info.as_struct().unwrap();
+        let field_idx =
+            abi::StructInfo::find_field_index(type_info.name(), struct_info, field_name)?;
+
+        // Safety: If we found the `field_idx`, we are guaranteed to also have the `field_type` and
+        // `field_offset`.
+        let field_type = unsafe { struct_info.field_types().get_unchecked(field_idx) };
+        let field_ptr = unsafe { self.field_offset_unchecked::<u8>(struct_info, field_idx) };
+        unsafe { DynValue::marshal_from_ptr(field_ptr, self.runtime.clone(), field_type) }
+            .ok_or_else(|| {
+                format!(
+                    "Unsupported type for `{}::{}`. Found: `{}`.",
+                    type_info.name(),
+                    field_name,
+                    field_type.name(),
+                )
+            })
     }
 
     /// Replaces the value of the field corresponding to the specified `field_name` and returns the
     /// old value.
-    pub fn replace<T: ArgumentReflection>(
+    pub fn replace<T: ArgumentReflection + ReturnTypeReflection>(
         &mut self,
         field_name: &str,
         value: T,
     ) -> Result<T, String> {
+        self.unshare();
+
         let runtime_ref = self.runtime.borrow();
         let type_info = Self::type_info(self, &runtime_ref);
 
@@ -150,15 +881,104 @@ impl StructRef {
             )
         })?;
 
-        let field_ptr =
-            unsafe { self.field_offset_unchecked::<T::Marshalled>(struct_info, field_idx) };
-        let old = Marshal::marshal_from_ptr(field_ptr, self.runtime.clone(), Some(field_type));
-        Marshal::marshal_to_ptr(value.marshal(), field_ptr, Some(field_type));
+        // Prevent worker threads from reading the field while it is written
+        let guard = runtime_ref.sync_state.write();
+        let field_ptr = unsafe { self.field_offset_unchecked::<u8>(struct_info, field_idx) };
+        let old = Marshal::marshal_from_ptr(
+            field_ptr.cast::<<T as ReturnTypeReflection>::Marshalled>(),
+            self.runtime.clone(),
+            Some(field_type),
+        )
+        .map_err(|e| e.to_string())?;
+        self.write_field(&runtime_ref, type_info, field_idx, || {
+            MarshalArgument::marshal_to_ptr(
+                value.marshal(&runtime_ref, field_type),
+                field_ptr.cast::<<T as ArgumentReflection>::Marshalled>(),
+                Some(field_type),
+            )
+        });
+        drop(guard);
+        drop(runtime_ref);
+
+        self.notify_field_changed(&[field_name]);
         Ok(old)
     }
 
+    /// Replaces the value of the field corresponding to the specified `field_name` with the result
+    /// of applying `f` to its current value, and returns the new value.
+    ///
+    /// Unlike a [`get`] followed by a [`set`], the field is resolved only once.
+    ///
+    /// [`get`]: #method.get
+    /// [`set`]: #method.set
+    pub fn replace_with<T, F>(&mut self, field_name: &str, f: F) -> Result<T, String>
+    where
+        T: ArgumentReflection + ReturnTypeReflection + Clone,
+        F: FnOnce(T) -> T,
+    {
+        self.unshare();
+
+        let runtime_ref = self.runtime.borrow();
+        let type_info = Self::type_info(self, &runtime_ref);
+
+        // Safety: `as_struct` is guaranteed to return `Some` for `StructRef`s.
+        let struct_info = type_info.as_struct().unwrap();
+        let field_idx =
+            abi::StructInfo::find_field_index(type_info.name(), struct_info, field_name)?;
+
+        // Safety: If we found the `field_idx`, we are guaranteed to also have the `field_type` and
+        // `field_offset`.
+        let field_type = unsafe { struct_info.field_types().get_unchecked(field_idx) };
+        equals_return_type::<T>(field_type).map_err(|(expected, found)| {
+            format!(
+                "Mismatched types for `{}::{}`. Expected: `{}`. Found: `{}`.",
+                type_info.name(),
+                field_name,
+                expected,
+                found,
+            )
+        })?;
+
+        let field_ptr = unsafe { self.field_offset_unchecked::<u8>(struct_info, field_idx) };
+        let old = Marshal::marshal_from_ptr(
+            field_ptr.cast::<<T as ReturnTypeReflection>::Marshalled>(),
+            self.runtime.clone(),
+            Some(field_type),
+        )
+        .map_err(|e| e.to_string())?;
+        let new = f(old);
+
+        // A struct value of a different type than the field's could have been returned
+        equals_argument_type(&runtime_ref, field_type, &new).map_err(|(expected, found)| {
+            format!(
+                "Mismatched types for `{}::{}`. Expected: `{}`. Found: `{}`.",
+                type_info.name(),
+                field_name,
+                expected,
+                found,
+            )
+        })?;
+
+        // Prevent worker threads from reading the field while it is written
+        let guard = runtime_ref.sync_state.write();
+        self.write_field(&runtime_ref, type_info, field_idx, || {
+            MarshalArgument::marshal_to_ptr(
+                new.clone().marshal(&runtime_ref, field_type),
+                field_ptr.cast::<<T as ArgumentReflection>::Marshalled>(),
+                Some(field_type),
+            )
+        });
+        drop(guard);
+        drop(runtime_ref);
+
+        self.notify_field_changed(&[field_name]);
+        Ok(new)
+    }
+
     /// Sets the value of the field corresponding to the specified `field_name`.
     pub fn set<T: ArgumentReflection>(&mut self, field_name: &str, value: T) -> Result<(), String> {
+        self.unshare();
+
         let runtime_ref = self.runtime.borrow();
         let type_info = Self::type_info(self, &runtime_ref);
 
@@ -180,11 +1000,286 @@ impl StructRef {
             )
         })?;
 
+        // Prevent worker threads from reading the field while it is written
+        let guard = runtime_ref.sync_state.write();
         let field_ptr =
             unsafe { self.field_offset_unchecked::<T::Marshalled>(struct_info, field_idx) };
-        Marshal::marshal_to_ptr(value.marshal(), field_ptr, Some(field_type));
+        self.write_field(&runtime_ref, type_info, field_idx, || {
+            MarshalArgument::marshal_to_ptr(
+                value.marshal(&runtime_ref, field_type),
+                field_ptr,
+                Some(field_type),
+            )
+        });
+        drop(guard);
+        drop(runtime_ref);
+
+        self.notify_field_changed(&[field_name]);
+        Ok(())
+    }
+
+    /// Overwrites the values of all fields with those of `other`, which must be of the same
+    /// struct type.
+    ///
+    /// The struct's memory is copied at once, which is equivalent to assigning every field:
+    /// value struct fields are copied, while garbage collected struct fields end up referencing
+    /// the same structs as those of `other`. Returns an error if the structs' types differ.
+    pub fn copy_from(&mut self, other: &StructRef) -> Result<(), String> {
+        self.unshare();
+
+        let runtime_ref = self.runtime.borrow();
+        let type_info = Self::type_info(self, &runtime_ref);
+        let other_type_info = Self::type_info(other, &runtime_ref);
+        if type_info.guid != other_type_info.guid {
+            return Err(format!(
+                "Mismatched struct types. Expected: `{}`. Found: `{}`.",
+                type_info.name(),
+                other_type_info.name()
+            ));
+        }
+
+        let (src, dest) = (other.handle.handle(), self.handle.handle());
+        if src != dest {
+            // Prevent worker threads from reading the fields while they are written
+            let _guard = runtime_ref.sync_state.write();
+            // Safety: `as_struct` is guaranteed to return `Some` for `StructRef`s.
+            let field_indices: Vec<usize> =
+                (0..type_info.as_struct().unwrap().field_types().len()).collect();
+            // Safety: Both `GcRootPtr`s keep their objects alive, and are of type `type_info`.
+            // Distinct objects never overlap.
+            let dest_ptr = unsafe { self.handle.deref_mut::<u8>() };
+            self.write_fields(&runtime_ref, type_info, &field_indices, || unsafe {
+                ptr::copy_nonoverlapping(src.deref::<u8>(), dest_ptr, type_info.size_in_bytes())
+            });
+        }
+
+        if runtime_ref.field_observers.borrow().is_observed(dest) {
+            // Safety: `as_struct` is guaranteed to return `Some` for `StructRef`s.
+            let field_names: Vec<String> = type_info
+                .as_struct()
+                .unwrap()
+                .field_names()
+                .map(ToString::to_string)
+                .collect();
+            drop(runtime_ref);
+
+            let field_names: Vec<&str> = field_names.iter().map(String::as_str).collect();
+            self.notify_field_changed(&field_names);
+        }
         Ok(())
     }
+
+    /// Sets the values of multiple fields at once, resolving the struct's type information only
+    /// once.
+    ///
+    /// The update is atomic: all `fields` are validated before any field is written. If a field
+    /// does not exist, occurs more than once, or its value has a mismatching type, an error is
+    /// returned and the struct is left unmodified. Otherwise, all fields are written.
+    pub fn set_all(&mut self, fields: &[(&str, DynValue)]) -> Result<(), String> {
+        self.unshare();
+
+        let runtime_ref = self.runtime.borrow();
+        let type_info = Self::type_info(self, &runtime_ref);
+
+        // Safety: `as_struct` is guaranteed to return `Some` for `StructRef`s.
+        let struct_info = type_info.as_struct().unwrap();
+
+        let mut field_indices = Vec::with_capacity(fields.len());
+        for (field_name, value) in fields.iter() {
+            let field_idx =
+                abi::StructInfo::find_field_index(type_info.name(), struct_info, field_name)?;
+            if field_indices.contains(&field_idx) {
+                return Err(format!(
+                    "Field `{}::{}` is set more than once.",
+                    type_info.name(),
+                    field_name
+                ));
+            }
+
+            // Safety: If we found the `field_idx`, we are guaranteed to also have the `field_type`.
+            let field_type = unsafe { struct_info.field_types().get_unchecked(field_idx) };
+            if field_type.guid != value.type_guid(&runtime_ref) {
+                return Err(format!(
+                    "Mismatched types for `{}::{}`. Expected: `{}`. Found: `{}`.",
+                    type_info.name(),
+                    field_name,
+                    field_type.name(),
+                    value.type_name(&runtime_ref),
+                ));
+            }
+
+            field_indices.push(field_idx);
+        }
+
+        // All fields have been validated, so writing them can no longer fail. Prevent worker
+        // threads from reading the fields while they are written.
+        let guard = runtime_ref.sync_state.write();
+        for ((_, value), field_idx) in fields.iter().zip(field_indices) {
+            // Safety: If we found the `field_idx`, we are guaranteed to also have the `field_type`
+            // and `field_offset`. The type of `value` was validated to match the `field_type`.
+            let field_type = unsafe { struct_info.field_types().get_unchecked(field_idx) };
+            let field_ptr = unsafe { self.field_offset_unchecked::<u8>(struct_info, field_idx) };
+            self.write_field(&runtime_ref, type_info, field_idx, || unsafe {
+                value
+                    .clone()
+                    .marshal_to_ptr(field_ptr, &runtime_ref, field_type)
+            });
+        }
+        drop(guard);
+        drop(runtime_ref);
+
+        let field_names: Vec<&str> = fields.iter().map(|(field_name, _)| *field_name).collect();
+        self.notify_field_changed(&field_names);
+        Ok(())
+    }
+}
+
+/// A callback of a [`StructVisitor`], as recorded by an [`EqualityVisitor`].
+#[derive(Debug, PartialEq)]
+enum VisitEvent {
+    EnterStruct(abi::Guid),
+    Fundamental(Vec<u8>),
+    Cycle(usize),
+    LeaveStruct,
+}
+
+/// A visitor that records the callbacks for the graph of one struct, and then compares them
+/// against the callbacks for the graph of another struct. Consistent with [`HashVisitor`], two
+/// structs are equal if they produce the same sequence of callbacks.
+///
+/// The values of the top-level fields named in `ignore` are skipped.
+struct EqualityVisitor<'i> {
+    ignore: &'i [&'i str],
+    /// The number of structs that are currently entered
+    depth: usize,
+    events: Vec<VisitEvent>,
+    /// The number of recorded events that matched, or `None` while recording
+    matched: Option<usize>,
+}
+
+impl<'i> EqualityVisitor<'i> {
+    fn new(ignore: &'i [&'i str]) -> Self {
+        Self {
+            ignore,
+            depth: 0,
+            events: Vec::new(),
+            matched: None,
+        }
+    }
+
+    /// Stops recording callbacks, and starts comparing subsequent callbacks against the
+    /// recorded ones.
+    fn start_comparing(&mut self) {
+        self.depth = 0;
+        self.matched = Some(0);
+    }
+
+    /// Returns whether all recorded callbacks were matched.
+    fn is_equal(&self) -> bool {
+        self.matched == Some(self.events.len())
+    }
+
+    fn event(&mut self, event: VisitEvent) -> Visit {
+        match self.matched.as_mut() {
+            None => {
+                self.events.push(event);
+                Visit::Continue
+            }
+            Some(matched) if self.events.get(*matched) == Some(&event) => {
+                *matched += 1;
+                Visit::Continue
+            }
+            Some(_) => Visit::Break,
+        }
+    }
+}
+
+impl StructVisitor for EqualityVisitor<'_> {
+    fn enter_struct(&mut self, type_info: &abi::TypeInfo) -> Visit {
+        self.depth += 1;
+        self.event(VisitEvent::EnterStruct(type_info.guid))
+    }
+
+    fn enter_field(&mut self, name: &str, _type_info: &abi::TypeInfo) -> Visit {
+        if self.depth == 1 && self.ignore.iter().any(|ignored| *ignored == name) {
+            Visit::Skip
+        } else {
+            Visit::Continue
+        }
+    }
+
+    fn visit_fundamental(
+        &mut self,
+        _name: &str,
+        _type_info: &abi::TypeInfo,
+        bytes: &[u8],
+    ) -> Visit {
+        self.event(VisitEvent::Fundamental(bytes.to_vec()))
+    }
+
+    fn visit_cycle(&mut self, _type_info: &abi::TypeInfo, depth: usize) -> Visit {
+        self.event(VisitEvent::Cycle(depth))
+    }
+
+    fn leave_struct(&mut self, _type_info: &abi::TypeInfo) -> Visit {
+        self.depth -= 1;
+        self.event(VisitEvent::LeaveStruct)
+    }
+}
+
+/// A visitor that hashes the values of a struct graph, consistent with [`StructRef::equals`]:
+/// equal structs produce the same sequence of callbacks, and thus the same hash.
+struct HashVisitor<H: Hasher> {
+    state: H,
+}
+
+impl<H: Hasher> StructVisitor for HashVisitor<H> {
+    fn enter_struct(&mut self, type_info: &abi::TypeInfo) -> Visit {
+        type_info.guid.hash(&mut self.state);
+        Visit::Continue
+    }
+
+    fn visit_fundamental(
+        &mut self,
+        _name: &str,
+        _type_info: &abi::TypeInfo,
+        bytes: &[u8],
+    ) -> Visit {
+        bytes.hash(&mut self.state);
+        Visit::Continue
+    }
+
+    fn visit_cycle(&mut self, _type_info: &abi::TypeInfo, depth: usize) -> Visit {
+        depth.hash(&mut self.state);
+        Visit::Continue
+    }
+}
+
+/// A guard that prevents the memory of a Mun struct from being relocated while it is alive.
+///
+/// Created by [`StructRef::pin`]. Pointers obtained through the guard are valid until the guard
+/// is dropped, or until a hot reload changes the struct's type.
+///
+/// The guard borrows the `StructRef`, so the struct cannot be written through it. Writing the
+/// struct through another `StructRef` writes its memory in place, unless the struct's memory is
+/// shared by interned value structs, in which case the other `StructRef` writes a private copy.
+///
+/// [`StructRef::pin`]: struct.StructRef.html#method.pin
+pub struct PinnedStruct<'s> {
+    struct_ref: &'s StructRef,
+}
+
+impl<'s> PinnedStruct<'s> {
+    /// Returns a pointer to the struct memory, which remains valid for the lifetime of the guard.
+    pub fn as_ptr(&self) -> *const u8 {
+        // Safety: The `GcRootPtr` keeps the object alive while we hold a reference to it.
+        unsafe { self.struct_ref.handle.deref::<u8>() }
+    }
+
+    /// Returns the pinned `StructRef`.
+    pub fn struct_ref(&self) -> &'s StructRef {
+        self.struct_ref
+    }
 }
 
 impl ArgumentReflection for StructRef {
@@ -216,11 +1311,34 @@ impl ArgumentReflection for StructRef {
         }
     }
 
-    fn marshal(self) -> Self::Marshalled {
+    fn marshal(self, _runtime: &Runtime, _type_info: &abi::TypeInfo) -> Self::Marshalled {
         self.into_raw()
     }
 }
 
+/// A `&StructRef` is marshalled like a `StructRef`, but without consuming the handle. This allows
+/// passing the same struct to multiple invocations. The `StructRef` keeps the struct alive for as
+/// long as it is borrowed, which includes the entire invocation.
+///
+/// As there is no `StructRef` to borrow from for values that are read from Mun, a `&StructRef`
+/// can only be passed to Mun. It does not implement `ReturnTypeReflection`, so it cannot be
+/// returned from Mun or read from a struct field.
+impl<'s> ArgumentReflection for &'s StructRef {
+    type Marshalled = RawStruct;
+
+    fn type_guid(&self, runtime: &Runtime) -> abi::Guid {
+        <StructRef as ArgumentReflection>::type_guid(self, runtime)
+    }
+
+    fn type_name(&self, runtime: &Runtime) -> &str {
+        <StructRef as ArgumentReflection>::type_name(self, runtime)
+    }
+
+    fn marshal(self, _runtime: &Runtime, _type_info: &abi::TypeInfo) -> Self::Marshalled {
+        RawStruct(self.handle.handle())
+    }
+}
+
 impl ReturnTypeReflection for StructRef {
     type Marshalled = RawStruct;
 
@@ -230,15 +1348,15 @@ impl ReturnTypeReflection for StructRef {
 }
 
 impl Marshal<StructRef> for RawStruct {
-    fn marshal_value(self, runtime: Rc<RefCell<Runtime>>) -> StructRef {
-        StructRef::new(runtime, self)
+    fn marshal_value(self, runtime: Rc<RefCell<Runtime>>) -> Result<StructRef, MarshalError> {
+        Ok(StructRef::new(runtime, self))
     }
 
     fn marshal_from_ptr(
         ptr: NonNull<Self>,
         runtime: Rc<RefCell<Runtime>>,
         type_info: Option<&abi::TypeInfo>,
-    ) -> StructRef {
+    ) -> Result<StructRef, MarshalError> {
         // `type_info` is only `None` for the `()` type
         let type_info = type_info.unwrap();
         let struct_info = type_info.as_struct().unwrap();
@@ -247,32 +1365,29 @@ impl Marshal<StructRef> for RawStruct {
         let gc_handle = if struct_info.memory_kind == abi::StructMemoryKind::Value {
             // For a value struct, `ptr` points to a struct value.
 
-            // Create a new object using the runtime's intrinsic
-            let mut gc_handle = {
-                let runtime_ref = runtime.borrow();
-                runtime_ref.gc().alloc(
-                    // Safety: `ty` is a shared reference, so is guaranteed to not be `ptr::null()`.
-                    UnsafeTypeInfo::new(unsafe {
-                        NonNull::new_unchecked(type_info as *const abi::TypeInfo as *mut _)
-                    }),
-                )
+            let contents = unsafe {
+                slice::from_raw_parts(ptr.cast::<u8>().as_ptr(), type_info.size_in_bytes())
             };
 
-            // Construct
-            let src = ptr.cast::<u8>().as_ptr() as *const _;
-            let dest = unsafe { gc_handle.deref_mut::<u8>() };
-            let size = type_info.size_in_bytes();
-            unsafe { ptr::copy_nonoverlapping(src, dest, size) };
-
-            gc_handle
+            // Copy the struct into a new object, or share the memory of an identical value
+            // struct, if enabled
+            runtime.borrow().gc.alloc_interned(
+                // Safety: `ty` is a shared reference, so is guaranteed to not be `ptr::null()`.
+                UnsafeTypeInfo::new(unsafe {
+                    NonNull::new_unchecked(type_info as *const abi::TypeInfo as *mut _)
+                }),
+                contents,
+            )
         } else {
             // For a gc struct, `ptr` points to a `GcPtr`.
             unsafe { *ptr.cast::<GcPtr>().as_ptr() }
         };
 
-        StructRef::new(runtime, RawStruct(gc_handle))
+        Ok(StructRef::new(runtime, RawStruct(gc_handle)))
     }
+}
 
+impl MarshalArgument<StructRef> for RawStruct {
     fn marshal_to_ptr(value: RawStruct, mut ptr: NonNull<Self>, type_info: Option<&abi::TypeInfo>) {
         // `type_info` is only `None` for the `()` type
         let type_info = type_info.unwrap();
@@ -287,3 +1402,9 @@ impl Marshal<StructRef> for RawStruct {
         }
     }
 }
+
+impl<'s> MarshalArgument<&'s StructRef> for RawStruct {
+    fn marshal_to_ptr(value: RawStruct, ptr: NonNull<Self>, type_info: Option<&abi::TypeInfo>) {
+        <RawStruct as MarshalArgument<StructRef>>::marshal_to_ptr(value, ptr, type_info)
+    }
+}
@@ -0,0 +1,143 @@
+use crate::garbage_collector::GcPtr;
+use memory::gc::HasIndirectionPtr;
+use std::slice;
+
+/// Determines how the traversal of a struct graph continues after a [`StructVisitor`] callback.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Visit {
+    /// Continue the traversal
+    Continue,
+    /// Continue the traversal, but skip the contents of the struct or field that is being
+    /// entered. Equivalent to `Continue` for other callbacks.
+    Skip,
+    /// Stop the traversal
+    Break,
+}
+
+/// A visitor over the graph of a Mun struct, as traversed by [`StructRef::visit`].
+///
+/// Fields are visited in declaration order. Struct fields are visited recursively by value,
+/// regardless of their memory kind. A garbage collected struct that is referenced again while it
+/// is being visited - i.e. a cyclic reference - is not visited recursively, but reported through
+/// `visit_cycle` instead.
+///
+/// All callbacks default to continuing the traversal, so a visitor only needs to implement the
+/// callbacks it is interested in.
+///
+/// [`StructRef::visit`]: struct.StructRef.html#method.visit
+pub trait StructVisitor {
+    /// Called before the fields of a struct of type `type_info` are visited. Returning
+    /// `Visit::Skip` skips the struct's fields and the matching `leave_struct`.
+    fn enter_struct(&mut self, _type_info: &abi::TypeInfo) -> Visit {
+        Visit::Continue
+    }
+
+    /// Called before the field `name` of type `type_info` is visited. Returning `Visit::Skip`
+    /// skips the field's value.
+    fn enter_field(&mut self, _name: &str, _type_info: &abi::TypeInfo) -> Visit {
+        Visit::Continue
+    }
+
+    /// Called for the value of the fundamental-typed field `name`, whose memory is `bytes`.
+    fn visit_fundamental(
+        &mut self,
+        _name: &str,
+        _type_info: &abi::TypeInfo,
+        _bytes: &[u8],
+    ) -> Visit {
+        Visit::Continue
+    }
+
+    /// Called for a cyclic reference to a struct of type `type_info`, instead of visiting it
+    /// again. `depth` is the position of the referenced struct in the chain of garbage collected
+    /// structs that led to the reference, where the root struct has depth zero.
+    fn visit_cycle(&mut self, _type_info: &abi::TypeInfo, _depth: usize) -> Visit {
+        Visit::Continue
+    }
+
+    /// Called after the fields of a struct of type `type_info` have been visited.
+    fn leave_struct(&mut self, _type_info: &abi::TypeInfo) -> Visit {
+        Visit::Continue
+    }
+}
+
+/// Visits the struct of type `type_info` at `ptr`, where `path` is the chain of garbage collected
+/// structs that led to `ptr`. Returns `false` if the visitor stopped the traversal.
+///
+/// # Safety
+///
+/// `ptr` must point to the memory of a struct of type `type_info`.
+pub(crate) unsafe fn visit_struct(
+    ptr: *const u8,
+    type_info: &abi::TypeInfo,
+    path: &mut Vec<GcPtr>,
+    visitor: &mut dyn StructVisitor,
+) -> bool {
+    match visitor.enter_struct(type_info) {
+        Visit::Continue => (),
+        Visit::Skip => return true,
+        Visit::Break => return false,
+    }
+
+    // Safety: `visit_struct` is only called for struct types.
+    let struct_info = type_info.as_struct().unwrap();
+    for ((name, field_type), offset) in struct_info
+        .field_names()
+        .zip(struct_info.field_types().iter())
+        .zip(struct_info.field_offsets().iter())
+    {
+        match visitor.enter_field(name, field_type) {
+            Visit::Continue => (),
+            Visit::Skip => continue,
+            Visit::Break => return false,
+        }
+
+        if !visit_value(
+            ptr.add(usize::from(*offset)),
+            name,
+            field_type,
+            path,
+            visitor,
+        ) {
+            return false;
+        }
+    }
+
+    visitor.leave_struct(type_info) != Visit::Break
+}
+
+/// Visits the value of type `type_info` of field `name` at `ptr`. Returns `false` if the visitor
+/// stopped the traversal.
+///
+/// # Safety
+///
+/// `ptr` must point to a value of type `type_info`.
+unsafe fn visit_value(
+    ptr: *const u8,
+    name: &str,
+    type_info: &abi::TypeInfo,
+    path: &mut Vec<GcPtr>,
+    visitor: &mut dyn StructVisitor,
+) -> bool {
+    match type_info.as_struct() {
+        None => {
+            let bytes = slice::from_raw_parts(ptr, type_info.size_in_bytes());
+            visitor.visit_fundamental(name, type_info, bytes) != Visit::Break
+        }
+        Some(struct_info) if struct_info.memory_kind == abi::StructMemoryKind::Value => {
+            visit_struct(ptr, type_info, path, visitor)
+        }
+        Some(_) => {
+            // For a gc struct, the value is a `GcPtr`.
+            let handle = *ptr.cast::<GcPtr>();
+            if let Some(depth) = path.iter().position(|ptr| *ptr == handle) {
+                return visitor.visit_cycle(type_info, depth) != Visit::Break;
+            }
+
+            path.push(handle);
+            let completed = visit_struct(handle.deref::<u8>(), type_info, path, visitor);
+            path.pop();
+            completed
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use crate::{
+    garbage_collector::{GarbageCollector, GcPtr, GcRootPtr, UnsafeTypeInfo},
+    reflection::{equals_return_type, ReturnTypeReflection},
+};
+use memory::gc::{self, GcRuntime, HasIndirectionPtr};
+use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::{
+    ptr::NonNull,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+/// The state of a [`Runtime`] that is shared with worker threads.
+///
+/// # Synchronization contract
+///
+/// Worker threads only ever read struct memory, and they do so while holding a read lock. The
+/// runtime holds the write lock for the duration of every operation that can free, relocate, or
+/// modify struct memory, or invalidate type information:
+///
+/// * collecting garbage,
+/// * closing an allocation scope,
+/// * loading, unloading, or reloading an assembly,
+/// * setting fields through a [`StructRef`], and
+/// * dropping the runtime.
+///
+/// As an object referenced by a [`SyncStructRef`] is rooted, the collector never frees it. The
+/// objects that it references are kept alive by tracing, and are rooted before the read lock is
+/// released when they are accessed from a worker thread.
+///
+/// Loading, unloading, or reloading an assembly changes the generation of the runtime, which
+/// invalidates all existing [`SyncStructRef`]s and [`RuntimeSnapshot`]s.
+///
+/// [`RuntimeSnapshot`]: struct.RuntimeSnapshot.html
+///
+/// [`Runtime`]: struct.Runtime.html
+pub(crate) struct SyncState {
+    gc: Arc<GarbageCollector>,
+    lock: RwLock<()>,
+    generation: AtomicUsize,
+}
+
+impl SyncState {
+    /// Constructs the shared state of a runtime that uses the `gc` garbage collector.
+    pub fn new(gc: Arc<GarbageCollector>) -> Self {
+        Self {
+            gc,
+            lock: RwLock::new(()),
+            generation: AtomicUsize::new(0),
+        }
+    }
+
+    /// Acquires shared access to struct memory, blocking until the runtime releases its
+    /// exclusive access.
+    pub fn read(&self) -> RwLockReadGuard<'_, ()> {
+        self.lock.read()
+    }
+
+    /// Acquires exclusive access to struct memory, blocking until all worker threads have
+    /// released their shared access.
+    pub fn write(&self) -> RwLockWriteGuard<'_, ()> {
+        self.lock.write()
+    }
+
+    /// Returns the number of times that the runtime has loaded, unloaded, or reloaded an
+    /// assembly.
+    pub fn generation(&self) -> usize {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Increments the generation of the runtime. Must be called while holding the write lock.
+    pub fn bump_generation(&self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+/// A `Send + Sync` handle to a [`Runtime`] that provides read-only access from worker threads.
+///
+/// Created by [`Runtime::sync_handle`].
+///
+/// [`Runtime`]: struct.Runtime.html
+/// [`Runtime::sync_handle`]: struct.Runtime.html#method.sync_handle
+#[derive(Clone)]
+pub struct SyncRuntimeHandle {
+    state: Arc<SyncState>,
+}
+
+impl SyncRuntimeHandle {
+    pub(crate) fn new(state: Arc<SyncState>) -> Self {
+        Self { state }
+    }
+
+    /// Returns the number of times that the runtime has loaded, unloaded, or reloaded an
+    /// assembly. [`SyncStructRef`]s created in an earlier generation are stale.
+    pub fn generation(&self) -> usize {
+        self.state.generation()
+    }
+
+    /// Returns statistics about the garbage collector.
+    pub fn gc_stats(&self) -> gc::Stats {
+        self.state.gc.stats()
+    }
+}
+
+/// A `Send + Sync` wrapper for read-only access to a Mun struct from worker threads.
+///
+/// Created by [`StructRef::into_sync`]. The struct's memory is kept alive for as long as a
+/// `SyncStructRef` to it exists.
+///
+/// # Restrictions
+///
+/// * The struct cannot be mutated through a `SyncStructRef`.
+/// * Accessing a `SyncStructRef` never allocates memory or triggers garbage collection.
+/// * Accesses block while the runtime collects garbage, closes an allocation scope, reloads an
+///   assembly, or sets fields through a [`StructRef`].
+/// * When the runtime loads, unloads, or reloads an assembly, or is dropped, the `SyncStructRef`
+///   becomes stale and all accesses fail.
+/// * Mun functions invoked on the runtime's thread do not synchronize with worker threads, so
+///   they must not mutate structs that are shared with worker threads.
+///
+/// [`StructRef::into_sync`]: struct.StructRef.html#method.into_sync
+#[derive(Clone)]
+pub struct SyncStructRef {
+    root: GcRootPtr,
+    offset: usize,
+    type_info: UnsafeTypeInfo,
+    generation: usize,
+    state: Arc<SyncState>,
+}
+
+// Safety: All access to the struct's memory and type information is synchronized with the runtime
+// through the `SyncState`, as documented there.
+unsafe impl Send for SyncStructRef {}
+unsafe impl Sync for SyncStructRef {}
+
+impl SyncStructRef {
+    /// Creates a `SyncStructRef` for the rooted struct of type `type_info`.
+    pub(crate) fn new(
+        root: GcRootPtr,
+        type_info: UnsafeTypeInfo,
+        state: Arc<SyncState>,
+    ) -> SyncStructRef {
+        Self {
+            root,
+            offset: 0,
+            type_info,
+            generation: state.generation(),
+            state,
+        }
+    }
+
+    /// Returns a handle to the runtime that the struct belongs to.
+    pub fn runtime_handle(&self) -> SyncRuntimeHandle {
+        SyncRuntimeHandle::new(self.state.clone())
+    }
+
+    /// Returns whether the runtime has loaded, unloaded, or reloaded an assembly since the
+    /// `SyncStructRef` was created, in which case all accesses fail.
+    pub fn is_stale(&self) -> bool {
+        self.generation != self.state.generation()
+    }
+
+    /// Invokes `f` with the type information of the struct and a pointer to its memory, while
+    /// holding shared access to struct memory.
+    fn access<R>(
+        &self,
+        f: impl FnOnce(&abi::TypeInfo, *const u8) -> Result<R, String>,
+    ) -> Result<R, String> {
+        let _guard = self.state.read();
+        if self.is_stale() {
+            return Err(
+                "The struct is stale, as the runtime has loaded, unloaded, or reloaded an assembly."
+                    .to_string(),
+            );
+        }
+
+        // Safety: The runtime has not invalidated the type information or relocated the struct's
+        // memory, as we hold the read lock and the generation has not changed.
+        unsafe {
+            let type_info = &*self.type_info.into_inner().as_ptr();
+            let ptr = self.root.deref::<u8>().add(self.offset);
+            f(type_info, ptr)
+        }
+    }
+
+    /// Returns the name of the struct's type.
+    pub fn type_name(&self) -> Result<String, String> {
+        self.access(|type_info, _| Ok(type_info.name().to_string()))
+    }
+
+    /// Retrieves the value of the fundamental-typed field corresponding to the specified
+    /// `field_name`.
+    ///
+    /// Use [`get_struct`] to retrieve struct-typed fields.
+    ///
+    /// [`get_struct`]: #method.get_struct
+    pub fn get<T: ReturnTypeReflection<Marshalled = T> + Copy>(
+        &self,
+        field_name: &str,
+    ) -> Result<T, String> {
+        self.access(|type_info, ptr| {
+            // Safety: `as_struct` is guaranteed to return `Some` for `SyncStructRef`s.
+            let struct_info = type_info.as_struct().unwrap();
+            let field_idx =
+                abi::StructInfo::find_field_index(type_info.name(), struct_info, field_name)?;
+
+            // Safety: If we found the `field_idx`, we are guaranteed to also have the
+            // `field_type` and `field_offset`.
+            let (field_type, field_offset) = unsafe {
+                (
+                    struct_info.field_types().get_unchecked(field_idx),
+                    *struct_info.field_offsets().get_unchecked(field_idx),
+                )
+            };
+            equals_return_type::<T>(field_type).map_err(|(expected, found)| {
+                format!(
+                    "Mismatched types for `{}::{}`. Expected: `{}`. Found: `{}`.",
+                    type_info.name(),
+                    field_name,
+                    expected,
+                    found,
+                )
+            })?;
+
+            Ok(unsafe { ptr.add(usize::from(field_offset)).cast::<T>().read() })
+        })
+    }
+
+    /// Retrieves the struct-typed field corresponding to the specified `field_name`.
+    pub fn get_struct(&self, field_name: &str) -> Result<SyncStructRef, String> {
+        self.access(|type_info, ptr| {
+            // Safety: `as_struct` is guaranteed to return `Some` for `SyncStructRef`s.
+            let struct_info = type_info.as_struct().unwrap();
+            let field_idx =
+                abi::StructInfo::find_field_index(type_info.name(), struct_info, field_name)?;
+
+            // Safety: If we found the `field_idx`, we are guaranteed to also have the
+            // `field_type` and `field_offset`.
+            let (field_type, field_offset) = unsafe {
+                (
+                    *struct_info.field_types().get_unchecked(field_idx),
+                    usize::from(*struct_info.field_offsets().get_unchecked(field_idx)),
+                )
+            };
+            let field_struct_info = field_type.as_struct().ok_or_else(|| {
+                format!(
+                    "Mismatched types for `{}::{}`. Expected: `struct`. Found: `{}`.",
+                    type_info.name(),
+                    field_name,
+                    field_type.name(),
+                )
+            })?;
+
+            // Safety: `field_type` is a shared reference, so is guaranteed to not be
+            // `ptr::null()`.
+            let field_type = UnsafeTypeInfo::new(unsafe {
+                NonNull::new_unchecked(field_type as *const abi::TypeInfo as *mut _)
+            });
+            if field_struct_info.memory_kind == abi::StructMemoryKind::Value {
+                // A value struct is stored inline, so it is kept alive by our root.
+                Ok(SyncStructRef {
+                    root: self.root.clone(),
+                    offset: self.offset + field_offset,
+                    type_info: field_type,
+                    generation: self.generation,
+                    state: self.state.clone(),
+                })
+            } else {
+                // A gc struct is referenced by a `GcPtr`, which we root before releasing the read
+                // lock, so the collector cannot free it in the meantime.
+                let handle = unsafe { *ptr.add(field_offset).cast::<GcPtr>() };
+                Ok(SyncStructRef {
+                    root: GcRootPtr::new(&self.state.gc, handle),
+                    offset: 0,
+                    type_info: self.state.gc.ptr_type(handle),
+                    generation: self.generation,
+                    state: self.state.clone(),
+                })
+            }
+        })
+    }
+}
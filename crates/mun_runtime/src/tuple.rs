@@ -0,0 +1,204 @@
+use crate::{
+    garbage_collector::UnsafeTypeInfo,
+    marshal::{Marshal, MarshalArgument, MarshalError},
+    reflection::{equals_return_type, ArgumentReflection, ReturnTypeReflection},
+    struct_ref::RawStruct,
+    Runtime, StructRef,
+};
+use memory::gc::{GcRuntime, HasIndirectionPtr};
+use std::{cell::RefCell, ptr::NonNull, rc::Rc};
+
+/// The name of the type that tuples report to the reflection system.
+const TUPLE_TYPE_NAME: &str = "tuple";
+
+/// Returns the field types and offsets of `type_info`, if it is a struct type with exactly `arity`
+/// fields.
+pub(crate) fn tuple_fields(
+    type_info: &abi::TypeInfo,
+    arity: usize,
+) -> Option<(&[&abi::TypeInfo], &[u16])> {
+    type_info
+        .as_struct()
+        .filter(|struct_info| usize::from(struct_info.num_fields) == arity)
+        .map(|struct_info| (struct_info.field_types(), struct_info.field_offsets()))
+}
+
+/// Returns a pointer to the memory of the struct of type `type_info` that `ptr` points to. If
+/// `type_info` is not a struct type, `ptr` is returned as is.
+///
+/// # Safety
+///
+/// `ptr` must point to a struct value or a `GcPtr`, depending on the memory kind of `type_info`.
+pub(crate) unsafe fn struct_data_ptr(
+    ptr: NonNull<RawStruct>,
+    type_info: &abi::TypeInfo,
+) -> *const u8 {
+    match type_info.as_struct() {
+        // For a gc struct, `ptr` points to a `GcPtr`.
+        Some(struct_info) if struct_info.memory_kind == abi::StructMemoryKind::GC => {
+            ptr.as_ref().get_ptr()
+        }
+        // For a value struct, `ptr` points to a struct value.
+        _ => ptr.cast::<u8>().as_ptr(),
+    }
+}
+
+/// A tuple that can be read from the memory of a Mun struct.
+trait FromStructData: Sized {
+    /// Reads the fields of the struct of type `type_info`, whose memory starts at `ptr`, or
+    /// returns an error if the fields of the struct do not match those of the tuple.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to the memory of a struct of type `type_info`.
+    unsafe fn from_struct_data(
+        ptr: *const u8,
+        runtime: Rc<RefCell<Runtime>>,
+        type_info: &abi::TypeInfo,
+    ) -> Result<Self, MarshalError>;
+}
+
+macro_rules! impl_tuple {
+    ($($arity:literal => ($($T:ident $idx:tt),+);)+) => {
+        $(
+            impl<$($T: ArgumentReflection),+> ArgumentReflection for ($($T,)+) {
+                type Marshalled = RawStruct;
+
+                fn type_guid(&self, _runtime: &Runtime) -> abi::Guid {
+                    abi::Guid {
+                        b: md5::compute(TUPLE_TYPE_NAME).0,
+                    }
+                }
+
+                fn type_name(&self, _runtime: &Runtime) -> &str {
+                    TUPLE_TYPE_NAME
+                }
+
+                fn is_compatible(&self, runtime: &Runtime, type_info: &abi::TypeInfo) -> bool {
+                    match tuple_fields(type_info, $arity) {
+                        Some((field_types, _)) => {
+                            $(self.$idx.is_compatible(runtime, field_types[$idx]))&&+
+                        }
+                        None => false,
+                    }
+                }
+
+                fn marshal(self, runtime: &Runtime, type_info: &abi::TypeInfo) -> Self::Marshalled {
+                    let (field_types, field_offsets) = tuple_fields(type_info, $arity)
+                        .expect("the tuple's type should have been validated");
+
+                    let mut gc_handle = runtime.gc().alloc(UnsafeTypeInfo::new(NonNull::from(type_info)));
+                    let ptr = unsafe { gc_handle.deref_mut::<u8>() };
+                    $(
+                        let field_type = field_types[$idx];
+                        // Safety: The field offsets of a struct lie within its allocated memory.
+                        let field_ptr = unsafe {
+                            NonNull::new_unchecked(ptr.add(usize::from(field_offsets[$idx])))
+                        };
+                        MarshalArgument::marshal_to_ptr(
+                            self.$idx.marshal(runtime, field_type),
+                            field_ptr.cast::<<$T as ArgumentReflection>::Marshalled>(),
+                            Some(field_type),
+                        );
+                    )+
+
+                    RawStruct(gc_handle)
+                }
+            }
+
+            impl<$($T: ReturnTypeReflection),+> ReturnTypeReflection for ($($T,)+) {
+                type Marshalled = RawStruct;
+
+                fn type_name() -> &'static str {
+                    TUPLE_TYPE_NAME
+                }
+
+                fn accepts_struct(type_info: &abi::TypeInfo) -> bool {
+                    match tuple_fields(type_info, $arity) {
+                        Some((field_types, _)) => {
+                            $(equals_return_type::<$T>(field_types[$idx]).is_ok())&&+
+                        }
+                        None => false,
+                    }
+                }
+            }
+
+            impl<$($T: ReturnTypeReflection),+> FromStructData for ($($T,)+) {
+                unsafe fn from_struct_data(
+                    ptr: *const u8,
+                    runtime: Rc<RefCell<Runtime>>,
+                    type_info: &abi::TypeInfo,
+                ) -> Result<Self, MarshalError> {
+                    let (field_types, field_offsets) = match tuple_fields(type_info, $arity) {
+                        Some(fields) if Self::accepts_struct(type_info) => fields,
+                        _ => {
+                            let element_names = [$(<$T as ReturnTypeReflection>::type_name()),+];
+                            return Err(MarshalError::LayoutMismatch {
+                                expected: format!("({})", element_names.join(", ")),
+                                found: type_info.name().to_string(),
+                            });
+                        }
+                    };
+
+                    Ok(($({
+                        let field_type = field_types[$idx];
+                        let field_ptr = NonNull::new_unchecked(
+                            ptr.add(usize::from(field_offsets[$idx])) as *mut u8
+                        );
+                        Marshal::marshal_from_ptr(
+                            field_ptr.cast::<<$T as ReturnTypeReflection>::Marshalled>(),
+                            runtime.clone(),
+                            Some(field_type),
+                        )?
+                    },)+))
+                }
+            }
+
+            impl<$($T: ReturnTypeReflection),+> Marshal<($($T,)+)> for RawStruct {
+                fn marshal_value(self, runtime: Rc<RefCell<Runtime>>) -> Result<($($T,)+), MarshalError> {
+                    // Safety: The type returned from `ptr_type` is guaranteed to live at least as
+                    // long as `Runtime` does not change, which it does not during marshalling.
+                    let type_info = unsafe {
+                        &*runtime.borrow().gc().ptr_type(self.0).into_inner().as_ptr()
+                    };
+
+                    // A returned struct is always passed as a `GcPtr`, regardless of its memory kind
+                    unsafe { FromStructData::from_struct_data(self.get_ptr(), runtime, type_info) }
+                }
+
+                fn marshal_from_ptr(
+                    ptr: NonNull<Self>,
+                    runtime: Rc<RefCell<Runtime>>,
+                    type_info: Option<&abi::TypeInfo>,
+                ) -> Result<($($T,)+), MarshalError> {
+                    // `type_info` is only `None` for the `()` type
+                    let type_info = type_info.unwrap();
+                    unsafe {
+                        FromStructData::from_struct_data(
+                            struct_data_ptr(ptr, type_info),
+                            runtime,
+                            type_info,
+                        )
+                    }
+                }
+            }
+
+            impl<$($T: ArgumentReflection),+> MarshalArgument<($($T,)+)> for RawStruct {
+                fn marshal_to_ptr(value: RawStruct, ptr: NonNull<Self>, type_info: Option<&abi::TypeInfo>) {
+                    <RawStruct as MarshalArgument<StructRef>>::marshal_to_ptr(value, ptr, type_info)
+                }
+            }
+        )+
+    }
+}
+
+impl_tuple! {
+    1 => (A 0);
+    2 => (A 0, B 1);
+    3 => (A 0, B 1, C 2);
+    4 => (A 0, B 1, C 2, D 3);
+    5 => (A 0, B 1, C 2, D 3, E 4);
+    6 => (A 0, B 1, C 2, D 3, E 4, F 5);
+    7 => (A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+    8 => (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+}
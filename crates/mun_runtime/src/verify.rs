@@ -0,0 +1,376 @@
+use std::{collections::HashSet, fmt, mem};
+
+/// A problem with the ABI data of a loaded module, as found by [`Runtime::verify_module`].
+///
+/// [`Runtime::verify_module`]: struct.Runtime.html#method.verify_module
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ModuleError {
+    /// The type of `location` is a null pointer.
+    MissingType {
+        /// The function argument, return type, or struct field whose type is missing
+        location: String,
+    },
+    /// The type of `location` is a struct type that no loaded module defines.
+    UnknownType {
+        /// The function argument, return type, or struct field whose type is unknown
+        location: String,
+        /// The name of the unknown type
+        type_name: String,
+    },
+    /// The alignment of a type is not a power of two.
+    InvalidAlignment {
+        /// The name of the type
+        type_name: String,
+        /// The alignment of the type, in bytes
+        alignment: u8,
+    },
+    /// A struct field starts before the end of the preceding field.
+    OverlappingField {
+        /// The name of the struct type
+        type_name: String,
+        /// The name of the field
+        field_name: String,
+    },
+    /// A struct field does not fit within the size of its struct.
+    FieldOutOfBounds {
+        /// The name of the struct type
+        type_name: String,
+        /// The name of the field
+        field_name: String,
+    },
+}
+
+impl fmt::Display for ModuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModuleError::MissingType { location } => {
+                write!(f, "The type of {} is missing.", location)
+            }
+            ModuleError::UnknownType {
+                location,
+                type_name,
+            } => write!(f, "The type `{}` of {} is unknown.", type_name, location),
+            ModuleError::InvalidAlignment {
+                type_name,
+                alignment,
+            } => write!(
+                f,
+                "The alignment of `{}` is not a power of two. Found: {}.",
+                type_name, alignment
+            ),
+            ModuleError::OverlappingField {
+                type_name,
+                field_name,
+            } => write!(
+                f,
+                "Field `{}::{}` overlaps the preceding field.",
+                type_name, field_name
+            ),
+            ModuleError::FieldOutOfBounds {
+                type_name,
+                field_name,
+            } => write!(
+                f,
+                "Field `{}::{}` exceeds the size of `{}`.",
+                type_name, field_name, type_name
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ModuleError {}
+
+/// Verifies the ABI data of the module described by `info`, appending all problems to `errors`.
+/// Struct types are known if their `Guid` is in `struct_types`.
+pub(crate) fn verify_assembly(
+    info: &abi::AssemblyInfo,
+    struct_types: &HashSet<abi::Guid>,
+    errors: &mut Vec<ModuleError>,
+) {
+    for function in info.symbols.functions() {
+        let name = function.prototype.name();
+        let signature = &function.prototype.signature;
+        for index in 0..usize::from(signature.num_arg_types) {
+            // Safety: `arg_types` points to `num_arg_types` type pointers.
+            let arg_type = unsafe { *signature.arg_types.add(index) };
+            let location = format!("argument {} of function `{}`", index, name);
+            verify_type_ref(arg_type, location, struct_types, errors);
+        }
+
+        // A missing return type denotes the `()` type
+        if !signature.return_type.is_null() {
+            let location = format!("the return type of function `{}`", name);
+            verify_type_ref(signature.return_type, location, struct_types, errors);
+        }
+    }
+
+    for type_info in info.symbols.types() {
+        verify_type(type_info, struct_types, errors);
+    }
+}
+
+/// Verifies the layout of `type_info`, and that the types of its fields are known.
+fn verify_type(
+    type_info: &abi::TypeInfo,
+    struct_types: &HashSet<abi::Guid>,
+    errors: &mut Vec<ModuleError>,
+) {
+    if !type_info.alignment.is_power_of_two() {
+        errors.push(ModuleError::InvalidAlignment {
+            type_name: type_info.name().to_string(),
+            alignment: type_info.alignment,
+        });
+    }
+
+    let struct_info = match type_info.as_struct() {
+        Some(struct_info) => struct_info,
+        None => return,
+    };
+
+    let mut end = 0;
+    for (index, (field_name, offset)) in struct_info
+        .field_names()
+        .zip(struct_info.field_offsets().iter())
+        .enumerate()
+    {
+        let offset = usize::from(*offset);
+        if offset < end {
+            errors.push(ModuleError::OverlappingField {
+                type_name: type_info.name().to_string(),
+                field_name: field_name.to_string(),
+            });
+        }
+
+        // Safety: `field_types` points to `num_fields` type pointers.
+        let field_type = unsafe { *struct_info.field_types.add(index) };
+        let location = format!("field `{}::{}`", type_info.name(), field_name);
+        if !verify_type_ref(field_type, location, struct_types, errors) {
+            continue;
+        }
+
+        // Safety: `verify_type_ref` returns `true` only for non-null pointers.
+        let field_type = unsafe { &*field_type };
+        let size = match field_type.as_struct() {
+            Some(s) if s.memory_kind == abi::StructMemoryKind::GC => mem::size_of::<*const u8>(),
+            _ => field_type.size_in_bytes(),
+        };
+        end = offset + size;
+        if end > type_info.size_in_bytes() {
+            errors.push(ModuleError::FieldOutOfBounds {
+                type_name: type_info.name().to_string(),
+                field_name: field_name.to_string(),
+            });
+        }
+    }
+}
+
+/// Verifies that `type_info`, the type of `location`, is not null, and that it is known if it is
+/// a struct type. Returns whether the type is not null.
+fn verify_type_ref(
+    type_info: *const abi::TypeInfo,
+    location: String,
+    struct_types: &HashSet<abi::Guid>,
+    errors: &mut Vec<ModuleError>,
+) -> bool {
+    // Safety: Type pointers either are null, or point to valid type information.
+    let type_info = match unsafe { type_info.as_ref() } {
+        Some(type_info) => type_info,
+        None => {
+            errors.push(ModuleError::MissingType { location });
+            return false;
+        }
+    };
+
+    if type_info.group.is_struct() && !struct_types.contains(&type_info.guid) {
+        errors.push(ModuleError::UnknownType {
+            location,
+            type_name: type_info.name().to_string(),
+        });
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::{CStr, CString};
+    use std::os::raw::c_char;
+    use std::ptr;
+
+    /// A dummy struct for initializing a struct's `TypeInfo`
+    #[repr(C)]
+    struct StructTypeInfo {
+        type_info: abi::TypeInfo,
+        struct_info: abi::StructInfo,
+    }
+
+    const FAKE_TYPE_GUID: abi::Guid = abi::Guid {
+        b: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    };
+
+    fn fake_type_info(
+        name: &CStr,
+        group: abi::TypeGroup,
+        size: u32,
+        alignment: u8,
+    ) -> abi::TypeInfo {
+        abi::TypeInfo {
+            guid: FAKE_TYPE_GUID,
+            name: name.as_ptr(),
+            size_in_bits: size,
+            alignment,
+            group,
+        }
+    }
+
+    fn fake_struct_type_info(
+        name: &CStr,
+        field_names: &[*const c_char],
+        field_types: &[*const abi::TypeInfo],
+        field_offsets: &[u16],
+        size: u32,
+    ) -> StructTypeInfo {
+        StructTypeInfo {
+            type_info: fake_type_info(name, abi::TypeGroup::StructTypes, size, 8),
+            struct_info: abi::StructInfo {
+                field_names: field_names.as_ptr(),
+                field_types: field_types.as_ptr(),
+                field_offsets: field_offsets.as_ptr(),
+                field_defaults: ptr::null(),
+                field_docs: ptr::null(),
+                num_fields: field_names.len() as u16,
+                memory_kind: abi::StructMemoryKind::GC,
+                magic: abi::STRUCT_INFO_MAGIC,
+            },
+        }
+    }
+
+    /// Verifies `type_info`, for which only the struct types in `struct_types` are known, and
+    /// returns the problems that were found.
+    fn verify(type_info: &abi::TypeInfo, struct_types: &[abi::Guid]) -> Vec<ModuleError> {
+        let struct_types: HashSet<abi::Guid> = struct_types.iter().cloned().collect();
+        let mut errors = Vec::new();
+        verify_type(type_info, &struct_types, &mut errors);
+        errors
+    }
+
+    #[test]
+    fn test_verify_valid_struct() {
+        let i32_type_info: *const abi::TypeInfo = <i32 as abi::HasStaticTypeInfo>::type_info();
+        let struct_name = CString::new("Foo").unwrap();
+        let field_names = [CString::new("a").unwrap(), CString::new("b").unwrap()];
+        let field_name_ptrs: Vec<_> = field_names.iter().map(|name| name.as_ptr()).collect();
+        let struct_type_info = fake_struct_type_info(
+            &struct_name,
+            &field_name_ptrs,
+            &[i32_type_info, i32_type_info],
+            &[0, 4],
+            64,
+        );
+
+        assert!(verify(&struct_type_info.type_info, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_verify_missing_type() {
+        let struct_name = CString::new("Foo").unwrap();
+        let field_name = CString::new("a").unwrap();
+        let struct_type_info = fake_struct_type_info(
+            &struct_name,
+            &[field_name.as_ptr()],
+            &[ptr::null()],
+            &[0],
+            32,
+        );
+
+        assert_eq!(
+            verify(&struct_type_info.type_info, &[]),
+            [ModuleError::MissingType {
+                location: "field `Foo::a`".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_verify_unknown_type() {
+        let bar_name = CString::new("Bar").unwrap();
+        let bar_type_info = fake_struct_type_info(&bar_name, &[], &[], &[], 0);
+        let struct_name = CString::new("Foo").unwrap();
+        let field_name = CString::new("a").unwrap();
+        let struct_type_info = fake_struct_type_info(
+            &struct_name,
+            &[field_name.as_ptr()],
+            &[&bar_type_info.type_info as *const _],
+            &[0],
+            64,
+        );
+
+        assert_eq!(
+            verify(&struct_type_info.type_info, &[]),
+            [ModuleError::UnknownType {
+                location: "field `Foo::a`".to_string(),
+                type_name: "Bar".to_string()
+            }]
+        );
+        assert!(verify(&struct_type_info.type_info, &[bar_type_info.type_info.guid]).is_empty());
+    }
+
+    #[test]
+    fn test_verify_invalid_alignment() {
+        let type_name = CString::new("Foo").unwrap();
+        let type_info = fake_type_info(&type_name, abi::TypeGroup::FundamentalTypes, 24, 3);
+
+        assert_eq!(
+            verify(&type_info, &[]),
+            [ModuleError::InvalidAlignment {
+                type_name: "Foo".to_string(),
+                alignment: 3
+            }]
+        );
+    }
+
+    #[test]
+    fn test_verify_overlapping_field() {
+        let i32_type_info: *const abi::TypeInfo = <i32 as abi::HasStaticTypeInfo>::type_info();
+        let struct_name = CString::new("Foo").unwrap();
+        let field_names = [CString::new("a").unwrap(), CString::new("b").unwrap()];
+        let field_name_ptrs: Vec<_> = field_names.iter().map(|name| name.as_ptr()).collect();
+        let struct_type_info = fake_struct_type_info(
+            &struct_name,
+            &field_name_ptrs,
+            &[i32_type_info, i32_type_info],
+            &[0, 2],
+            64,
+        );
+
+        assert_eq!(
+            verify(&struct_type_info.type_info, &[]),
+            [ModuleError::OverlappingField {
+                type_name: "Foo".to_string(),
+                field_name: "b".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_verify_field_out_of_bounds() {
+        let i64_type_info: *const abi::TypeInfo = <i64 as abi::HasStaticTypeInfo>::type_info();
+        let struct_name = CString::new("Foo").unwrap();
+        let field_name = CString::new("a").unwrap();
+        let struct_type_info = fake_struct_type_info(
+            &struct_name,
+            &[field_name.as_ptr()],
+            &[i64_type_info],
+            &[4],
+            64,
+        );
+
+        assert_eq!(
+            verify(&struct_type_info.type_info, &[]),
+            [ModuleError::FieldOutOfBounds {
+                type_name: "Foo".to_string(),
+                field_name: "a".to_string()
+            }]
+        );
+    }
+}
@@ -1,6 +1,8 @@
 #[macro_use]
 mod util;
 
+use mun_runtime::{invoke_fn, invoke_handle, FnHandle, ReloadPhase, SignatureError, StructRef};
+use std::{cell::RefCell, rc::Rc};
 use util::*;
 
 #[test]
@@ -19,6 +21,232 @@ fn hotreloadable() {
     assert_invoke_eq!(i32, 10, driver, "main");
 }
 
+#[test]
+fn hotreload_callbacks() {
+    let mut driver = TestDriver::new(
+        r"
+    pub fn main() -> i32 { 5 }
+    ",
+    );
+
+    let phases = Rc::new(RefCell::new(Vec::new()));
+    let phases_clone = phases.clone();
+    let file_name = driver.out_path().file_name().unwrap().to_os_string();
+    driver
+        .runtime_mut()
+        .borrow_mut()
+        .on_reload(move |phase, path| {
+            assert_eq!(path.file_name(), Some(file_name.as_os_str()));
+            phases_clone.borrow_mut().push(phase);
+        });
+
+    driver.update(
+        r"
+    pub fn main() -> i32 { 10 }
+    ",
+    );
+    assert_eq!(
+        *phases.borrow(),
+        [ReloadPhase::Before, ReloadPhase::After(Ok(()))]
+    );
+    assert_invoke_eq!(i32, 10, driver, "main");
+}
+
+#[test]
+fn hotreloadable_fn_handle() {
+    let mut driver = TestDriver::new(
+        r"
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+    ",
+    );
+
+    let mut handle: FnHandle<(i32, i32), i32> =
+        driver.runtime_mut().borrow().resolve("add").unwrap();
+    assert_eq!(
+        invoke_handle!(driver.runtime_mut(), handle, 2, 3).unwrap(),
+        5
+    );
+
+    assert_eq!(
+        driver
+            .runtime_mut()
+            .borrow()
+            .resolve::<(i32,), i32>("add")
+            .err(),
+        Some(SignatureError::ArgumentCount {
+            expected: 2,
+            found: 1
+        })
+    );
+
+    driver.update(
+        r"
+    pub fn add(a: i32, b: i32) -> i32 { a + b + 1 }
+    ",
+    );
+    assert!(handle.is_stale(&driver.runtime_mut().borrow()));
+    assert_eq!(
+        invoke_handle!(driver.runtime_mut(), handle, 2, 3).unwrap(),
+        6
+    );
+    assert!(!handle.is_stale(&driver.runtime_mut().borrow()));
+
+    driver.update(
+        r"
+    pub fn add(a: i32, b: i64) -> i32 { a }
+    ",
+    );
+    assert_eq!(
+        invoke_handle!(driver.runtime_mut(), handle, 2, 3).err(),
+        Some(SignatureError::ArgumentType {
+            index: 1,
+            expected: "core::i64".to_string(),
+            found: "core::i32".to_string(),
+        })
+    );
+}
+
+#[test]
+fn hotreloadable_snapshot() {
+    let mut driver = TestDriver::new(
+        r"
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+    ",
+    );
+
+    let snapshot = driver.runtime_mut().borrow().clone_for_thread();
+    assert!(snapshot.contains_fn("add"));
+    let result = std::thread::spawn(move || {
+        let result: Result<i32, String> = snapshot.invoke_fn2("add", 2i32, 3i32);
+        assert_eq!(
+            snapshot.invoke_fn2::<i32, i64, i32>("add", 2, 3).err(),
+            Some(
+                "Invalid argument type at index 1. Expected: core::i32. Found: core::i64."
+                    .to_string()
+            )
+        );
+        (result, snapshot)
+    })
+    .join()
+    .unwrap();
+    let (result, snapshot) = result;
+    assert_eq!(result, Ok(5));
+    assert!(!snapshot.is_stale());
+
+    driver.update(
+        r"
+    pub fn add(a: i32, b: i32) -> i32 { a + b + 1 }
+    ",
+    );
+    assert!(snapshot.is_stale());
+    assert_eq!(
+        snapshot.invoke_fn2::<i32, i32, i32>("add", 2, 3),
+        Err(
+            "The snapshot is stale, as the runtime has loaded, unloaded, or reloaded an assembly."
+                .to_string()
+        )
+    );
+
+    let snapshot = driver.runtime_mut().borrow().clone_for_thread();
+    assert_eq!(snapshot.invoke_fn2::<i32, i32, i32>("add", 2, 3), Ok(6));
+}
+
+#[test]
+fn hotreloadable_sync_struct_ref() {
+    let mut driver = TestDriver::new(
+        r"
+    struct Foo { a: i32 }
+
+    pub fn foo_new() -> Foo { Foo { a: 5 } }
+    ",
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new").unwrap();
+    let foo = foo.into_sync();
+    let runtime_handle = foo.runtime_handle();
+    assert_eq!(foo.get::<i32>("a"), Ok(5));
+    assert!(!foo.is_stale());
+
+    let generation = runtime_handle.generation();
+    driver.update(
+        r"
+    struct Foo { a: i32, b: i32 }
+
+    pub fn foo_new() -> Foo { Foo { a: 5, b: 6 } }
+    ",
+    );
+    assert_ne!(runtime_handle.generation(), generation);
+    assert!(foo.is_stale());
+    assert_eq!(
+        foo.get::<i32>("a"),
+        Err(
+            "The struct is stale, as the runtime has loaded, unloaded, or reloaded an assembly."
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn hotreload_pinned_struct() {
+    let mut driver = TestDriver::new(
+        r"
+    struct Foo { a: i32 }
+
+    pub fn foo_new() -> Foo { Foo { a: 5 } }
+    ",
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new").unwrap();
+    let pinned = foo.pin();
+    assert_eq!(unsafe { *pinned.as_ptr().cast::<i32>() }, 5);
+
+    // Pinning does not defer reloads
+    driver.update(
+        r"
+    struct Foo { b: i64, a: i32 }
+
+    pub fn foo_new() -> Foo { Foo { b: 6, a: 5 } }
+    ",
+    );
+    assert_eq!(pinned.struct_ref().get::<i32>("a"), Ok(5));
+    assert_eq!(pinned.struct_ref().get::<i64>("b"), Ok(0));
+}
+
+#[test]
+fn hotreload_type_guid() {
+    let mut driver = TestDriver::new(
+        r"
+    struct Foo { a: i32 }
+    struct Bar { a: i32 }
+
+    pub fn foo_new() -> Foo { Foo { a: 5 } }
+    pub fn bar_new() -> Bar { Bar { a: 5 } }
+    ",
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new").unwrap();
+    let foo_guid = driver.runtime_mut().borrow().type_guid("Foo").unwrap();
+    let bar_guid = driver.runtime_mut().borrow().type_guid("Bar").unwrap();
+    assert_eq!(
+        foo_guid,
+        StructRef::type_info(&foo, &driver.runtime_mut().borrow()).guid
+    );
+    assert_eq!(driver.runtime_mut().borrow().type_guid("Baz"), None);
+
+    driver.update(
+        r"
+    struct Foo { a: i32, b: i32 }
+    struct Bar { a: i32 }
+
+    pub fn foo_new() -> Foo { Foo { a: 5, b: 6 } }
+    pub fn bar_new() -> Bar { Bar { a: 5 } }
+    ",
+    );
+    let runtime = driver.runtime_mut().borrow();
+    assert_ne!(runtime.type_guid("Foo"), Some(foo_guid));
+    assert_eq!(runtime.type_guid("Bar"), Some(bar_guid));
+}
+
 #[test]
 fn hotreload_struct_decl() {
     let mut driver = TestDriver::new(
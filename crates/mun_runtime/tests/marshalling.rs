@@ -1,6 +1,10 @@
 use mun_runtime::{
-    invoke_fn, ArgumentReflection, RetryResultExt, ReturnTypeReflection, Runtime, StructRef,
+    impl_enum_reflection, invoke_fn, ArgumentReflection, BitFlags, DynArgument, DynReturnType,
+    DynValue, FieldError, FieldLayout, Flag, MarshalError, MunResult, NonFinitePolicy, OpaquePtr,
+    RetryResultExt, ReturnTypeReflection, Runtime, SignatureError, StructBuilder, StructRef,
+    StructVisitor, SyncStructRef, TypeMismatch, Visit,
 };
+use std::{cell::RefCell, cmp::Ordering, ffi::c_void, io, ptr::NonNull, rc::Rc, time::Duration};
 
 #[macro_use]
 mod util;
@@ -17,6 +21,323 @@ fn compile_and_run() {
     assert_invoke_eq!((), (), driver, "main");
 }
 
+#[test]
+fn unit_return_type() {
+    let mut driver = TestDriver::new(
+        r"
+        pub fn empty() {}
+        pub fn value() -> i32 { 3 }
+    ",
+    );
+    assert_invoke_eq!((), (), driver, "empty");
+
+    let result: Result<i32, _> = invoke_fn!(driver.runtime_mut(), "empty");
+    assert!(result.is_err());
+
+    let result: Result<(), _> = invoke_fn!(driver.runtime_mut(), "value");
+    assert!(result.is_err());
+}
+
+#[test]
+fn duration() {
+    let mut driver = TestDriver::new(
+        r"
+        pub fn double(dt: u64) -> u64 { dt * 2 }
+    ",
+    );
+
+    let dt = Duration::from_millis(16);
+    let result: Duration = invoke_fn!(driver.runtime_mut(), "double", dt).unwrap();
+    assert_eq!(result, dt * 2);
+
+    let result: u64 = invoke_fn!(driver.runtime_mut(), "double", dt).unwrap();
+    assert_eq!(result, 32_000_000);
+
+    // Durations that do not fit in a `u64` number of nanoseconds saturate
+    let result: Duration = invoke_fn!(
+        driver.runtime_mut(),
+        "double",
+        Duration::from_secs(u64::MAX)
+    )
+    .unwrap();
+    assert_eq!(result, Duration::from_nanos(u64::MAX.wrapping_mul(2)));
+}
+
+#[test]
+fn ordering() {
+    let mut driver = TestDriver::new(
+        r"
+        pub fn compare(a: i32, b: i32) -> i8 {
+            if a < b { -1 } else if a > b { 1 } else { 0 }
+        }
+        pub fn reverse(ordering: i8) -> i8 { 0 - ordering }
+    ",
+    );
+
+    let mut values = [5, -3, 8, 0, 2];
+    values.sort_by(|a, b| -> Ordering {
+        invoke_fn!(driver.runtime_mut(), "compare", *a, *b).unwrap()
+    });
+    assert_eq!(values, [-3, 0, 2, 5, 8]);
+
+    let result: Ordering = invoke_fn!(driver.runtime_mut(), "reverse", Ordering::Less).unwrap();
+    assert_eq!(result, Ordering::Greater);
+}
+
+#[test]
+fn opaque_ptr() {
+    let mut driver = TestDriver::new(
+        r"
+        struct Entity { handle: usize }
+
+        pub fn entity_new(handle: usize) -> Entity { Entity { handle } }
+        pub fn entity_handle(entity: Entity) -> usize { entity.handle }
+    ",
+    );
+
+    let mut value = 5u64;
+    let handle = OpaquePtr::from((&mut value as *mut u64).cast::<c_void>());
+    let entity: StructRef = invoke_fn!(driver.runtime_mut(), "entity_new", handle).unwrap();
+    let result: OpaquePtr = invoke_fn!(driver.runtime_mut(), "entity_handle", entity).unwrap();
+    assert_eq!(result, handle);
+    assert_eq!(unsafe { *result.as_ptr().cast::<u64>() }, 5);
+}
+
+#[test]
+fn ordering_invalid() {
+    let mut driver = TestDriver::new(
+        r"
+        pub fn invalid() -> i8 { 2 }
+    ",
+    );
+
+    let result: Result<Ordering, _> = invoke_fn!(driver.runtime_mut(), "invalid");
+    let err = result.unwrap_err();
+    assert!(!err.is_retriable());
+    assert_eq!(
+        err.to_string(),
+        "Invalid discriminant `2` of type `core::cmp::Ordering`."
+    );
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Options {
+    Verbose = 0,
+    DryRun = 1,
+    Force = 40,
+}
+
+impl Flag for Options {
+    fn bit_index(self) -> u32 {
+        self as u32
+    }
+}
+
+#[test]
+fn bit_flags() {
+    let mut driver = TestDriver::new(
+        r"
+        pub fn with_dry_run(flags: u32) -> u32 { flags | 2 }
+        pub fn with_force(flags: u64) -> u64 { flags | (1 << 40) }
+        pub fn is_verbose(flags: u32) -> bool { flags & 1 != 0 }
+    ",
+    );
+
+    let flags: BitFlags<Options> = [Options::Verbose].iter().copied().collect();
+    let result: BitFlags<Options> =
+        invoke_fn!(driver.runtime_mut(), "with_dry_run", flags).unwrap();
+    assert!(result.contains(Options::Verbose));
+    assert!(result.contains(Options::DryRun));
+    assert_eq!(result.bits(), 0b11);
+
+    let is_verbose: bool = invoke_fn!(driver.runtime_mut(), "is_verbose", flags).unwrap();
+    assert!(is_verbose);
+
+    let mut flags = BitFlags::<Options, u64>::empty();
+    flags.set(Options::DryRun, true);
+    let result: BitFlags<Options, u64> =
+        invoke_fn!(driver.runtime_mut(), "with_force", flags).unwrap();
+    assert!(result.contains(Options::Force));
+    assert!(result.contains(Options::DryRun));
+    assert!(!result.contains(Options::Verbose));
+
+    // The ABI type of the bitmask must match
+    let result: Result<BitFlags<Options, u64>, _> =
+        invoke_fn!(driver.runtime_mut(), "with_dry_run", flags);
+    assert!(result.is_err());
+}
+
+#[test]
+#[should_panic(expected = "Flag index `40` does not fit in a `core::u32`.")]
+fn bit_flags_out_of_range() {
+    let mut flags = BitFlags::<Options>::empty();
+    flags.insert(Options::Force);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(i64)]
+enum Direction {
+    North,
+    East,
+    South,
+    West = 10,
+}
+
+impl_enum_reflection!(Direction: i64 { North, East, South, West });
+
+#[test]
+fn enum_discriminant() {
+    let mut driver = TestDriver::new(
+        r"
+        pub fn turn(direction: i64) -> i64 {
+            if direction == 2 { 10 } else if direction == 10 { 0 } else { direction + 1 }
+        }
+    ",
+    );
+
+    let mut direction = Direction::North;
+    let mut directions = Vec::new();
+    for _ in 0..4 {
+        direction = invoke_fn!(driver.runtime_mut(), "turn", direction).unwrap();
+        directions.push(direction);
+    }
+    assert_eq!(
+        directions,
+        [
+            Direction::East,
+            Direction::South,
+            Direction::West,
+            Direction::North
+        ]
+    );
+}
+
+#[test]
+fn enum_discriminant_invalid() {
+    let mut driver = TestDriver::new(
+        r"
+        pub fn invalid() -> i64 { 3 }
+    ",
+    );
+
+    let result: Result<Direction, _> = invoke_fn!(driver.runtime_mut(), "invalid");
+    let err = result.unwrap_err();
+    assert!(!err.is_retriable());
+    assert_eq!(
+        err.to_string(),
+        "Invalid discriminant `3` of type `Direction`."
+    );
+}
+
+#[test]
+fn struct_builder() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(gc) Foo {
+        a: f64,
+        scale: f64 = 2.0,
+        bar: Bar,
+    }
+
+    struct Bar { b: f64 }
+
+    pub fn bar_new(b: f64) -> Bar { Bar { b } }
+    pub fn foo_sum(foo: Foo) -> f64 { (foo.a + foo.bar.b) * foo.scale }
+    "#,
+    );
+
+    let runtime = driver.runtime_mut().clone();
+    let bar: StructRef = invoke_fn!(runtime, "bar_new", 3.0f64).unwrap();
+
+    let builder = StructBuilder::new(runtime.clone(), "Foo")
+        .unwrap()
+        .set("a", 4.0f64)
+        .unwrap();
+    assert_eq!(builder.unset_fields(), ["bar"]);
+    assert_eq!(
+        builder.finish().err(),
+        Some("Missing fields for `Foo`: `bar`.".to_string())
+    );
+
+    let foo = StructBuilder::new(runtime.clone(), "Foo")
+        .unwrap()
+        .set("a", 4.0f64)
+        .unwrap()
+        .set("bar", bar)
+        .unwrap()
+        .finish()
+        .unwrap();
+    assert_eq!(foo.get::<f64>("scale"), Ok(2.0));
+    let sum: f64 = invoke_fn!(runtime, "foo_sum", foo).unwrap();
+    assert_eq!(sum, 14.0);
+
+    assert_eq!(
+        StructBuilder::new(runtime.clone(), "Foo")
+            .unwrap()
+            .set("a", 4i64)
+            .err(),
+        Some(
+            "Mismatched types for `Foo::a`. Expected: `core::f64`. Found: `core::i64`.".to_string()
+        )
+    );
+    assert!(StructBuilder::new(runtime.clone(), "Foo")
+        .unwrap()
+        .set("b", 4.0f64)
+        .is_err());
+    assert!(StructBuilder::new(runtime, "Baz").is_err());
+}
+
+#[test]
+fn struct_builder_nested() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(gc) Foo {
+        a: f64,
+        bar: Bar,
+        baz: Baz,
+    }
+
+    struct(gc) Bar { b: f64, baz: Baz }
+    struct(value) Baz { c: f64 }
+
+    pub fn foo_sum(foo: Foo) -> f64 { foo.a + foo.bar.b + foo.bar.baz.c + foo.baz.c }
+    "#,
+    );
+
+    let runtime = driver.runtime_mut().clone();
+    let foo = StructBuilder::new(runtime.clone(), "Foo")
+        .unwrap()
+        .set("a", 1.0f64)
+        .unwrap()
+        .set_nested("bar", |bar| {
+            bar.set("b", 2.0f64)?
+                .set_nested("baz", |baz| baz.set("c", 3.0f64))
+        })
+        .unwrap()
+        .set_nested("baz", |baz| baz.set("c", 4.0f64))
+        .unwrap()
+        .finish()
+        .unwrap();
+    let sum: f64 = invoke_fn!(runtime, "foo_sum", foo).unwrap();
+    assert_eq!(sum, 10.0);
+
+    // Errors of nested builders are propagated
+    assert_eq!(
+        StructBuilder::new(runtime.clone(), "Foo")
+            .unwrap()
+            .set_nested("bar", |bar| bar.set("b", 2.0f64))
+            .err(),
+        Some("Missing fields for `Bar`: `baz`.".to_string())
+    );
+    assert_eq!(
+        StructBuilder::new(runtime, "Foo")
+            .unwrap()
+            .set_nested("a", Ok)
+            .err(),
+        Some("Mismatched types for `Foo::a`. Expected: `struct`. Found: `core::f64`.".to_string())
+    );
+}
+
 #[test]
 fn return_value() {
     let mut driver = TestDriver::new(
@@ -298,6 +619,39 @@ fn field_crash() {
     assert_invoke_eq!(i32, 15, driver, "main", 10);
 }
 
+#[test]
+fn marshal_struct_by_ref() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(value) Foo { a: i32 };
+    struct(gc) Bar { a: i32 };
+
+    pub fn foo_new(a: i32) -> Foo { Foo { a } }
+    pub fn foo_a(foo: Foo) -> i32 { foo.a }
+    pub fn bar_new(a: i32) -> Bar { Bar { a } }
+    pub fn bar_a(bar: Bar) -> i32 { bar.a }
+    pub fn bar_inc(bar: Bar) { bar.a += 1; }
+    "#,
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", 5i32).unwrap();
+    let a: i32 = invoke_fn!(driver.runtime_mut(), "foo_a", &foo).unwrap();
+    assert_eq!(a, 5);
+    let a: i32 = invoke_fn!(driver.runtime_mut(), "foo_a", &foo).unwrap();
+    assert_eq!(a, 5);
+
+    let bar: StructRef = invoke_fn!(driver.runtime_mut(), "bar_new", 1i32).unwrap();
+    let _: () = invoke_fn!(driver.runtime_mut(), "bar_inc", &bar).unwrap();
+    let _: () = invoke_fn!(driver.runtime_mut(), "bar_inc", &bar).unwrap();
+    let a: i32 = invoke_fn!(driver.runtime_mut(), "bar_a", &bar).unwrap();
+    assert_eq!(a, 3);
+    assert_eq!(bar.get::<i32>("a"), Ok(3));
+
+    // The type of the referenced struct is validated
+    let result: Result<i32, _> = invoke_fn!(driver.runtime_mut(), "foo_a", &bar);
+    assert!(result.is_err());
+}
+
 #[test]
 fn marshal_struct() {
     let mut driver = TestDriver::new(
@@ -474,6 +828,95 @@ fn marshal_struct() {
     assert!(bar_err.is_err());
 }
 
+#[test]
+fn struct_memory_kind() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(value) Foo { a: i32 };
+    struct(gc) Bar { a: i32 };
+
+    pub fn foo_new(a: i32) -> Foo { Foo { a } }
+    pub fn bar_new(a: i32) -> Bar { Bar { a } }
+    "#,
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", 1i32).unwrap();
+    assert_eq!(foo.memory_kind(), abi::StructMemoryKind::Value);
+
+    let bar: StructRef = invoke_fn!(driver.runtime_mut(), "bar_new", 1i32).unwrap();
+    assert_eq!(bar.memory_kind(), abi::StructMemoryKind::GC);
+}
+
+#[test]
+fn pin_struct() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(gc) Foo { a: i32 };
+
+    pub fn foo_new(a: i32) -> Foo { Foo { a } }
+    "#,
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", 5i32).unwrap();
+    let pinned = foo.pin();
+    assert_eq!(unsafe { *pinned.as_ptr().cast::<i32>() }, 5);
+
+    // Collecting garbage does not move a pinned struct
+    let ptr = pinned.as_ptr();
+    driver.runtime_mut().borrow().gc_collect();
+    assert_eq!(pinned.as_ptr(), ptr);
+}
+
+#[test]
+fn derive_from_mun_struct() {
+    use mun_runtime_derive::FromMunStruct;
+    use std::convert::TryFrom;
+
+    let mut driver = TestDriver::new(
+        r#"
+    struct Foo { a: i32, b: bool };
+    struct Bar { type: i32 };
+
+    pub fn foo_new(a: i32, b: bool) -> Foo {
+        Foo { a, b, }
+    }
+
+    pub fn bar_new(type: i32) -> Bar {
+        Bar { type, }
+    }
+    "#,
+    );
+
+    #[derive(Debug, PartialEq, FromMunStruct)]
+    struct Foo {
+        a: i32,
+        b: bool,
+    }
+
+    #[derive(Debug, PartialEq, FromMunStruct)]
+    struct Bar {
+        r#type: i32,
+    }
+
+    #[derive(Debug, FromMunStruct)]
+    struct MissingField {
+        c: i32,
+    }
+
+    #[derive(Debug, FromMunStruct)]
+    struct MismatchedType {
+        a: f64,
+    }
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", 1i32, true).unwrap();
+    assert_eq!(Foo::try_from(&foo), Ok(Foo { a: 1, b: true }));
+    assert!(MissingField::try_from(&foo).is_err());
+    assert!(MismatchedType::try_from(&foo).is_err());
+
+    let bar: StructRef = invoke_fn!(driver.runtime_mut(), "bar_new", 2i32).unwrap();
+    assert_eq!(Bar::try_from(&bar), Ok(Bar { r#type: 2 }));
+}
+
 #[test]
 fn extern_fn() {
     extern "C" fn add_int(a: i32, b: i32) -> i32 {
@@ -645,3 +1088,1117 @@ fn signed_and_unsigned_rem() {
     assert_invoke_eq!(i32, -2, driver, "signed");
     assert_invoke_eq!(i32, 2, driver, "unsigned");
 }
+
+#[test]
+fn struct_c_layout() {
+    #[repr(C)]
+    struct Foo {
+        a: bool,
+        b: f64,
+        c: i32,
+    }
+
+    let mut driver = TestDriver::new(
+        r#"
+    struct(value) Foo { a: bool, b: f64, c: i32 };
+
+    pub fn foo_new() -> Foo { Foo { a: true, b: 2.0, c: 3 } }
+    "#,
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new").unwrap();
+    let runtime = driver.runtime_mut().borrow();
+    let type_info = StructRef::type_info(&foo, &runtime);
+    assert!(type_info.has_c_layout());
+    assert_eq!(type_info.size_in_bytes(), std::mem::size_of::<Foo>());
+    assert_eq!(type_info.alignment(), std::mem::align_of::<Foo>());
+
+    let pinned = foo.pin();
+    let host_foo = unsafe { &*pinned.as_ptr().cast::<Foo>() };
+    assert!(host_foo.a);
+    assert_eq!(host_foo.b, 2.0);
+    assert_eq!(host_foo.c, 3);
+}
+
+#[test]
+fn struct_set_all() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(value) Foo { a: i32, b: bool };
+    struct(gc) Bar { a: f64, foo: Foo };
+
+    pub fn foo_new(a: i32, b: bool) -> Foo { Foo { a, b } }
+    pub fn bar_new(a: f64, foo: Foo) -> Bar { Bar { a, foo } }
+    "#,
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", 1i32, false).unwrap();
+    let mut bar: StructRef = invoke_fn!(driver.runtime_mut(), "bar_new", 1.0f64, foo).unwrap();
+
+    let new_foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", 2i32, true).unwrap();
+    bar.set_all(&[("a", DynValue::from(3.0f64)), ("foo", new_foo.into())])
+        .unwrap();
+    assert_eq!(bar.get::<f64>("a"), Ok(3.0));
+    let foo = bar.get::<StructRef>("foo").unwrap();
+    assert_eq!(foo.get::<i32>("a"), Ok(2));
+    assert_eq!(foo.get::<bool>("b"), Ok(true));
+}
+
+#[test]
+fn struct_set_all_atomic() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(gc) Foo { a: i32, b: bool };
+
+    pub fn foo_new(a: i32, b: bool) -> Foo { Foo { a, b } }
+    "#,
+    );
+
+    let mut foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", 1i32, false).unwrap();
+
+    // A mismatching type must prevent all fields from being written
+    assert_eq!(
+        foo.set_all(&[("a", 2i32.into()), ("b", 3i32.into())]),
+        Err(
+            "Mismatched types for `Foo::b`. Expected: `core::bool`. Found: `core::i32`."
+                .to_string()
+        )
+    );
+    assert_eq!(foo.get::<i32>("a"), Ok(1));
+
+    // A non-existent field must prevent all fields from being written
+    assert_eq!(
+        foo.set_all(&[("a", 2i32.into()), ("c", true.into())]),
+        Err("Struct `Foo` does not contain field `c`.".to_string())
+    );
+    assert_eq!(foo.get::<i32>("a"), Ok(1));
+
+    // A field can only be set once
+    assert_eq!(
+        foo.set_all(&[("a", 2i32.into()), ("a", 3i32.into())]),
+        Err("Field `Foo::a` is set more than once.".to_string())
+    );
+    assert_eq!(foo.get::<i32>("a"), Ok(1));
+}
+
+#[cfg(feature = "mutation-log")]
+#[test]
+fn struct_mutation_log() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(gc) Foo { a: i32, b: bool };
+
+    pub fn foo_new(a: i32, b: bool) -> Foo { Foo { a, b } }
+    "#,
+    );
+
+    let mut foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", 1i32, false).unwrap();
+    foo.set("a", 2i32).unwrap();
+    assert_eq!(foo.replace("b", true), Ok(false));
+    assert_eq!(foo.replace_with("a", |a: i32| a * 3), Ok(6));
+    // Failed writes are not recorded
+    assert!(foo.set("a", 1.0f64).is_err());
+    foo.set_all(&[("b", false.into()), ("a", 7i32.into())])
+        .unwrap();
+    let other: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", 8i32, true).unwrap();
+    foo.copy_from(&other).unwrap();
+    // Only the fields whose bytes changed are recorded
+    let offset = foo.offset_of("a").unwrap();
+    unsafe {
+        foo.with_bytes_mut(|bytes| {
+            bytes[offset..offset + 4].copy_from_slice(&9i32.to_ne_bytes());
+        })
+    }
+    .unwrap();
+
+    let log = driver.runtime_mut().borrow().mutation_log();
+    let entries: Vec<(&str, &str, &[u8], &[u8])> = log
+        .iter()
+        .map(|m| {
+            (
+                m.type_name.as_str(),
+                m.field_name.as_str(),
+                m.old.as_slice(),
+                m.new.as_slice(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            ("Foo", "a", &1i32.to_ne_bytes()[..], &2i32.to_ne_bytes()[..]),
+            ("Foo", "b", &[0u8][..], &[1u8][..]),
+            ("Foo", "a", &2i32.to_ne_bytes()[..], &6i32.to_ne_bytes()[..]),
+            ("Foo", "b", &[1u8][..], &[0u8][..]),
+            ("Foo", "a", &6i32.to_ne_bytes()[..], &7i32.to_ne_bytes()[..]),
+            ("Foo", "a", &7i32.to_ne_bytes()[..], &8i32.to_ne_bytes()[..]),
+            ("Foo", "b", &[0u8][..], &[1u8][..]),
+            ("Foo", "a", &8i32.to_ne_bytes()[..], &9i32.to_ne_bytes()[..]),
+        ]
+    );
+    assert!(log.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+
+    driver.runtime_mut().borrow().clear_mutation_log();
+    assert!(driver.runtime_mut().borrow().mutation_log().is_empty());
+}
+
+#[test]
+fn struct_with_bytes_mut() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(value) Vec2 { x: f32, y: f32 }
+    struct(gc) Foo { a: i32, pos: Vec2 }
+    struct(gc) Bar { foo: Foo }
+
+    pub fn foo_new() -> Foo { Foo { a: 1, pos: Vec2 { x: 2.0, y: 3.0 } } }
+    pub fn bar_new(foo: Foo) -> Bar { Bar { foo } }
+    "#,
+    );
+
+    let mut foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new").unwrap();
+    let mut buffer = Vec::new();
+    buffer.extend_from_slice(&5i32.to_ne_bytes());
+    buffer.extend_from_slice(&6.0f32.to_ne_bytes());
+    buffer.extend_from_slice(&7.0f32.to_ne_bytes());
+    let runtime = driver.runtime_mut().clone();
+    let len = unsafe {
+        foo.with_bytes_mut(|bytes| {
+            // The runtime is not borrowed while the bytes are written
+            assert!(runtime.try_borrow_mut().is_ok());
+            bytes.copy_from_slice(&buffer);
+            bytes.len()
+        })
+    }
+    .unwrap();
+    assert_eq!(len, 12);
+    assert_eq!(foo.get::<i32>("a"), Ok(5));
+    let pos = foo.get::<StructRef>("pos").unwrap();
+    assert_eq!(pos.get::<f32>("x"), Ok(6.0));
+    assert_eq!(pos.get::<f32>("y"), Ok(7.0));
+
+    let mut bar: StructRef = invoke_fn!(driver.runtime_mut(), "bar_new", foo).unwrap();
+    assert_eq!(
+        unsafe { bar.with_bytes_mut(|_| ()) },
+        Err(
+            "Struct `Bar` references garbage collected structs, so its bytes cannot be written \
+             directly. Use `set` to write its fields instead."
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn struct_replace_with() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(gc) Foo { a: i32, b: bool };
+    struct(gc) Bar { foo: Foo };
+
+    pub fn foo_new(a: i32, b: bool) -> Foo { Foo { a, b } }
+    pub fn bar_new(foo: Foo) -> Bar { Bar { foo } }
+    "#,
+    );
+
+    let mut foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", 1i32, false).unwrap();
+    assert_eq!(foo.replace_with("a", |a: i32| a + 1), Ok(2));
+    assert_eq!(foo.replace_with("b", |b: bool| !b), Ok(true));
+    assert_eq!(foo.get::<i32>("a"), Ok(2));
+    assert_eq!(foo.get::<bool>("b"), Ok(true));
+
+    assert_eq!(
+        foo.replace_with("b", |b: i32| b),
+        Err(
+            "Mismatched types for `Foo::b`. Expected: `core::bool`. Found: `core::i32`."
+                .to_string()
+        )
+    );
+    assert_eq!(
+        foo.replace_with("c", |c: i32| c),
+        Err("Struct `Foo` does not contain field `c`.".to_string())
+    );
+
+    // A struct of a different type must not be written
+    let mut bar: StructRef = invoke_fn!(driver.runtime_mut(), "bar_new", foo.clone()).unwrap();
+    let other_bar: StructRef = invoke_fn!(driver.runtime_mut(), "bar_new", foo).unwrap();
+    assert_eq!(
+        bar.replace_with("foo", |_: StructRef| other_bar).err(),
+        Some("Mismatched types for `Bar::foo`. Expected: `Foo`. Found: `Bar`.".to_string())
+    );
+    assert!(bar.get::<StructRef>("foo").unwrap().is_a("Foo"));
+}
+
+#[test]
+fn struct_copy_from() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(value) Foo { a: i32, b: bool };
+    struct(gc) Bar { a: f64, foo: Foo, baz: Baz };
+    struct(gc) Baz { c: i32 };
+
+    pub fn bar_new(a: f64, c: i32) -> Bar { Bar { a, foo: Foo { a: c, b: true }, baz: Baz { c } } }
+    pub fn baz_new(c: i32) -> Baz { Baz { c } }
+    "#,
+    );
+
+    let mut bar: StructRef = invoke_fn!(driver.runtime_mut(), "bar_new", 1.0f64, 1i32).unwrap();
+    let other_bar: StructRef = invoke_fn!(driver.runtime_mut(), "bar_new", 2.0f64, 2i32).unwrap();
+    bar.copy_from(&other_bar).unwrap();
+    assert!(bar.equals(&other_bar));
+    assert_eq!(bar.get::<f64>("a"), Ok(2.0));
+    assert_eq!(bar.get::<StructRef>("foo").unwrap().get::<i32>("a"), Ok(2));
+
+    // Garbage collected struct fields reference the same struct afterwards
+    let mut baz: StructRef = bar.get("baz").unwrap();
+    baz.set("c", 3i32).unwrap();
+    assert_eq!(
+        other_bar.get::<StructRef>("baz").unwrap().get::<i32>("c"),
+        Ok(3)
+    );
+
+    assert_eq!(
+        bar.copy_from(&baz),
+        Err("Mismatched struct types. Expected: `Bar`. Found: `Baz`.".to_string())
+    );
+}
+
+#[test]
+fn dyn_argument() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct Foo { a: i32 };
+
+    pub fn foo_new(a: i32) -> Foo { Foo { a } }
+    "#,
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", 1i32).unwrap();
+    let mut arguments = vec![
+        DynArgument::new(1i32),
+        DynArgument::new(true),
+        DynArgument::new(foo.clone()),
+        DynArgument::from(DynValue::F64(2.0)),
+    ];
+
+    let runtime = driver.runtime_mut().borrow();
+    let type_names: Vec<&str> = arguments.iter().map(|a| a.type_name(&runtime)).collect();
+    assert_eq!(type_names, ["core::i32", "core::bool", "Foo", "core::f64"]);
+    assert_eq!(
+        arguments[2].type_guid(&runtime),
+        ArgumentReflection::type_guid(&foo, &runtime)
+    );
+
+    // A `DynArgument` that holds a `DynValue` is marshalled like the contained value
+    let mut value = 0f64;
+    let argument = arguments.pop().unwrap();
+    unsafe {
+        argument.marshal_to_ptr(
+            NonNull::from(&mut value).cast(),
+            &runtime,
+            <f64 as abi::HasStaticTypeInfo>::type_info(),
+        )
+    };
+    assert_eq!(value, 2.0);
+}
+
+#[test]
+fn dyn_return_type() {
+    let mut driver = TestDriver::new(
+        r#"
+    pub fn main() {}
+    "#,
+    );
+
+    let return_types = [DynReturnType::of::<i32>(), DynReturnType::of::<f64>()];
+    assert_eq!(
+        return_types[0].type_name(),
+        <i32 as ReturnTypeReflection>::type_name()
+    );
+    assert_eq!(
+        return_types[1].type_guid(),
+        <f64 as ReturnTypeReflection>::type_guid()
+    );
+
+    let mut value = 5i32;
+    let marshalled = unsafe {
+        return_types[0].marshal_from_ptr(
+            NonNull::from(&mut value).cast(),
+            driver.runtime_mut().clone(),
+            None,
+        )
+    };
+    assert_eq!(marshalled.unwrap().downcast_ref::<i32>(), Some(&5));
+}
+
+#[test]
+fn struct_offset_of() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct Foo { a: u8, b: f64, c: i32 };
+
+    pub fn foo_new() -> Foo { Foo { a: 1, b: 2.0, c: 3 } }
+    "#,
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new").unwrap();
+    assert_eq!(foo.offset_of("a"), Ok(0));
+    assert_eq!(foo.offset_of("b"), Ok(8));
+    assert_eq!(foo.offset_of("c"), Ok(16));
+    assert_eq!(
+        foo.offset_of("d"),
+        Err("Struct `Foo` does not contain field `d`.".to_string())
+    );
+
+    let runtime = driver.runtime_mut().borrow();
+    assert_eq!(runtime.offset_of("Foo", "c"), foo.offset_of("c"));
+    assert_eq!(
+        runtime.offset_of("Bar", "c"),
+        Err("Struct type `Bar` does not exist.".to_string())
+    );
+}
+
+#[test]
+fn struct_layout() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(value) Foo { a: bool, b: f64 };
+    struct(gc) Bar {
+        /// The foo
+        foo: Foo,
+        baz: Baz,
+        /// The number
+        /// of c's
+        c: i32,
+    };
+    struct(gc) Baz;
+
+    pub fn main() {}
+    "#,
+    );
+
+    let runtime = driver.runtime_mut().borrow();
+    assert!(runtime.struct_layout("Qux").is_none());
+
+    let layout = runtime.struct_layout("Bar").unwrap();
+    let pointer_size = std::mem::size_of::<*const u8>();
+    assert_eq!(
+        layout,
+        vec![
+            FieldLayout {
+                name: "foo",
+                offset: 0,
+                size: 16,
+                alignment: 8,
+                type_name: "Foo",
+                doc: Some("The foo"),
+            },
+            FieldLayout {
+                name: "baz",
+                offset: 16,
+                size: pointer_size,
+                alignment: std::mem::align_of::<*const u8>(),
+                type_name: "Baz",
+                doc: None,
+            },
+            FieldLayout {
+                name: "c",
+                offset: 16 + pointer_size,
+                size: 4,
+                alignment: 4,
+                type_name: "core::i32",
+                doc: Some("The number\nof c's"),
+            },
+        ]
+    );
+}
+
+#[test]
+fn int128_layout() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct Wide { a: u8, b: i128, c: u128 };
+
+    pub fn wide_new() -> Wide {
+        Wide { a: 1, b: -170141183460469231731687303715884105727, c: 340282366920938463463374607431768211455 }
+    }
+    "#,
+    );
+
+    // The layout of 128-bit integer fields must match the host's, so they can be read in place
+    #[repr(C)]
+    struct Wide {
+        a: u8,
+        b: i128,
+        c: u128,
+    }
+    let host = Wide { a: 0, b: 0, c: 0 };
+    let base = &host as *const Wide as usize;
+
+    let layout = driver.runtime_mut().borrow().struct_layout("Wide").unwrap();
+    assert_eq!(layout[1].offset, &host.b as *const i128 as usize - base);
+    assert_eq!(layout[1].alignment, std::mem::align_of::<i128>());
+    assert_eq!(layout[2].offset, &host.c as *const u128 as usize - base);
+    assert_eq!(layout[2].alignment, std::mem::align_of::<u128>());
+
+    let wide: StructRef = invoke_fn!(driver.runtime_mut(), "wide_new").unwrap();
+    assert_eq!(wide.get::<u8>("a"), Ok(1));
+    assert_eq!(wide.get::<i128>("b"), Ok(std::i128::MIN + 1));
+    assert_eq!(wide.get::<u128>("c"), Ok(std::u128::MAX));
+}
+
+#[test]
+fn struct_field_doc() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct Foo {
+        /// The first field
+        a: i32,
+        b: f64,
+    }
+
+    pub fn foo_new() -> Foo { Foo { a: 1, b: 2.0 } }
+    "#,
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new").unwrap();
+    assert_eq!(foo.field_doc("a"), Ok(Some("The first field".to_string())));
+    assert_eq!(foo.field_doc("b"), Ok(None));
+    assert_eq!(
+        foo.field_doc("c"),
+        Err("Struct `Foo` does not contain field `c`.".to_string())
+    );
+}
+
+#[test]
+fn struct_write_json() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(value) Foo { a: i32, b: bool };
+    struct(gc) Baz;
+    struct(gc) Bar { foo: Foo, c: f64, baz: Baz, d: u64 };
+
+    pub fn bar_new() -> Bar {
+        Bar { foo: Foo { a: -3, b: true }, c: 1.5, baz: Baz, d: 18446744073709551615 }
+    }
+    "#,
+    );
+
+    let mut bar: StructRef = invoke_fn!(driver.runtime_mut(), "bar_new").unwrap();
+    let mut json = Vec::new();
+    bar.write_json(&mut json).unwrap();
+    assert_eq!(
+        String::from_utf8(json).unwrap(),
+        r#"{"foo":{"a":-3,"b":true},"c":1.5,"baz":{},"d":18446744073709551615}"#
+    );
+
+    // Non-finite numbers cannot be represented in JSON
+    bar.set("c", std::f64::INFINITY).unwrap();
+    let error = bar.write_json(&mut Vec::new()).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(
+        error.to_string(),
+        "Field `c` holds `inf`, which cannot be represented in JSON."
+    );
+
+    let mut json = Vec::new();
+    bar.write_json_with(&mut json, NonFinitePolicy::Null)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(json).unwrap(),
+        r#"{"foo":{"a":-3,"b":true},"c":null,"baz":{},"d":18446744073709551615}"#
+    );
+
+    let mut json = Vec::new();
+    bar.write_json_with(&mut json, NonFinitePolicy::PassThrough)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(json).unwrap(),
+        r#"{"foo":{"a":-3,"b":true},"c":Infinity,"baz":{},"d":18446744073709551615}"#
+    );
+
+    bar.set("c", std::f64::NAN).unwrap();
+    let mut json = Vec::new();
+    bar.write_json_with(&mut json, NonFinitePolicy::PassThrough)
+        .unwrap();
+    assert!(String::from_utf8(json).unwrap().contains(r#""c":NaN"#));
+
+    // In-memory marshalling passes non-finite numbers through unchanged
+    assert!(bar.get::<f64>("c").unwrap().is_nan());
+}
+
+#[test]
+fn struct_downcast() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct Foo { a: i32 }
+    struct Bar { a: i32 }
+
+    pub fn foo_new() -> Foo { Foo { a: 1 } }
+    pub fn bar_new() -> Bar { Bar { a: 2 } }
+    "#,
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new").unwrap();
+    let bar: StructRef = invoke_fn!(driver.runtime_mut(), "bar_new").unwrap();
+    let structs = [foo, bar];
+
+    let foos: Vec<i32> = structs
+        .iter()
+        .filter(|s| s.is_a("Foo"))
+        .map(|s| s.get::<i32>("a").unwrap())
+        .collect();
+    assert_eq!(foos, [1]);
+
+    assert_eq!(structs[1].downcast_check("Bar"), Ok(()));
+    assert_eq!(
+        structs[1].downcast_check("Foo"),
+        Err("Mismatched struct types. Expected: `Foo`. Found: `Bar`.".to_string())
+    );
+}
+
+#[test]
+fn struct_try_get() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct Foo { a: i32, b: f64 }
+
+    pub fn foo_new() -> Foo { Foo { a: 1, b: 2.0 } }
+    "#,
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new").unwrap();
+    assert_eq!(foo.try_get::<i32>("a"), Some(Ok(1)));
+    assert_eq!(foo.try_get::<f64>("b"), Some(Ok(2.0)));
+    assert_eq!(foo.try_get::<i32>("c"), None);
+
+    let mismatch = foo.try_get::<i32>("b").unwrap().unwrap_err();
+    assert_eq!(
+        mismatch,
+        FieldError::TypeMismatch(TypeMismatch {
+            struct_name: "Foo".to_string(),
+            field_name: "b".to_string(),
+            expected: "core::i32".to_string(),
+            found: "core::f64".to_string(),
+        })
+    );
+    assert_eq!(mismatch.to_string(), foo.get::<i32>("b").err().unwrap());
+}
+
+#[test]
+fn raw_struct_ptr() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct Foo { a: i32 }
+
+    pub fn foo_new() -> Foo { Foo { a: 3 } }
+    "#,
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new").unwrap();
+    let raw = foo.clone().into_raw();
+    let ptr = unsafe { raw.try_get_ptr() }.expect("the struct handle is not null");
+    assert_eq!(ptr, unsafe { raw.get_ptr() });
+    assert_eq!(unsafe { *ptr.cast::<i32>() }, 3);
+}
+
+#[test]
+fn struct_equals_and_hash() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(value) Foo { a: i32, b: f64 };
+    struct(gc) Bar { foo: Foo, c: bool };
+    struct(gc) Baz { bar: Bar };
+
+    pub fn foo_new(a: i32, b: f64) -> Foo { Foo { a, b } }
+    pub fn baz_new(a: i32, c: bool) -> Baz { Baz { bar: Bar { foo: Foo { a, b: 1.5 }, c } } }
+    "#,
+    );
+
+    let baz: StructRef = invoke_fn!(driver.runtime_mut(), "baz_new", 3i32, true).unwrap();
+    let same_baz: StructRef = invoke_fn!(driver.runtime_mut(), "baz_new", 3i32, true).unwrap();
+    assert!(baz.equals(&same_baz));
+    assert_eq!(baz.structural_hash(), same_baz.structural_hash());
+
+    // Nested gc structs are compared by value
+    let other_baz: StructRef = invoke_fn!(driver.runtime_mut(), "baz_new", 4i32, true).unwrap();
+    assert!(!baz.equals(&other_baz));
+    assert_ne!(baz.structural_hash(), other_baz.structural_hash());
+
+    let other_baz: StructRef = invoke_fn!(driver.runtime_mut(), "baz_new", 3i32, false).unwrap();
+    assert!(!baz.equals(&other_baz));
+
+    // Structs of different types are never equal
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", 3i32, 1.5f64).unwrap();
+    let bar: StructRef = baz.get("bar").unwrap();
+    assert!(!bar.equals(&foo));
+    assert!(foo.equals(&bar.get::<StructRef>("foo").unwrap()));
+
+    // Fields are compared bitwise
+    let nan: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", 3i32, f64::NAN).unwrap();
+    let same_nan: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", 3i32, f64::NAN).unwrap();
+    assert!(nan.equals(&same_nan));
+    assert_eq!(nan.structural_hash(), same_nan.structural_hash());
+
+    // Mutating a struct changes its hash
+    let mut foo = foo;
+    let hash = foo.structural_hash();
+    foo.set("a", 4i32).unwrap();
+    assert_ne!(foo.structural_hash(), hash);
+}
+
+#[test]
+fn struct_equals_ignoring() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(gc) Foo { a: i32, timestamp: f64 };
+    struct(gc) Bar { a: i32 };
+
+    pub fn foo_new(a: i32, timestamp: f64) -> Foo { Foo { a, timestamp } }
+    pub fn bar_new(a: i32) -> Bar { Bar { a } }
+    "#,
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", 3i32, 1.0f64).unwrap();
+    let later_foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", 3i32, 2.0f64).unwrap();
+    assert!(!foo.equals(&later_foo));
+    assert_eq!(foo.equals_ignoring(&later_foo, &["timestamp"]), Ok(true));
+    assert_eq!(foo.equals_ignoring(&later_foo, &[]), Ok(false));
+
+    let other_foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", 4i32, 2.0f64).unwrap();
+    assert_eq!(foo.equals_ignoring(&other_foo, &["timestamp"]), Ok(false));
+    assert_eq!(
+        foo.equals_ignoring(&other_foo, &["a", "timestamp"]),
+        Ok(true)
+    );
+
+    // Structs of different types are never equal
+    let bar: StructRef = invoke_fn!(driver.runtime_mut(), "bar_new", 3i32).unwrap();
+    assert_eq!(foo.equals_ignoring(&bar, &["timestamp"]), Ok(false));
+
+    assert_eq!(
+        foo.equals_ignoring(&later_foo, &["time"]),
+        Err("Struct `Foo` does not contain field `time`.".to_string())
+    );
+}
+
+#[test]
+fn struct_visit() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(value) Foo { a: i32, b: bool };
+    struct(gc) Bar { foo: Foo, c: f64 };
+
+    pub fn bar_new() -> Bar { Bar { foo: Foo { a: 3, b: true }, c: 1.5 } }
+    "#,
+    );
+
+    #[derive(Default)]
+    struct TraceVisitor {
+        trace: Vec<String>,
+        skip_field: Option<&'static str>,
+        break_on_field: Option<&'static str>,
+    }
+
+    impl StructVisitor for TraceVisitor {
+        fn enter_struct(&mut self, type_info: &abi::TypeInfo) -> Visit {
+            self.trace.push(format!("enter {}", type_info.name()));
+            Visit::Continue
+        }
+
+        fn enter_field(&mut self, name: &str, _type_info: &abi::TypeInfo) -> Visit {
+            if self.break_on_field == Some(name) {
+                Visit::Break
+            } else if self.skip_field == Some(name) {
+                Visit::Skip
+            } else {
+                Visit::Continue
+            }
+        }
+
+        fn visit_fundamental(
+            &mut self,
+            name: &str,
+            type_info: &abi::TypeInfo,
+            bytes: &[u8],
+        ) -> Visit {
+            self.trace.push(format!(
+                "{}: {} ({} bytes)",
+                name,
+                type_info.name(),
+                bytes.len()
+            ));
+            Visit::Continue
+        }
+
+        fn leave_struct(&mut self, type_info: &abi::TypeInfo) -> Visit {
+            self.trace.push(format!("leave {}", type_info.name()));
+            Visit::Continue
+        }
+    }
+
+    let bar: StructRef = invoke_fn!(driver.runtime_mut(), "bar_new").unwrap();
+
+    let mut visitor = TraceVisitor::default();
+    assert!(bar.visit(&mut visitor));
+    assert_eq!(
+        visitor.trace,
+        [
+            "enter Bar",
+            "enter Foo",
+            "a: core::i32 (4 bytes)",
+            "b: core::bool (1 bytes)",
+            "leave Foo",
+            "c: core::f64 (8 bytes)",
+            "leave Bar",
+        ]
+    );
+
+    let mut visitor = TraceVisitor {
+        skip_field: Some("foo"),
+        ..TraceVisitor::default()
+    };
+    assert!(bar.visit(&mut visitor));
+    assert_eq!(
+        visitor.trace,
+        ["enter Bar", "c: core::f64 (8 bytes)", "leave Bar"]
+    );
+
+    // The visitor can short-circuit the traversal
+    let mut visitor = TraceVisitor {
+        break_on_field: Some("b"),
+        ..TraceVisitor::default()
+    };
+    assert!(!bar.visit(&mut visitor));
+    assert_eq!(
+        visitor.trace,
+        ["enter Bar", "enter Foo", "a: core::i32 (4 bytes)"]
+    );
+}
+
+#[test]
+fn sync_struct_ref() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(value) Foo { a: i32, b: bool };
+    struct(gc) Bar { foo: Foo, c: f64 };
+    struct(gc) Baz { bar: Bar };
+
+    pub fn baz_new() -> Baz { Baz { bar: Bar { foo: Foo { a: 3, b: true }, c: 1.5 } } }
+    "#,
+    );
+
+    let baz: StructRef = invoke_fn!(driver.runtime_mut(), "baz_new").unwrap();
+    let baz = baz.into_sync();
+
+    let worker_baz = baz.clone();
+    let (a, b, c) = std::thread::spawn(move || {
+        let bar = worker_baz.get_struct("bar").unwrap();
+        let foo = bar.get_struct("foo").unwrap();
+        assert_eq!(foo.type_name(), Ok("Foo".to_string()));
+        (
+            foo.get::<i32>("a").unwrap(),
+            foo.get::<bool>("b").unwrap(),
+            bar.get::<f64>("c").unwrap(),
+        )
+    })
+    .join()
+    .unwrap();
+    assert_eq!((a, b, c), (3, true, 1.5));
+
+    // The worker's references are rooted, so they survive garbage collection
+    let bar: SyncStructRef = baz.get_struct("bar").unwrap();
+    driver.runtime_mut().borrow().gc_collect();
+    assert_eq!(bar.get::<f64>("c"), Ok(1.5));
+
+    assert_eq!(
+        bar.get::<i32>("c"),
+        Err(
+            "Mismatched types for `Bar::c`. Expected: `core::f64`. Found: `core::i32`.".to_string()
+        )
+    );
+    assert_eq!(
+        bar.get_struct("c").err(),
+        Some("Mismatched types for `Bar::c`. Expected: `struct`. Found: `core::f64`.".to_string())
+    );
+}
+
+#[test]
+fn tuple() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(value) Pair(i64, f64);
+    struct(gc) Wrapper(Pair, bool);
+
+    pub fn pair_new(a: i64, b: f64) -> Pair { Pair(a, b) }
+    pub fn pair_id(pair: Pair) -> Pair { pair }
+    pub fn wrapper_new(pair: Pair, c: bool) -> Wrapper { Wrapper(pair, c) }
+    "#,
+    );
+
+    let pair: (i64, f64) = invoke_fn!(driver.runtime_mut(), "pair_new", -3i64, 1.5f64).unwrap();
+    assert_eq!(pair, (-3, 1.5));
+
+    let pair: (i64, f64) = invoke_fn!(driver.runtime_mut(), "pair_id", (7i64, 2.5f64)).unwrap();
+    assert_eq!(pair, (7, 2.5));
+
+    let wrapper: ((i64, f64), bool) =
+        invoke_fn!(driver.runtime_mut(), "wrapper_new", (4i64, 0.5f64), true).unwrap();
+    assert_eq!(wrapper, ((4, 0.5), true));
+
+    let wrapper: StructRef =
+        invoke_fn!(driver.runtime_mut(), "wrapper_new", (4i64, 0.5f64), false).unwrap();
+    assert_eq!(wrapper.get::<(i64, f64)>("0").unwrap(), (4, 0.5));
+}
+
+#[test]
+fn result() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(value) ParseResult { tag: u8, ok: i32, err: u8 }
+    struct(value) Triple(u8, i32, u8);
+    struct(gc) Wrapper(ParseResult);
+
+    pub fn parse(x: i32) -> ParseResult {
+        if x >= 0 {
+            ParseResult { tag: 0, ok: x, err: 0 }
+        } else {
+            ParseResult { tag: 1, ok: 0, err: 7 }
+        }
+    }
+    pub fn triple() -> Triple { Triple(0, 1, 0) }
+    pub fn wrapper_new(x: i32) -> Wrapper { Wrapper(parse(x)) }
+    pub fn invalid() -> ParseResult { ParseResult { tag: 2, ok: 0, err: 0 } }
+    pub fn wrapper_invalid() -> Wrapper { Wrapper(invalid()) }
+    "#,
+    );
+
+    let result: MunResult<i32, u8> = invoke_fn!(driver.runtime_mut(), "parse", 5i32).unwrap();
+    assert_eq!(result, MunResult::Ok(5));
+
+    let result: MunResult<i32, u8> = invoke_fn!(driver.runtime_mut(), "parse", -5i32).unwrap();
+    assert_eq!(result.into_result(), Err(7));
+
+    // Results can be stored in the fields of structs
+    let wrapper: StructRef = invoke_fn!(driver.runtime_mut(), "wrapper_new", 3i32).unwrap();
+    assert_eq!(wrapper.get::<MunResult<i32, u8>>("0"), Ok(MunResult::Ok(3)));
+
+    // The payload types must match
+    let result: Result<MunResult<u8, u8>, _> = invoke_fn!(driver.runtime_mut(), "parse", 5i32);
+    assert!(result.is_err());
+
+    // Only structs with the layout of the ABI's result type are results
+    let result: Result<MunResult<i32, u8>, _> = invoke_fn!(driver.runtime_mut(), "triple");
+    assert!(result.is_err());
+
+    // An invalid discriminant results in an error that cannot be retried
+    let result: Result<MunResult<i32, u8>, _> = invoke_fn!(driver.runtime_mut(), "invalid");
+    let err = result.unwrap_err();
+    assert!(!err.is_retriable());
+    assert_eq!(
+        err.to_string(),
+        "Invalid discriminant `2` of type `ParseResult`."
+    );
+
+    let mut handle = driver
+        .runtime_mut()
+        .borrow()
+        .resolve::<(), MunResult<i32, u8>>("invalid")
+        .unwrap();
+    assert_eq!(
+        handle.call(driver.runtime_mut()),
+        Err(SignatureError::InvalidReturnValue(
+            MarshalError::InvalidDiscriminant {
+                type_name: "ParseResult".to_string(),
+                discriminant: "2".to_string(),
+            }
+        ))
+    );
+
+    let wrapper: StructRef = invoke_fn!(driver.runtime_mut(), "wrapper_invalid").unwrap();
+    assert_eq!(
+        wrapper.get::<MunResult<i32, u8>>("0"),
+        Err("Invalid discriminant `2` of type `ParseResult`.".to_string())
+    );
+}
+
+#[test]
+fn tuple_mismatch() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(value) Pair(i64, f64);
+
+    pub fn pair_new(a: i64, b: f64) -> Pair { Pair(a, b) }
+    pub fn pair_id(pair: Pair) -> Pair { pair }
+    "#,
+    );
+
+    let result: Result<(i64, f64, bool), _> =
+        invoke_fn!(driver.runtime_mut(), "pair_new", -3i64, 1.5f64);
+    assert!(result.is_err());
+
+    let result: Result<(f64, i64), _> = invoke_fn!(driver.runtime_mut(), "pair_new", -3i64, 1.5f64);
+    assert!(result.is_err());
+
+    let result: Result<(i64, f64), _> = invoke_fn!(driver.runtime_mut(), "pair_id", (1.5f64, 7i64));
+    assert!(result.is_err());
+
+    // Marshalling through a type-erased return type validates the struct's fields
+    let runtime = driver.runtime_mut().clone();
+    let pair: StructRef = invoke_fn!(runtime, "pair_new", -3i64, 1.5f64).unwrap();
+    let runtime_ref = runtime.borrow();
+    let type_info = StructRef::type_info(&pair, &runtime_ref);
+    let ptr = NonNull::new(unsafe { pair.clone().into_raw().get_ptr() } as *mut u8).unwrap();
+    let result = unsafe {
+        DynReturnType::of::<(f64, i64)>().marshal_from_ptr(ptr, runtime.clone(), Some(type_info))
+    };
+    assert_eq!(
+        result.err(),
+        Some(MarshalError::LayoutMismatch {
+            expected: "(core::f64, core::i64)".to_string(),
+            found: "Pair".to_string(),
+        })
+    );
+}
+
+#[test]
+fn non_null_and_ref_type_guids() {
+    let mut driver = TestDriver::new(
+        r#"
+    pub fn main() {}
+    "#,
+    );
+
+    let runtime = driver.runtime_mut().borrow();
+    let mut value = 5i32;
+    let ptr = NonNull::from(&mut value).cast::<std::ffi::c_void>();
+    assert_eq!(
+        ArgumentReflection::type_guid(&ptr, &runtime),
+        ArgumentReflection::type_guid(&ptr.as_ptr(), &runtime)
+    );
+
+    let reference = unsafe { ptr.as_ref() };
+    assert_eq!(
+        ArgumentReflection::type_guid(&reference, &runtime),
+        ArgumentReflection::type_guid(&(reference as *const std::ffi::c_void), &runtime)
+    );
+    assert_eq!(
+        ArgumentReflection::type_name(&reference, &runtime),
+        "*const core::void"
+    );
+}
+
+#[test]
+fn struct_get_dyn() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(value) Foo { a: i32, b: bool };
+    struct(gc) Bar { a: f64, foo: Foo, baz: Baz };
+    struct(gc) Baz { a: u8 };
+
+    pub fn foo_new(a: i32, b: bool) -> Foo { Foo { a, b } }
+    pub fn bar_new(a: f64, foo: Foo) -> Bar { Bar { a, foo, baz: Baz { a: 4 } } }
+    "#,
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", 1i32, true).unwrap();
+    let bar: StructRef = invoke_fn!(driver.runtime_mut(), "bar_new", 2.5f64, foo).unwrap();
+
+    match bar.get_dyn("a") {
+        Ok(DynValue::F64(value)) => assert_eq!(value, 2.5),
+        _ => panic!("expected an `f64` value"),
+    }
+
+    let foo = match bar.get_dyn("foo") {
+        Ok(DynValue::Struct(foo)) => foo,
+        _ => panic!("expected a struct value"),
+    };
+    match (foo.get_dyn("a"), foo.get_dyn("b")) {
+        (Ok(DynValue::I32(a)), Ok(DynValue::Bool(b))) => assert_eq!((a, b), (1, true)),
+        _ => panic!("expected an `i32` and a `bool` value"),
+    }
+
+    // A garbage collected field remains alive while the returned `StructRef` is
+    let baz = match bar.get_dyn("baz") {
+        Ok(DynValue::Struct(baz)) => baz,
+        _ => panic!("expected a struct value"),
+    };
+    drop(bar);
+    driver.runtime_mut().borrow().gc_collect();
+    assert_eq!(baz.get::<u8>("a"), Ok(4));
+
+    assert_eq!(
+        baz.get_dyn("b").err(),
+        Some("Struct `Baz` does not contain field `b`.".to_string())
+    );
+}
+
+#[test]
+fn struct_field_observers() {
+    let mut driver = TestDriver::new(
+        r#"
+    struct(gc) Foo { a: i32, b: f64 };
+    struct(value) Bar { a: i32 };
+
+    pub fn foo_new(a: i32, b: f64) -> Foo { Foo { a, b } }
+    pub fn bar_new(a: i32) -> Bar { Bar { a } }
+    "#,
+    );
+
+    let mut foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", 1i32, 2.0f64).unwrap();
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    for field_name in ["a", "b"].iter().copied() {
+        let changes = changes.clone();
+        foo.on_field_changed(field_name, move |foo| {
+            changes.borrow_mut().push((
+                field_name,
+                foo.get::<i32>("a").unwrap(),
+                foo.get::<f64>("b").unwrap(),
+            ))
+        })
+        .unwrap();
+    }
+
+    foo.set("a", 3i32).unwrap();
+    assert_eq!(changes.replace(Vec::new()), [("a", 3, 2.0)]);
+
+    // All fields are written before their observers are notified, in the order of the fields
+    foo.set_all(&[("b", DynValue::from(4.0f64)), ("a", DynValue::from(5i32))])
+        .unwrap();
+    assert_eq!(changes.replace(Vec::new()), [("b", 5, 4.0), ("a", 5, 4.0)]);
+
+    // Observers belong to the struct, not to the `StructRef`
+    let mut alias = foo.clone();
+    assert_eq!(alias.replace("b", 6.0f64), Ok(4.0));
+    assert_eq!(changes.replace(Vec::new()), [("b", 5, 6.0)]);
+
+    let mut other: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", 1i32, 2.0f64).unwrap();
+    other.set("a", 7i32).unwrap();
+    assert!(changes.borrow().is_empty());
+
+    // Only the fields whose bytes were changed are notified
+    let offset = foo.offset_of("b").unwrap();
+    unsafe {
+        foo.with_bytes_mut(|bytes| {
+            bytes[offset..offset + 8].copy_from_slice(&9.0f64.to_ne_bytes());
+        })
+    }
+    .unwrap();
+    assert_eq!(changes.replace(Vec::new()), [("b", 5, 9.0)]);
+    assert_eq!(foo.replace("b", 6.0f64), Ok(9.0));
+    changes.borrow_mut().clear();
+
+    foo.clear_field_observers("a");
+    foo.set("a", 8i32).unwrap();
+    assert!(changes.borrow().is_empty());
+    assert_eq!(foo.replace_with("b", |b: f64| b + 1.0), Ok(7.0));
+    assert_eq!(changes.replace(Vec::new()), [("b", 8, 7.0)]);
+
+    assert_eq!(
+        foo.on_field_changed("c", |_| ()),
+        Err("Struct `Foo` does not contain field `c`.".to_string())
+    );
+    let bar: StructRef = invoke_fn!(driver.runtime_mut(), "bar_new", 1i32).unwrap();
+    assert_eq!(
+        bar.on_field_changed("a", |_| ()),
+        Err("Cannot observe the fields of value struct `Bar`.".to_string())
+    );
+}
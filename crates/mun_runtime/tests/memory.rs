@@ -1,4 +1,17 @@
-use mun_runtime::{invoke_fn, StructRef};
+#[cfg(any(debug_assertions, feature = "heap-validation"))]
+use mun_runtime::ValidationError;
+use mun_runtime::{
+    invoke_fn, FieldRemap, HeapSnapshotError, Runtime, StructRef, SyncStructRef, TypeUsage,
+};
+use std::{
+    ffi::c_void,
+    os::raw::c_char,
+    ptr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
 
 #[macro_use]
 mod util;
@@ -40,6 +53,413 @@ fn gc_trace() {
     assert_eq!(driver.runtime_mut().borrow().gc_stats().allocated_memory, 0);
 }
 
+#[test]
+fn gc_disabled() {
+    let mut driver = TestDriver::new(
+        r#"
+    pub struct Foo { a: i64 }
+
+    pub fn new_foo() -> Foo { Foo { a: 1 } }
+    "#,
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "new_foo").unwrap();
+    drop(foo);
+
+    let runtime = driver.runtime_mut().borrow();
+    runtime.with_gc_disabled(|| {
+        assert!(!runtime.gc_collect());
+        runtime.with_gc_disabled(|| assert!(!runtime.gc_collect()));
+        assert!(!runtime.gc_collect());
+    });
+    assert!(runtime.gc_stats().allocated_memory > 0);
+    assert!(runtime.gc_collect());
+    assert_eq!(runtime.gc_stats().allocated_memory, 0);
+}
+
+#[test]
+fn gc_shrink_heap() {
+    let mut driver = TestDriver::new(
+        r#"
+    pub struct Foo { a: i64 }
+
+    pub fn new_foo() -> Foo { Foo { a: 1 } }
+    "#,
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "new_foo").unwrap();
+    for _ in 0..100 {
+        let _foo: StructRef = invoke_fn!(driver.runtime_mut(), "new_foo").unwrap();
+    }
+
+    driver.runtime_mut().borrow().shrink_heap();
+    let allocated_memory = driver.runtime_mut().borrow().gc_stats().allocated_memory;
+    assert_eq!(allocated_memory, std::mem::size_of::<i64>());
+    assert_eq!(foo.get::<i64>("a"), Ok(1));
+}
+
+#[test]
+fn gc_drops_attached_resources() {
+    struct Resource(Arc<AtomicUsize>);
+
+    impl Drop for Resource {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let mut driver = TestDriver::new(
+        r#"
+    pub struct Foo { a: i64 }
+    pub struct(value) Bar { a: i64 }
+
+    pub fn new_foo() -> Foo { Foo { a: 1 } }
+    pub fn new_bar() -> Bar { Bar { a: 1 } }
+    "#,
+    );
+
+    let drops = Arc::new(AtomicUsize::new(0));
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "new_foo").unwrap();
+    foo.attach_resource(Resource(drops.clone())).unwrap();
+    foo.attach_resource(5u32).unwrap();
+
+    // A relinquished resource is owned by the host again
+    assert_eq!(foo.take_resource::<u32>(), Some(5));
+    assert_eq!(foo.take_resource::<u32>(), None);
+
+    driver.runtime_mut().borrow().gc_collect();
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+
+    drop(foo);
+    driver.runtime_mut().borrow().gc_collect();
+    assert_eq!(drops.load(Ordering::SeqCst), 1);
+
+    let bar: StructRef = invoke_fn!(driver.runtime_mut(), "new_bar").unwrap();
+    assert_eq!(
+        bar.attach_resource(Resource(drops.clone())),
+        Err("Cannot attach a resource to value struct `Bar`.".to_string())
+    );
+    // The rejected resource is dropped immediately
+    assert_eq!(drops.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn gc_resource_drop_unroots_struct() {
+    // Dropping the resource unroots `bar` while the garbage collector is collecting
+    struct Resource {
+        _bar: SyncStructRef,
+    }
+
+    let mut driver = TestDriver::new(
+        r#"
+    pub struct Foo { a: i64 }
+    pub struct Bar { b: i64 }
+
+    pub fn new_foo() -> Foo { Foo { a: 1 } }
+    pub fn new_bar() -> Bar { Bar { b: 2 } }
+    "#,
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "new_foo").unwrap();
+    let bar: StructRef = invoke_fn!(driver.runtime_mut(), "new_bar").unwrap();
+    foo.attach_resource(Resource {
+        _bar: bar.into_sync(),
+    })
+    .unwrap();
+
+    drop(foo);
+    assert!(driver.runtime_mut().borrow().gc_collect());
+    assert!(driver.runtime_mut().borrow().gc_stats().allocated_memory > 0);
+
+    // `bar` is no longer rooted
+    assert!(driver.runtime_mut().borrow().gc_collect());
+    assert_eq!(driver.runtime_mut().borrow().gc_stats().allocated_memory, 0);
+}
+
+static OPEN_CONNECTIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe extern "C" fn open_connection(arg: *mut c_void) -> *mut c_void {
+    if !arg.is_null() {
+        OPEN_CONNECTIONS.fetch_add(1, Ordering::SeqCst);
+    }
+    arg
+}
+
+unsafe extern "C" fn close_connection(_handle: *mut c_void) {
+    OPEN_CONNECTIONS.fetch_sub(1, Ordering::SeqCst);
+}
+
+#[test]
+fn gc_drops_attached_handles() {
+    let mut driver = TestDriver::new(
+        r#"
+    pub struct Foo { a: i64 }
+
+    pub fn new_foo() -> Foo { Foo { a: 1 } }
+    "#,
+    )
+    .register_handle_type(abi::HandleType {
+        name: b"Connection\0".as_ptr() as *const c_char,
+        create: Some(open_connection),
+        drop: Some(close_connection),
+    });
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "new_foo").unwrap();
+    let mut connections = [1u32, 2u32];
+    let first = &mut connections[0] as *mut u32 as *mut c_void;
+    let second = &mut connections[1] as *mut u32 as *mut c_void;
+    assert_eq!(unsafe { foo.create_handle("Connection", first) }, Ok(first));
+    assert_eq!(
+        unsafe { foo.create_handle("Connection", second) },
+        Ok(second)
+    );
+    assert_eq!(OPEN_CONNECTIONS.load(Ordering::SeqCst), 2);
+
+    assert_eq!(
+        unsafe { foo.create_handle("File", first) },
+        Err("Unknown handle type `File`.".to_string())
+    );
+    assert_eq!(
+        unsafe { foo.create_handle("Connection", ptr::null_mut()) },
+        Err("Failed to create a handle of type `Connection`.".to_string())
+    );
+
+    // A relinquished handle is owned by the host again
+    assert_eq!(foo.take_handle("File"), None);
+    assert_eq!(foo.take_handle("Connection"), Some(first));
+    assert_eq!(OPEN_CONNECTIONS.load(Ordering::SeqCst), 2);
+    unsafe { close_connection(first) };
+
+    drop(foo);
+    driver.runtime_mut().borrow().gc_collect();
+    assert_eq!(OPEN_CONNECTIONS.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn gc_defers_resource_drops() {
+    struct Resource(Arc<AtomicUsize>);
+
+    impl Drop for Resource {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let mut driver = TestDriver::new(
+        r#"
+    pub struct Foo { a: i64 }
+
+    pub fn new_foo() -> Foo { Foo { a: 1 } }
+    "#,
+    )
+    .set_deferred_resource_drops(true);
+
+    let drops = Arc::new(AtomicUsize::new(0));
+    for _ in 0..3 {
+        let foo: StructRef = invoke_fn!(driver.runtime_mut(), "new_foo").unwrap();
+        foo.attach_resource(Resource(drops.clone())).unwrap();
+    }
+    assert_eq!(driver.runtime_mut().borrow().run_pending_finalizers(10), 0);
+
+    // The resources are queued when their structs are collected
+    driver.runtime_mut().borrow().gc_collect();
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+
+    let runtime = driver.runtime_mut().borrow();
+    assert_eq!(runtime.run_pending_finalizers(2), 1);
+    assert_eq!(drops.load(Ordering::SeqCst), 2);
+    assert_eq!(runtime.run_pending_finalizers(2), 0);
+    assert_eq!(drops.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn gc_rooted_raw_structs() {
+    let mut driver = TestDriver::new(
+        r#"
+    pub struct Foo { a: i64 }
+
+    pub fn new_foo(a: i64) -> Foo { Foo { a } }
+    "#,
+    );
+
+    let raw_foos: Vec<_> = (0..10i64)
+        .map(|a| {
+            let foo: StructRef = invoke_fn!(driver.runtime_mut(), "new_foo", a).unwrap();
+            foo.into_rooted_raw()
+        })
+        .collect();
+
+    let runtime = driver.runtime_mut().clone();
+    runtime.borrow().gc_collect();
+    assert_eq!(
+        runtime.borrow().gc_stats().allocated_memory,
+        10 * std::mem::size_of::<i64>()
+    );
+
+    // An additional root requires an additional unroot
+    unsafe { runtime.borrow().root(&raw_foos[0]) };
+    for raw in raw_foos.iter() {
+        unsafe { runtime.borrow().unroot(raw) };
+    }
+    runtime.borrow().gc_collect();
+    assert_eq!(
+        runtime.borrow().gc_stats().allocated_memory,
+        std::mem::size_of::<i64>()
+    );
+
+    // Ownership of the remaining root is transferred to the `StructRef`
+    let foo = unsafe { StructRef::from_rooted_raw(runtime.clone(), raw_foos[0].clone()) };
+    assert_eq!(foo.get::<i64>("a"), Ok(0));
+    drop(foo);
+    runtime.borrow().gc_collect();
+    assert_eq!(runtime.borrow().gc_stats().allocated_memory, 0);
+}
+
+#[test]
+fn gc_memory_usage() {
+    let mut driver = TestDriver::new(
+        r#"
+    pub struct Foo {
+        bar: Bar,
+        baz: Bar,
+    }
+
+    pub struct Bar { a: i64 }
+
+    pub fn new_foo() -> Foo {
+        Foo { bar: Bar { a: 1 }, baz: Bar { a: 2 } }
+    }
+    "#,
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "new_foo").unwrap();
+    let usage = driver.runtime_mut().borrow().memory_usage();
+    assert_eq!(usage.len(), 2);
+    assert_eq!(
+        usage["Foo"],
+        TypeUsage {
+            count: 1,
+            bytes: 16
+        }
+    );
+    assert_eq!(
+        usage["Bar"],
+        TypeUsage {
+            count: 2,
+            bytes: 16
+        }
+    );
+
+    // Unreachable structs are included until they are collected
+    drop(foo);
+    assert_eq!(driver.runtime_mut().borrow().memory_usage().len(), 2);
+    assert!(driver.runtime_mut().borrow().gc_collect());
+    assert!(driver.runtime_mut().borrow().memory_usage().is_empty());
+}
+
+#[test]
+#[cfg(any(debug_assertions, feature = "heap-validation"))]
+fn gc_validate_heap() {
+    let mut driver = TestDriver::new(
+        r#"
+    pub struct Foo {
+        bar: Bar,
+        baz: Baz,
+    }
+
+    pub struct Bar { a: i64 }
+    pub struct Baz { b: f64 }
+
+    pub fn new_foo() -> Foo {
+        Foo { bar: Bar { a: 1 }, baz: Baz { b: 2.0 } }
+    }
+    "#,
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "new_foo").unwrap();
+    let runtime = driver.runtime_mut().borrow();
+    assert!(runtime.validate_heap().is_empty());
+
+    let layout = runtime.struct_layout("Foo").unwrap();
+    let pinned = foo.pin();
+    let field_ptr = |idx: usize| unsafe { pinned.as_ptr().add(layout[idx].offset) as *mut usize };
+    let bar = unsafe { *field_ptr(0) };
+    let baz = unsafe { *field_ptr(1) };
+
+    // Reference an object of the wrong type
+    unsafe { *field_ptr(0) = baz };
+    let errors = runtime.validate_heap();
+    assert_eq!(errors.len(), 1);
+    match &errors[0] {
+        ValidationError::MismatchedType {
+            field,
+            expected,
+            found,
+            ..
+        } => {
+            assert_eq!(field, "bar");
+            assert_eq!(expected, "Bar");
+            assert_eq!(found, "Baz");
+        }
+        error => panic!("unexpected error: {:?}", error),
+    }
+
+    // Reference memory that is not an object
+    let not_an_object = 0usize;
+    unsafe { *field_ptr(0) = &not_an_object as *const usize as usize };
+    let errors = runtime.validate_heap();
+    assert_eq!(errors.len(), 1);
+    match &errors[0] {
+        ValidationError::DanglingPointer { field, .. } => assert_eq!(field, "bar"),
+        error => panic!("unexpected error: {:?}", error),
+    }
+
+    unsafe { *field_ptr(0) = bar };
+    assert!(runtime.validate_heap().is_empty());
+}
+
+#[test]
+fn gc_age() {
+    let mut driver = TestDriver::new(
+        r#"
+    pub struct Foo {
+        bar: i64,
+    }
+
+    pub fn new_foo() -> Foo {
+        Foo { bar: 1 }
+    }
+    "#,
+    )
+    .set_gc_age_tracking(true);
+
+    let value: StructRef = invoke_fn!(driver.runtime_mut(), "new_foo").unwrap();
+    assert_eq!(value.gc_age(), Some(0));
+
+    driver.runtime_mut().borrow().gc_collect();
+    driver.runtime_mut().borrow().gc_collect();
+    assert_eq!(value.gc_age(), Some(2));
+}
+
+#[test]
+fn gc_age_disabled() {
+    let mut driver = TestDriver::new(
+        r#"
+    pub struct Foo {
+        bar: i64,
+    }
+
+    pub fn new_foo() -> Foo {
+        Foo { bar: 1 }
+    }
+    "#,
+    );
+
+    let value: StructRef = invoke_fn!(driver.runtime_mut(), "new_foo").unwrap();
+    driver.runtime_mut().borrow().gc_collect();
+    assert_eq!(value.gc_age(), None);
+}
+
 #[test]
 fn map_struct_insert_field1() {
     let mut driver = TestDriver::new(
@@ -73,6 +493,41 @@ fn map_struct_insert_field1() {
     assert_eq!(foo.get::<f64>("c").unwrap(), c);
 }
 
+#[test]
+fn map_struct_insert_field_default() {
+    let mut driver = TestDriver::new(
+        r#"
+        struct Foo {
+            b: i64,
+        }
+
+        pub fn foo_new(b: i64) -> Foo {
+            Foo { b }
+        }
+    "#,
+    );
+
+    let b = 5i64;
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "foo_new", b).unwrap();
+
+    driver.update(
+        r#"
+        struct Foo {
+            a: i32 = -2,
+            b: i64,
+            scale: f64 = 1.0,
+            enabled: bool = true,
+            c: f64,
+        }
+    "#,
+    );
+    assert_eq!(foo.get::<i32>("a").unwrap(), -2);
+    assert_eq!(foo.get::<i64>("b").unwrap(), b);
+    assert_eq!(foo.get::<f64>("scale").unwrap(), 1.0);
+    assert!(foo.get::<bool>("enabled").unwrap());
+    assert_eq!(foo.get::<f64>("c").unwrap(), 0.0);
+}
+
 #[test]
 fn map_struct_insert_field2() {
     let mut driver = TestDriver::new(
@@ -487,6 +942,74 @@ fn map_struct_all() {
     assert_eq!(foo.get::<i32>("f").unwrap(), 0);
 }
 
+#[test]
+fn map_struct_remapping() {
+    let mut driver = TestDriver::new(
+        r#"
+        struct Foo {
+            a: i32,
+            b: f64,
+            c: i32,
+        }
+
+        struct Bar {
+            a: i32,
+        }
+
+        pub fn foo_new(a: i32, b: f64, c: i32) -> Foo {
+            Foo { a, b, c }
+        }
+
+        pub fn bar_new(a: i32) -> Bar {
+            Bar { a }
+        }
+    "#,
+    );
+    assert!(driver
+        .runtime_mut()
+        .borrow()
+        .last_reload_remapping()
+        .is_empty());
+
+    driver.update(
+        r#"
+        struct Foo {
+            a: i32,
+            c: i64,
+            d: i32 = 3,
+            e: bool,
+        }
+
+        struct Bar {
+            a: i32,
+        }
+
+        pub fn bar_new(a: i32) -> Bar {
+            Bar { a }
+        }
+    "#,
+    );
+
+    let runtime = driver.runtime_mut().borrow();
+    let remapping = runtime.last_reload_remapping();
+    // Struct types whose layout did not change are not reported
+    assert!(!remapping.contains_key("Bar"));
+    assert_eq!(
+        remapping["Foo"],
+        [
+            ("a".to_string(), FieldRemap::Copied),
+            (
+                "c".to_string(),
+                FieldRemap::Converted {
+                    old_type: "core::i32".to_string()
+                }
+            ),
+            ("d".to_string(), FieldRemap::Defaulted),
+            ("e".to_string(), FieldRemap::ZeroInitialized),
+        ]
+    );
+}
+
 #[test]
 fn delete_used_struct() {
     let mut driver = TestDriver::new(
@@ -779,3 +1302,136 @@ fn insert_struct() {
     let d = foo.get::<StructRef>("d").unwrap();
     assert_eq!(d.get::<f64>("0"), Ok(0.0));
 }
+
+#[test]
+fn gc_scope() {
+    let mut driver = TestDriver::new(
+        r#"
+    pub struct Foo {
+        bar: i64,
+    }
+
+    pub fn new_foo(bar: i64) -> Foo {
+        Foo { bar }
+    }
+    "#,
+    );
+
+    let runtime = driver.runtime_mut().clone();
+    let escaped = runtime.borrow().scope(|_scope| -> StructRef {
+        for i in 0..10i64 {
+            let _: StructRef = invoke_fn!(runtime, "new_foo", i).unwrap();
+        }
+        assert!(runtime.borrow().gc_stats().allocated_memory > 0);
+
+        invoke_fn!(runtime, "new_foo", 42i64).unwrap()
+    });
+
+    // Only the struct that is still referenced survives the scope
+    assert_eq!(
+        runtime.borrow().gc_stats().allocated_memory,
+        std::mem::size_of::<i64>()
+    );
+    assert_eq!(escaped.get::<i64>("bar").unwrap(), 42);
+
+    drop(escaped);
+    assert!(runtime.borrow().gc_collect());
+    assert_eq!(runtime.borrow().gc_stats().allocated_memory, 0);
+}
+
+#[test]
+fn heap_snapshot() {
+    let mut driver = TestDriver::new(
+        r#"
+    pub struct Foo {
+        a: i64,
+        bar: Bar,
+        baz: Bar,
+    }
+
+    pub struct Bar {
+        b: f64,
+    }
+
+    pub fn new_foo(a: i64, b: f64) -> Foo {
+        let bar = Bar { b };
+        Foo { a, bar, baz: bar }
+    }
+    "#,
+    );
+
+    let foo: StructRef = invoke_fn!(driver.runtime_mut(), "new_foo", 1i64, 2.0f64).unwrap();
+    let snapshot = driver.runtime_mut().borrow().snapshot();
+
+    let restored = Runtime::restore(driver.runtime_mut(), &snapshot).unwrap();
+    assert_eq!(restored.len(), 1);
+    let mut restored_foo = restored.into_iter().next().unwrap();
+    assert_eq!(restored_foo.get::<i64>("a"), Ok(1));
+
+    // The restored struct is a copy
+    restored_foo.set("a", 3i64).unwrap();
+    assert_eq!(foo.get::<i64>("a"), Ok(1));
+
+    // Shared references are preserved
+    let mut bar: StructRef = restored_foo.get("bar").unwrap();
+    assert_eq!(bar.get::<f64>("b"), Ok(2.0));
+    bar.set("b", 4.0f64).unwrap();
+    let baz: StructRef = restored_foo.get("baz").unwrap();
+    assert_eq!(baz.get::<f64>("b"), Ok(4.0));
+    let foo_bar: StructRef = foo.get("bar").unwrap();
+    assert_eq!(foo_bar.get::<f64>("b"), Ok(2.0));
+
+    assert_eq!(
+        Runtime::restore(driver.runtime_mut(), &snapshot[..snapshot.len() - 1]).err(),
+        Some(HeapSnapshotError::InvalidData)
+    );
+    let mut future_snapshot = snapshot.clone();
+    future_snapshot[8] = 2;
+    assert_eq!(
+        Runtime::restore(driver.runtime_mut(), &future_snapshot).err(),
+        Some(HeapSnapshotError::UnsupportedVersion { version: 2 })
+    );
+
+    // Snapshots survive reloads that do not change the layout of their types
+    drop((foo, restored_foo, bar, baz, foo_bar));
+    driver.update(
+        r#"
+    pub struct Foo {
+        a: i64,
+        bar: Bar,
+        baz: Bar,
+    }
+
+    pub struct Bar {
+        b: f64,
+    }
+
+    pub fn new_foo(a: i64, b: f64) -> Foo {
+        Foo { a, bar: Bar { b }, baz: Bar { b } }
+    }
+    "#,
+    );
+    let restored = Runtime::restore(driver.runtime_mut(), &snapshot).unwrap();
+    assert_eq!(restored[0].get::<i64>("a"), Ok(1));
+    drop(restored);
+
+    driver.update(
+        r#"
+    pub struct Foo {
+        a: i32,
+        bar: Bar,
+        baz: Bar,
+    }
+
+    pub struct Bar {
+        b: f64,
+    }
+    "#,
+    );
+    assert_eq!(
+        Runtime::restore(driver.runtime_mut(), &snapshot).err(),
+        Some(HeapSnapshotError::IncompatibleType {
+            type_name: "Foo".to_string()
+        })
+    );
+}
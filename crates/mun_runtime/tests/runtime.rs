@@ -1,6 +1,11 @@
+#[macro_use]
 mod util;
 
-use std::io;
+use mun_runtime::{
+    invoke_fn, LayoutMismatch, RetryResultExt, ReturnTypeReflection, Runtime, SignatureError,
+    StructRef,
+};
+use std::{io, time::Duration};
 use util::*;
 
 #[test]
@@ -25,6 +30,94 @@ fn error_assembly_not_linkable() {
     );
 }
 
+#[test]
+fn register_host_fn() {
+    extern "C" fn dependency() -> i32 {
+        7
+    }
+
+    extern "C" fn dependency_invalid(a: i32) -> i32 {
+        a
+    }
+
+    let mut driver = TestDriver::new(
+        r"
+    pub fn main() -> i32 { 1 }
+    ",
+    );
+    let plugin = TestDriver::new(
+        r"
+    extern fn dependency() -> i32;
+
+    pub fn plugin() -> i32 { dependency() }
+    ",
+    );
+
+    // The plugin cannot be linked before its dependency is registered
+    assert!(driver
+        .runtime_mut()
+        .borrow_mut()
+        .load_library(plugin.out_path())
+        .is_err());
+
+    // A function with a mismatching signature is detected when the plugin is loaded
+    driver
+        .runtime_mut()
+        .borrow_mut()
+        .register_host_fn(
+            "dependency",
+            dependency_invalid as extern "C" fn(i32) -> i32,
+        )
+        .unwrap();
+    assert!(driver
+        .runtime_mut()
+        .borrow_mut()
+        .load_library(plugin.out_path())
+        .is_err());
+
+    // Names of existing functions cannot be registered
+    assert_eq!(
+        driver
+            .runtime_mut()
+            .borrow_mut()
+            .register_host_fn("main", dependency as extern "C" fn() -> i32)
+            .map_err(|e| e.to_string()),
+        Err("Failed to register: function `main` already exists.".to_string())
+    );
+}
+
+#[test]
+fn register_host_fn_after_spawn() {
+    extern "C" fn dependency() -> i32 {
+        7
+    }
+
+    let mut driver = TestDriver::new(
+        r"
+    pub fn main() -> i32 { 1 }
+    ",
+    );
+    let plugin = TestDriver::new(
+        r"
+    extern fn dependency() -> i32;
+
+    pub fn plugin() -> i32 { dependency() }
+    ",
+    );
+
+    driver
+        .runtime_mut()
+        .borrow_mut()
+        .register_host_fn("dependency", dependency as extern "C" fn() -> i32)
+        .unwrap();
+    driver
+        .runtime_mut()
+        .borrow_mut()
+        .load_library(plugin.out_path())
+        .unwrap();
+    assert_invoke_eq!(i32, 7, driver, "plugin");
+}
+
 #[test]
 fn arg_missing_bug() {
     let mut driver = TestDriver::new(
@@ -49,3 +142,445 @@ fn arg_missing_bug() {
 
     driver.spawn().unwrap()
 }
+
+#[test]
+fn bounded_retry() {
+    let mut driver = TestDriver::new(
+        r"
+    pub fn main() -> i32 { 5 }
+    ",
+    );
+
+    let result: Result<i32, _> = invoke_fn!(driver.runtime_mut(), "main");
+    assert_eq!(result.wait_for(Duration::from_millis(10)).unwrap(), 5);
+
+    let result: Result<i32, _> = invoke_fn!(driver.runtime_mut(), "missing");
+    assert!(result.wait_timeout(0).is_err());
+
+    let result: Result<i32, _> = invoke_fn!(driver.runtime_mut(), "missing");
+    let err = result.wait_for(Duration::from_millis(10)).unwrap_err();
+    assert_eq!(err.to_string(), "Failed to obtain function 'missing'");
+}
+
+#[test]
+fn load_and_unload_library() {
+    let mut driver = TestDriver::new(
+        r"
+    pub fn main() -> i32 { 1 }
+    ",
+    );
+    let plugin = TestDriver::new(
+        r"
+    pub fn plugin() -> i32 { 2 }
+    ",
+    );
+
+    let id = driver
+        .runtime_mut()
+        .borrow_mut()
+        .load_library(plugin.out_path())
+        .unwrap();
+    assert_invoke_eq!(i32, 2, driver, "plugin");
+
+    driver
+        .runtime_mut()
+        .borrow_mut()
+        .unload_library(id)
+        .unwrap();
+    assert!(driver
+        .runtime_mut()
+        .borrow()
+        .get_function_definition("plugin")
+        .is_none());
+    assert_invoke_eq!(i32, 1, driver, "main");
+
+    // A library can only be unloaded once
+    assert!(driver
+        .runtime_mut()
+        .borrow_mut()
+        .unload_library(id)
+        .is_err());
+}
+
+#[test]
+fn error_ambiguous_library() {
+    let mut driver = TestDriver::new(
+        r"
+    pub fn main() -> i32 { 1 }
+    ",
+    );
+    let plugin = TestDriver::new(
+        r"
+    pub fn main() -> i32 { 2 }
+    ",
+    );
+
+    let err = driver
+        .runtime_mut()
+        .borrow_mut()
+        .load_library(plugin.out_path())
+        .unwrap_err();
+    assert_eq!(
+        format!("{}", err),
+        "Failed to load: function `main` is already defined by another library."
+    );
+
+    // A library that fails to load leaves the runtime unchanged
+    assert_invoke_eq!(i32, 1, driver, "main");
+}
+
+#[test]
+fn error_conflicting_type_library() {
+    let mut driver = TestDriver::new(
+        r"
+    struct Foo { a: i32 }
+
+    pub fn main() -> i32 { Foo { a: 1 }.a }
+    ",
+    );
+    let plugin = TestDriver::new(
+        r"
+    struct Foo { b: f64 }
+
+    pub fn plugin() -> f64 { Foo { b: 2.0 }.b }
+    ",
+    );
+
+    let err = driver
+        .runtime_mut()
+        .borrow_mut()
+        .load_library(plugin.out_path())
+        .unwrap_err();
+    assert_eq!(
+        format!("{}", err),
+        "Failed to load: type `Foo` is already defined by another library."
+    );
+    assert!(driver
+        .runtime_mut()
+        .borrow()
+        .get_function_definition("plugin")
+        .is_none());
+
+    // Identical definitions of a type do not conflict
+    let plugin = TestDriver::new(
+        r"
+    struct Foo { a: i32 }
+
+    pub fn plugin() -> i32 { Foo { a: 2 }.a }
+    ",
+    );
+    driver
+        .runtime_mut()
+        .borrow_mut()
+        .load_library(plugin.out_path())
+        .unwrap();
+    assert_invoke_eq!(i32, 2, driver, "plugin");
+}
+
+#[test]
+fn exported_fn_symbols() {
+    let driver = TestDriver::new(
+        r"
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+    ",
+    );
+
+    let library = libloading::Library::new(driver.out_path()).unwrap();
+    let symbol = format!("{}\0", abi::fn_symbol_name("add"));
+    assert_eq!(symbol, "mun$add\0");
+    let add: libloading::Symbol<extern "C" fn(i32, i32) -> i32> =
+        unsafe { library.get(symbol.as_bytes()) }.unwrap();
+    assert_eq!(add(2, 3), 5);
+}
+
+#[test]
+fn exported_abi_version() {
+    let driver = TestDriver::new(
+        r"
+    pub fn main() {}
+    ",
+    );
+
+    let library = libloading::Library::new(driver.out_path()).unwrap();
+    let get_version: libloading::Symbol<extern "C" fn() -> u32> =
+        unsafe { library.get(b"get_version") }.unwrap();
+    assert_eq!(get_version(), abi::ABI_VERSION);
+}
+
+#[test]
+fn module_opt_level() {
+    let mut driver = TestDriver::new(
+        r"
+    pub fn main() {}
+    ",
+    );
+    let plugin = TestDriver::new(
+        r"
+    pub fn plugin() -> i32 { 2 }
+    ",
+    );
+
+    let id = driver
+        .runtime_mut()
+        .borrow_mut()
+        .load_library(plugin.out_path())
+        .unwrap();
+    // The test driver compiles with the default optimization level
+    assert_eq!(driver.runtime_mut().borrow().module_opt_level(id), Some(2));
+
+    driver
+        .runtime_mut()
+        .borrow_mut()
+        .unload_library(id)
+        .unwrap();
+    assert_eq!(driver.runtime_mut().borrow().module_opt_level(id), None);
+}
+
+#[test]
+fn verify_module() {
+    let mut driver = TestDriver::new(
+        r"
+    struct(value) Foo { a: u8, b: f64 }
+    struct(gc) Bar { foo: Foo, bar: Bar, c: i16 }
+
+    pub fn bar_c(bar: Bar) -> i16 { bar.c }
+    pub fn foo_new(a: u8, b: f64) -> Foo { Foo { a, b } }
+    pub fn main() {}
+    ",
+    );
+
+    assert_eq!(driver.runtime_mut().borrow().verify_module(), Ok(()));
+}
+
+#[test]
+fn type_kind_predicates() {
+    let mut driver = TestDriver::new(
+        r"
+    struct(value) Foo { a: u8 }
+    struct(gc) Bar { foo: Foo }
+
+    pub fn bar_new() -> Bar { Bar { foo: Foo { a: 1 } } }
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+    ",
+    );
+
+    let runtime = driver.runtime_mut().borrow();
+    assert!(runtime.is_struct_type("Foo"));
+    assert!(runtime.is_struct_type("Bar"));
+    assert!(!runtime.is_fundamental_type("Bar"));
+    assert!(runtime.is_fundamental_type("core::i32"));
+    assert!(!runtime.is_struct_type("core::i32"));
+    assert!(!runtime.is_struct_type("Baz"));
+    assert!(!runtime.is_fundamental_type("Baz"));
+}
+
+#[test]
+fn is_current_thread() {
+    struct RuntimePtr(*const Runtime);
+    unsafe impl Send for RuntimePtr {}
+
+    let mut driver = TestDriver::new(
+        r"
+    pub fn main() -> i32 { 5 }
+    ",
+    );
+    assert!(driver.runtime_mut().borrow().is_current_thread());
+
+    let runtime = RuntimePtr(&*driver.runtime_mut().borrow());
+    let on_other_thread = std::thread::spawn(move || {
+        let runtime = runtime;
+        unsafe { &*runtime.0 }.is_current_thread()
+    })
+    .join()
+    .unwrap();
+    assert!(!on_other_thread);
+}
+
+#[test]
+fn invoke_batch() {
+    let mut driver = TestDriver::new(
+        r"
+    struct Foo { a: i32 }
+
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+    pub fn foo_new(a: i32) -> Foo { Foo { a } }
+    pub fn main() -> i32 { 5 }
+    ",
+    );
+
+    let results: Vec<i32> =
+        Runtime::invoke_batch(driver.runtime_mut(), "add", (0..4).map(|i| (i, 10))).unwrap();
+    assert_eq!(results, [10, 11, 12, 13]);
+
+    let results: Vec<i32> =
+        Runtime::invoke_batch(driver.runtime_mut(), "main", vec![(), ()]).unwrap();
+    assert_eq!(results, [5, 5]);
+
+    let foos: Vec<StructRef> =
+        Runtime::invoke_batch(driver.runtime_mut(), "foo_new", vec![(1,), (2,)]).unwrap();
+    assert_eq!(foos[0].get::<i32>("a"), Ok(1));
+    assert_eq!(foos[1].get::<i32>("a"), Ok(2));
+
+    let mut handle = driver
+        .runtime_mut()
+        .borrow()
+        .resolve::<(i32, i32), i32>("add")
+        .unwrap();
+    assert_eq!(
+        handle.call_batch(driver.runtime_mut(), vec![(1, 2), (3, 4)]),
+        Ok(vec![3, 7])
+    );
+    assert_eq!(
+        handle.call_batch(driver.runtime_mut(), Vec::new()),
+        Ok(Vec::new())
+    );
+
+    assert_eq!(
+        Runtime::invoke_batch::<(i32,), i32, _>(driver.runtime_mut(), "add", vec![(1,)]),
+        Err(SignatureError::ArgumentCount {
+            expected: 2,
+            found: 1
+        })
+    );
+}
+
+#[test]
+fn function_source_location() {
+    let mut driver = TestDriver::with_debug_info(
+        r"
+    pub fn main() -> i32 { add(1, 2) }
+
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+    ",
+    );
+
+    let runtime = driver.runtime_mut().borrow();
+    let fn_ptr = runtime.get_function_definition("add").unwrap().fn_ptr;
+    let location = runtime
+        .function_source_location(fn_ptr)
+        .expect("Expected a source location for `add`.");
+    assert_eq!(location.name(), "add");
+    assert_eq!(location.file(), "main.mun");
+    assert_eq!(location.line, 4);
+}
+
+#[test]
+fn function_source_location_disabled() {
+    let mut driver = TestDriver::new(
+        r"
+    pub fn main() -> i32 { 1 }
+    ",
+    );
+
+    let runtime = driver.runtime_mut().borrow();
+    let fn_ptr = runtime.get_function_definition("main").unwrap().fn_ptr;
+    assert!(runtime.function_source_location(fn_ptr).is_none());
+}
+
+#[test]
+fn validate_signature() {
+    let mut driver = TestDriver::new(
+        r"
+    struct Foo { a: i32 }
+
+    pub fn add(a: i32, b: i32) -> i32 { a + b }
+    pub fn foo_new(a: i32) -> Foo { Foo { a } }
+    pub fn half(a: f64) -> f64 { a * 0.5 }
+    pub fn main() {}
+    ",
+    );
+
+    let runtime = driver.runtime_mut().borrow();
+    let i32_guid = <i32 as ReturnTypeReflection>::type_guid();
+    let f64_guid = <f64 as ReturnTypeReflection>::type_guid();
+
+    let function_info = runtime
+        .validate_signature("add", &[i32_guid, i32_guid], Some(i32_guid))
+        .unwrap();
+    assert_eq!(function_info.prototype.name(), "add");
+
+    assert!(runtime.validate_signature("main", &[], None).is_ok());
+    assert!(runtime
+        .validate_signature(
+            "foo_new",
+            &[i32_guid],
+            Some(<StructRef as ReturnTypeReflection>::type_guid())
+        )
+        .is_ok());
+
+    assert_eq!(
+        runtime.validate_signature("sub", &[], None).unwrap_err(),
+        SignatureError::FunctionNotFound {
+            function_name: "sub".to_string()
+        }
+    );
+    assert_eq!(
+        runtime
+            .validate_signature("add", &[i32_guid], Some(i32_guid))
+            .unwrap_err(),
+        SignatureError::ArgumentCount {
+            expected: 2,
+            found: 1
+        }
+    );
+    assert_eq!(
+        runtime
+            .validate_signature("add", &[i32_guid, f64_guid], Some(i32_guid))
+            .unwrap_err()
+            .to_string(),
+        "Invalid argument type at index 1. Expected: core::i32. Found: core::f64."
+    );
+    assert_eq!(
+        runtime
+            .validate_signature("add", &[i32_guid, i32_guid], None)
+            .unwrap_err(),
+        SignatureError::ReturnType {
+            expected: "core::i32".to_string(),
+            found: "core::empty".to_string()
+        }
+    );
+}
+
+#[test]
+fn check_marshal_compat() {
+    let mut driver = TestDriver::new(
+        r"
+    struct Foo { a: i32 }
+
+    pub fn foo_with(foo: Foo, a: i32) -> Foo { foo.a = a; foo }
+    ",
+    );
+
+    let runtime = driver.runtime_mut().borrow();
+    assert_eq!(
+        runtime.check_marshal_compat::<StructRef>("foo_with", 0),
+        Ok(())
+    );
+    assert_eq!(runtime.check_marshal_compat::<i32>("foo_with", 1), Ok(()));
+    assert_eq!(runtime.check_marshal_compat::<u32>("foo_with", 1), Ok(()));
+
+    assert_eq!(
+        runtime.check_marshal_compat::<i64>("foo_with", 1),
+        Err(LayoutMismatch::Layout {
+            index: 1,
+            type_name: "core::i32".to_string(),
+            expected_size: 4,
+            found_size: 8,
+            expected_alignment: 4,
+            found_alignment: 8,
+        })
+    );
+    assert_eq!(
+        runtime.check_marshal_compat::<i32>("foo_with", 2),
+        Err(LayoutMismatch::ArgumentNotFound {
+            index: 2,
+            num_args: 2
+        })
+    );
+    assert_eq!(
+        runtime.check_marshal_compat::<i32>("foo_new", 0),
+        Err(LayoutMismatch::FunctionNotFound {
+            function_name: "foo_new".to_string()
+        })
+    );
+}
@@ -3,7 +3,13 @@
 use mun_compiler::{Config, DisplayColor, Driver, FileId, PathOrInline, RelativePathBuf};
 use mun_runtime::{IntoFunctionDefinition, Runtime, RuntimeBuilder};
 use std::io::Cursor;
-use std::{cell::RefCell, path::PathBuf, rc::Rc, thread::sleep, time::Duration};
+use std::{
+    cell::RefCell,
+    path::{Path, PathBuf},
+    rc::Rc,
+    thread::sleep,
+    time::Duration,
+};
 
 /// Implements a compiler and runtime in one that can invoke functions. Use of the TestDriver
 /// enables quick testing of Mun constructs in the runtime with hot-reloading support.
@@ -37,11 +43,28 @@ impl RuntimeOrBuilder {
 impl TestDriver {
     /// Construct a new TestDriver from a single Mun source
     pub fn new(text: &str) -> Self {
+        Self::with_config(text, Config::default())
+    }
+
+    /// Construct a new TestDriver from a single Mun source, which is compiled with debug
+    /// information
+    pub fn with_debug_info(text: &str) -> Self {
+        Self::with_config(
+            text,
+            Config {
+                emit_debug_info: true,
+                ..Config::default()
+            },
+        )
+    }
+
+    /// Construct a new TestDriver from a single Mun source, using the specified compiler `config`
+    fn with_config(text: &str, config: Config) -> Self {
         let temp_dir = tempfile::TempDir::new().unwrap();
         let config = Config {
             out_dir: Some(temp_dir.path().to_path_buf()),
             display_color: DisplayColor::Disable,
-            ..Config::default()
+            ..config
         };
         let input = PathOrInline::Inline {
             rel_path: RelativePathBuf::from("main.mun"),
@@ -118,6 +141,45 @@ impl TestDriver {
         self
     }
 
+    /// Sets whether the garbage collector tracks the number of collection cycles objects survive.
+    pub fn set_gc_age_tracking(mut self, enabled: bool) -> Self {
+        self.runtime = match self.runtime {
+            RuntimeOrBuilder::Builder(builder) => {
+                RuntimeOrBuilder::Builder(builder.set_gc_age_tracking(enabled))
+            }
+            _ => unreachable!(),
+        };
+        self
+    }
+
+    /// Sets whether the resources attached to collected structs are queued until they are dropped
+    /// by `Runtime::run_pending_finalizers`.
+    pub fn set_deferred_resource_drops(mut self, enabled: bool) -> Self {
+        self.runtime = match self.runtime {
+            RuntimeOrBuilder::Builder(builder) => {
+                RuntimeOrBuilder::Builder(builder.set_deferred_resource_drops(enabled))
+            }
+            _ => unreachable!(),
+        };
+        self
+    }
+
+    /// Registers a type of opaque handles to host-owned resources.
+    pub fn register_handle_type(mut self, handle_type: abi::HandleType) -> Self {
+        self.runtime = match self.runtime {
+            RuntimeOrBuilder::Builder(builder) => {
+                RuntimeOrBuilder::Builder(builder.register_handle_type(handle_type))
+            }
+            _ => unreachable!(),
+        };
+        self
+    }
+
+    /// Returns the path of the compiled assembly
+    pub fn out_path(&self) -> &Path {
+        &self.out_path
+    }
+
     /// Returns the `Runtime` used by this instance
     pub fn runtime_mut(&mut self) -> &mut Rc<RefCell<Runtime>> {
         self.runtime.spawn().unwrap();
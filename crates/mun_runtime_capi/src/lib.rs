@@ -12,7 +12,7 @@ pub mod hub;
 mod tests;
 
 use std::ffi::{c_void, CStr, CString};
-use std::{os::raw::c_char, time::Duration};
+use std::{os::raw::c_char, sync::Arc, time::Duration};
 
 use crate::error::ErrorHandle;
 use crate::hub::HUB;
@@ -141,6 +141,9 @@ pub unsafe extern "C" fn mun_runtime_create(
         library_path: library_path.into(),
         delay: Duration::from_millis(delay_ms.into()),
         user_functions,
+        intern_value_structs: false,
+        track_gc_age: false,
+        allocator: Arc::new(runtime::SystemAllocator),
     };
 
     let runtime = match Runtime::new(runtime_options) {
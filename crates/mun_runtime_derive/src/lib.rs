@@ -0,0 +1,72 @@
+//! Derive macros for the Mun Runtime
+//!
+//! The `FromMunStruct` derive macro generates a `TryFrom<&StructRef>` implementation for a Rust
+//! struct with named fields. Each field is read from the Mun struct field with the same name,
+//! using `StructRef::get`.
+#![warn(missing_docs)]
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{ext::IdentExt, parse_macro_input, Data, DeriveInput, Fields};
+
+/// Derives `TryFrom<&mun_runtime::StructRef>` for a struct with named fields.
+///
+/// Conversion fails with the error of `StructRef::get` if the Mun struct does not contain a field
+/// with the same name, or if the type of the field does not match.
+#[proc_macro_derive(FromMunStruct)]
+pub fn derive_from_mun_struct(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return syn::Error::new_spanned(
+                    &input.ident,
+                    "`FromMunStruct` can only be derived for structs with named fields",
+                )
+                .to_compile_error()
+                .into()
+            }
+        },
+        _ => {
+            return syn::Error::new_spanned(
+                &input.ident,
+                "`FromMunStruct` can only be derived for structs",
+            )
+            .to_compile_error()
+            .into()
+        }
+    };
+
+    let field_initializers = fields.iter().map(|field| {
+        let ident = field
+            .ident
+            .as_ref()
+            .expect("named fields must have an identifier");
+        let ty = &field.ty;
+        // Raw identifiers, e.g. `r#type`, refer to the Mun field without the `r#` prefix
+        let field_name = ident.unraw().to_string();
+        quote! {
+            #ident: struct_ref.get::<#ty>(#field_name)?
+        }
+    });
+
+    let expanded = quote! {
+        impl #impl_generics ::std::convert::TryFrom<&::mun_runtime::StructRef> for #name #ty_generics #where_clause {
+            type Error = ::std::string::String;
+
+            fn try_from(struct_ref: &::mun_runtime::StructRef) -> ::std::result::Result<Self, Self::Error> {
+                ::core::result::Result::Ok(Self {
+                    #(#field_initializers,)*
+                })
+            }
+        }
+    };
+
+    expanded.into()
+}
@@ -472,6 +472,7 @@ impl ast::NameOwner for FunctionDef {}
 impl ast::VisibilityOwner for FunctionDef {}
 impl ast::DocCommentsOwner for FunctionDef {}
 impl ast::ExternOwner for FunctionDef {}
+impl ast::InlineOwner for FunctionDef {}
 impl FunctionDef {
     pub fn param_list(&self) -> Option<ParamList> {
         super::child_opt(self)
@@ -1170,7 +1171,11 @@ impl ast::NameOwner for RecordFieldDef {}
 impl ast::VisibilityOwner for RecordFieldDef {}
 impl ast::DocCommentsOwner for RecordFieldDef {}
 impl ast::TypeAscriptionOwner for RecordFieldDef {}
-impl RecordFieldDef {}
+impl RecordFieldDef {
+    pub fn default_value(&self) -> Option<Expr> {
+        super::child_opt(self)
+    }
+}
 
 // RecordFieldDefList
 
@@ -50,6 +50,35 @@ pub trait DocCommentsOwner: AstNode {
             iter: self.syntax().children_with_tokens(),
         }
     }
+
+    /// Returns the text of the doc comments, without their comment prefixes, or `None` if there
+    /// are no doc comments.
+    fn doc_comment_text(&self) -> Option<String> {
+        let lines: Vec<String> = self
+            .doc_comments()
+            .filter(|comment| comment.kind().doc.is_some())
+            .map(|comment| {
+                let kind = comment.kind();
+                let text = &comment.text()[comment.prefix().len()..];
+                let text = if kind.shape.is_block() {
+                    text.trim_end_matches("*/")
+                } else {
+                    text
+                };
+                if text.starts_with(' ') {
+                    text[1..].to_string()
+                } else {
+                    text.to_string()
+                }
+            })
+            .collect();
+
+        if lines.is_empty() {
+            None
+        } else {
+            Some(lines.join("\n"))
+        }
+    }
 }
 
 pub struct CommentIter {
@@ -78,3 +107,11 @@ pub trait ExternOwner: AstNode {
             .any(|p| p.kind() == SyntaxKind::EXTERN)
     }
 }
+
+pub trait InlineOwner: AstNode {
+    fn is_inline(&self) -> bool {
+        self.syntax()
+            .children()
+            .any(|p| p.kind() == SyntaxKind::INLINE)
+    }
+}
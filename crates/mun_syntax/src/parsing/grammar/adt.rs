@@ -87,6 +87,9 @@ fn record_field_def(p: &mut Parser) {
         name(p);
         p.expect(T![:]);
         types::type_(p);
+        if p.eat(T![=]) {
+            expressions::expr(p);
+        }
         m.complete(p, RECORD_FIELD_DEF);
     } else {
         m.abandon(p);
@@ -43,6 +43,10 @@ pub(super) fn maybe_declaration(p: &mut Parser, m: Marker) -> Result<(), Marker>
         abi(p);
     }
 
+    if p.at_contextual_kw("inline") && p.nth(1) == T![fn] {
+        inline(p);
+    }
+
     match p.current() {
         T![fn] => {
             fn_def(p);
@@ -60,6 +64,13 @@ fn abi(p: &mut Parser) {
     abi.complete(p, EXTERN);
 }
 
+fn inline(p: &mut Parser) {
+    assert!(p.at_contextual_kw("inline"));
+    let inline = p.start();
+    p.bump_remap(INLINE_KW);
+    inline.complete(p, INLINE);
+}
+
 fn declarations_without_modifiers(p: &mut Parser, m: Marker) -> Result<(), Marker> {
     match p.current() {
         T![struct] => {
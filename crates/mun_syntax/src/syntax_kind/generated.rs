@@ -97,9 +97,11 @@ pub enum SyntaxKind {
     COMMENT,
     GC_KW,
     VALUE_KW,
+    INLINE_KW,
     SOURCE_FILE,
     FUNCTION_DEF,
     EXTERN,
+    INLINE,
     RET_TYPE,
     VISIBILITY,
     PARAM_LIST,
@@ -408,9 +410,11 @@ impl SyntaxKind {
                 COMMENT => &SyntaxInfo { name: "COMMENT" },
                 GC_KW => &SyntaxInfo { name: "GC_KW" },
                 VALUE_KW => &SyntaxInfo { name: "VALUE_KW" },
+                INLINE_KW => &SyntaxInfo { name: "INLINE_KW" },
                 SOURCE_FILE => &SyntaxInfo { name: "SOURCE_FILE" },
                 FUNCTION_DEF => &SyntaxInfo { name: "FUNCTION_DEF" },
                 EXTERN => &SyntaxInfo { name: "EXTERN" },
+                INLINE => &SyntaxInfo { name: "INLINE" },
                 RET_TYPE => &SyntaxInfo { name: "RET_TYPE" },
                 VISIBILITY => &SyntaxInfo { name: "VISIBILITY" },
                 PARAM_LIST => &SyntaxInfo { name: "PARAM_LIST" },
@@ -321,6 +321,19 @@ fn struct_field_index() {
     )
 }
 
+#[test]
+fn struct_field_default() {
+    snapshot_test(
+        r#"
+    struct Foo {
+        a: f64 = 1.0,
+        b: i32 = -5,
+        c: bool = true,
+    }
+    "#,
+    )
+}
+
 #[test]
 fn memory_type_specifier() {
     snapshot_test(
@@ -355,3 +368,13 @@ fn extern_fn() {
     "#,
     )
 }
+
+#[test]
+fn inline_fn() {
+    snapshot_test(
+        r#"
+    pub inline fn foo() {}
+    fn inline() {}
+    "#,
+    )
+}
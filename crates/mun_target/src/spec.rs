@@ -11,6 +11,33 @@ pub enum LinkerFlavor {
     Msvc,
 }
 
+/// The relocation model of the generated machine code, which determines at which addresses a
+/// compiled Mun library can be loaded.
+///
+/// Functions in a Mun library never call other functions directly, but through the library's
+/// dispatch table: a global array of function pointers that the runtime fills when the library is
+/// loaded, and again whenever it is hot reloaded. The relocation model only affects how the code
+/// addresses this table and the library's type information, so every model supports hot reloading.
+///
+/// Mun libraries are shared libraries, which can only contain position-dependent code on Windows,
+/// where the loader relocates a library that cannot be loaded at its preferred address. On other
+/// targets, code generation fails for any model other than `RelocModel::Pic`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum RelocModel {
+    /// Position-independent code, which can be loaded at any address without modifying its
+    /// machine code. This is required by hardened platforms that enforce position-independent
+    /// executables (PIE).
+    Pic,
+
+    /// Code that is not position-independent itself, but that refers to external symbols
+    /// position-independently.
+    DynamicNoPic,
+
+    /// Code that assumes a fixed load address. Loading it at another address requires text
+    /// relocations, which hardened platforms refuse.
+    Static,
+}
+
 /// Everything Mun knows about a target.
 /// Every field must be specified, there are no default values.
 #[derive(PartialEq, Clone, Debug)]
@@ -67,6 +94,9 @@ pub struct TargetOptions {
 
     /// Whether the target toolchain is like Windows
     pub is_like_windows: bool,
+
+    /// The relocation model of the generated code. Defaults to `RelocModel::Pic`.
+    pub relocation_model: RelocModel,
 }
 
 impl Default for TargetOptions {
@@ -77,6 +107,7 @@ impl Default for TargetOptions {
             features: "".to_string(),
             dll_prefix: "lib".to_string(),
             is_like_windows: false,
+            relocation_model: RelocModel::Pic,
         }
     }
 }
@@ -13,8 +13,9 @@ pub fn target() -> TargetResult {
         target_env: "msvc".to_string(),
         target_vendor: "pc".to_string(),
         arch: "x86_64".to_string(),
-        data_layout: "e-m:w-p270:32:32-p271:32:32-p272:64:64-i64:64-f80:128-n8:16:32:64-S128"
-            .to_string(),
+        data_layout:
+            "e-m:w-p270:32:32-p271:32:32-p272:64:64-i64:64-i128:128-f80:128-n8:16:32:64-S128"
+                .to_string(),
         linker_flavor: LinkerFlavor::Msvc,
         options: base,
     })
@@ -57,6 +57,9 @@ pub fn generate(mode: Mode) -> Result<()> {
         // FIXME: Prevent double derivation of Copy and Debug attributes on Windows
         .derive_copy(false)
         .derive_debug(false)
+        // Only depend on `core`, to support `no_std`
+        .use_core()
+        .ctypes_prefix("cty")
         .raw_line("#![allow(non_snake_case, non_camel_case_types, non_upper_case_globals)]")
         .raw_line("use crate::{StructMemoryKind, TypeGroup};")
         .generate()